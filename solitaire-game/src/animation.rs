@@ -1,11 +1,15 @@
+use std::{f32::consts::PI, time::Duration};
+
 use bevy::{
     prelude::*,
     window::{PrimaryWindow, RequestRedraw},
 };
 
 use crate::{
-    Selected,
-    board::{BoardPosition, PEG_POS, PEG_POS_RAISED, Peg},
+    BoardCamera, MoveEvent, Selected,
+    board::{BoardPosition, PEG_POS, PEG_POS_RAISED, Peg, peg_ghost},
+    input::{Boards, board_at},
+    settings::{AnimationSpeed, Settings},
     viewport_to_world,
 };
 
@@ -15,22 +19,152 @@ pub struct PegAnimation;
 
 impl Plugin for PegAnimation {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, snap_to_board_grid);
+        app.init_resource::<Motion>();
+        app.add_observer(start_jump);
+        app.add_systems(Update, (snap_to_board_grid, jump, shake).chain());
+        app.add_systems(Update, shrink_captured);
         app.add_systems(Update, follow_mouse);
         app.add_systems(Update, follow_touch);
+        app.add_systems(
+            Update,
+            apply_motion_settings.run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+/// how pegs move, following the settings
+#[derive(Resource)]
+pub struct Motion {
+    /// how long a peg takes to jump over another one
+    pub jump: Duration,
+    /// share of the remaining way a peg moves towards its position each frame
+    pub approach: f32,
+    /// fixed timesteps per undone move while resetting, all at once if 0
+    pub reset_ticks: u64,
+    /// no shaking either
+    pub reduced: bool,
+}
+
+impl Motion {
+    pub fn new(speed: AnimationSpeed, reduced: bool) -> Self {
+        if reduced {
+            return Self {
+                jump: Duration::ZERO,
+                approach: 1.,
+                reset_ticks: 0,
+                reduced,
+            };
+        }
+        let (jump, approach, reset_ticks) = match speed {
+            AnimationSpeed::Slow => (500, 0.1, 4),
+            AnimationSpeed::Normal => (300, 0.2, 2),
+            AnimationSpeed::Fast => (150, 0.4, 1),
+        };
+        Self {
+            jump: Duration::from_millis(jump),
+            approach,
+            reset_ticks,
+            reduced,
+        }
+    }
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self::new(AnimationSpeed::default(), false)
+    }
+}
+
+fn apply_motion_settings(settings: Res<Settings>, mut motion: ResMut<Motion>) {
+    *motion = Motion::new(settings.animation_speed, settings.reduced_motion);
+}
+
+/// a peg on its way over the captured one
+#[derive(Component)]
+struct Jump {
+    from: Vec2,
+    timer: Timer,
+}
+
+/// what is left of a captured peg, until it has shrunk away
+#[derive(Component)]
+struct Captured(Timer);
+
+fn start_jump(
+    move_event: On<MoveEvent>,
+    transforms: Query<&Transform>,
+    motion: Res<Motion>,
+    mut commands: Commands,
+) {
+    let Ok(transform) = transforms.get(move_event.moved) else {
+        return;
+    };
+    commands.entity(move_event.moved).insert(Jump {
+        from: transform.translation.xy(),
+        timer: Timer::new(motion.jump, TimerMode::Once),
+    });
+    // the captured peg itself is disabled, to be restored by an undo
+    commands.spawn((
+        peg_ghost(move_event.mov.skip),
+        Captured(Timer::new(motion.jump, TimerMode::Once)),
+        ChildOf(move_event.board),
+    ));
+}
+
+/// an arc from where the peg was to its board position,
+/// rising (and growing) towards the middle of the jump
+fn jump(
+    mut commands: Commands,
+    pegs: Query<(Entity, &mut Jump, &BoardPosition, &mut Transform), Without<Selected>>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (peg, mut jump, board_pos, mut transform) in pegs {
+        let target = board_pos.to_world_space();
+        if jump.timer.tick(time.delta()).is_finished() {
+            transform.translation = (target, PEG_POS).into();
+            transform.scale = Vec3::ONE;
+            commands.entity(peg).remove::<Jump>();
+            continue;
+        }
+        let t = EaseFunction::CubicInOut.sample_clamped(jump.timer.fraction());
+        let height = (t * PI).sin();
+        let z = PEG_POS.lerp(PEG_POS_RAISED, height);
+        transform.translation = (jump.from.lerp(target, t), z).into();
+        transform.scale = Vec3::splat(1. + 0.3 * height);
+        request_redraw.write(RequestRedraw);
+    }
+}
+
+fn shrink_captured(
+    mut commands: Commands,
+    captured: Query<(Entity, &mut Captured, &mut Transform)>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (entity, mut captured, mut transform) in captured {
+        if captured.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = EaseFunction::QuadraticIn.sample_clamped(captured.0.fraction());
+        transform.scale = Vec3::splat(1. - t);
+        request_redraw.write(RequestRedraw);
     }
 }
 
 fn snap_to_board_grid(
     pegs: Query<Entity, (With<Peg>, Without<Selected>)>,
-    mut pos: Query<(&BoardPosition, &mut Transform)>,
+    // jumping pegs find their way on their own
+    mut pos: Query<(&BoardPosition, &mut Transform), Without<Jump>>,
+    motion: Res<Motion>,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
     for peg in pegs {
         if let Ok((board_pos, mut transform)) = pos.get_mut(peg) {
             let current = transform.translation;
             let target = Vec3::from(((*board_pos).to_world_space(), PEG_POS));
-            let mut new_pos = current.lerp(target, 0.2);
+            let mut new_pos = current.lerp(target, motion.approach);
             if new_pos.distance_squared(target) < 0.0001 {
                 new_pos = target;
             }
@@ -42,19 +176,52 @@ fn snap_to_board_grid(
     }
 }
 
+/// shakes a peg sideways, e.g. after an illegal move
+#[derive(Component)]
+pub struct Shake(pub Timer);
+
+impl Default for Shake {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.4, TimerMode::Once))
+    }
+}
+
+fn shake(
+    mut commands: Commands,
+    pegs: Query<(Entity, &mut Shake, &mut Transform)>,
+    time: Res<Time>,
+    motion: Res<Motion>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (peg, mut shake, mut transform) in pegs {
+        if motion.reduced || shake.0.tick(time.delta()).is_finished() {
+            commands.entity(peg).remove::<Shake>();
+            continue;
+        }
+        let t = shake.0.elapsed_secs();
+        transform.translation.x += 0.05 * (t * 50.).sin() * shake.0.fraction_remaining();
+        request_redraw.write(RequestRedraw);
+    }
+}
+
 fn follow_mouse(
     window: Single<&Window, With<PrimaryWindow>>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    transforms: Query<&mut Transform, With<Selected>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    transforms: Query<(&mut Transform, &ChildOf), With<Selected>>,
+    boards: Boards,
+    motion: Res<Motion>,
 ) {
     let (camera, camera_transform) = *camera_query;
     if let Some(cursor_pos) = window.cursor_position() {
-        for mut transform in transforms {
+        for (mut transform, board) in transforms {
             let current_z = transform.translation.z;
             let destination_z = PEG_POS_RAISED;
-            if let Some(mut destination) = viewport_to_world(cursor_pos, camera, camera_transform) {
-                destination.z = current_z.lerp(destination_z, 0.2);
-                transform.translation = destination;
+            if let Some(destination) = viewport_to_world(cursor_pos, camera, camera_transform)
+                && let Ok((_, board)) = boards.get(board.parent())
+            {
+                let local = destination.xy() - board.translation().xy();
+                transform.translation =
+                    (local, current_z.lerp(destination_z, motion.approach)).into();
                 // no need to RequestRedraw, since mouse movement already triggers a wakeup
             }
         }
@@ -62,19 +229,28 @@ fn follow_mouse(
 }
 
 fn follow_touch(
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    mut transforms: Query<&mut Transform, With<Selected>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    mut transforms: Query<(&mut Transform, &ChildOf), With<Selected>>,
+    boards: Boards,
     touches: Res<Touches>,
+    motion: Res<Motion>,
 ) {
     let (camera, camera_transform) = *camera_query;
     for touch in touches.iter() {
-        if let Some(mut destination) = viewport_to_world(touch.position(), camera, camera_transform)
+        // a finger only moves the peg on its own board
+        if let Some(destination) = viewport_to_world(touch.position(), camera, camera_transform)
+            && let Some((touched, _)) = board_at(&boards, destination.xy())
+            && let Ok((_, board)) = boards.get(touched)
         {
-            for mut transform in &mut transforms {
+            let local = destination.xy() - board.translation().xy();
+            for (mut transform, _) in transforms
+                .iter_mut()
+                .filter(|(_, board)| board.parent() == touched)
+            {
                 let current_z = transform.translation.z;
                 let destination_z = PEG_POS_RAISED;
-                destination.z = current_z.lerp(destination_z, 0.2);
-                transform.translation = destination;
+                transform.translation =
+                    (local, current_z.lerp(destination_z, motion.approach)).into();
                 // no need to RequestRedraw, since mouse movement already triggers a wakeup
             }
         }
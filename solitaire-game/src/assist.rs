@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use num_format::{Locale, ToFormattedString};
+use solitaire_solver::{Board, Move};
+
+use crate::{
+    CurrentBoard, MainBoard,
+    challenge::Challenge,
+    game_state::AppState,
+    main_changed,
+    menu::text_font,
+    settings::Settings,
+    solver::{FeasibleConstellations, RandomMoveChances, UniquePaths},
+};
+
+/// every legal move ranked by its chance of winning by moving at random
+/// afterwards and by the number of ways to win after it
+pub struct AssistPlugin;
+
+impl Plugin for AssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_assist_panel);
+        app.add_systems(
+            Update,
+            (
+                show_assist_panel,
+                update_assist_panel.run_if(
+                    main_changed::<CurrentBoard>
+                        .or(resource_changed::<Challenge>)
+                        .or(resource_added::<FeasibleConstellations>)
+                        .or(resource_added::<RandomMoveChances>)
+                        .or(resource_added::<UniquePaths>),
+                ),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct AssistPanel;
+
+/// a move with what is known about the position after it
+struct RankedMove {
+    mov: Move,
+    chance: Option<f64>,
+    paths: Option<u64>,
+}
+
+fn spawn_assist_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        AssistPanel,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.),
+            top: Val::Percent(25.),
+            padding: UiRect::all(Val::Px(10.)),
+            border_radius: BorderRadius::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        children![(
+            Text::default(),
+            text_font(&asset_server, 20.),
+            TextColor::WHITE,
+        )],
+    ));
+}
+
+fn show_assist_panel(
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut panel: Single<&mut Visibility, With<AssistPanel>>,
+) {
+    let in_game = matches!(
+        app_state.get(),
+        AppState::Playing | AppState::Paused | AppState::GameOver
+    );
+    panel.set_if_neq(if settings.assist && in_game {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
+
+/// best first, moves that can not be won anymore have no chance
+fn ranked_moves(
+    board: Board,
+    chances: Option<&RandomMoveChances>,
+    paths: Option<&UniquePaths>,
+) -> Vec<RankedMove> {
+    let mut moves: Vec<RankedMove> = board
+        .get_legal_moves()
+        .into_iter()
+        .map(|mov| {
+            let next = board.mov(mov).normalize();
+            RankedMove {
+                mov,
+                chance: chances.map(|c| c.0.get(&next).copied().unwrap_or(0.)),
+                paths: paths.map(|p| p.0.get(&next).copied().unwrap_or(0)),
+            }
+        })
+        .collect();
+    let chance = |m: &RankedMove| m.chance.unwrap_or(0.);
+    moves.sort_by(|a, b| chance(b).total_cmp(&chance(a)).then(b.paths.cmp(&a.paths)));
+    moves
+}
+
+fn update_assist_panel(
+    panel: Single<&Children, With<AssistPanel>>,
+    mut texts: Query<&mut Text>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    chances: Option<Res<RandomMoveChances>>,
+    paths: Option<Res<UniquePaths>>,
+) {
+    let moves = ranked_moves(board.0, chances.as_deref(), paths.as_deref());
+    // the solver only knows the way to the center
+    let text = if challenge.0.is_some() {
+        "no analysis for challenges".to_string()
+    } else if moves.is_empty() {
+        "no moves left".to_string()
+    } else {
+        let lines = moves.iter().map(|m| {
+            let chance = m
+                .chance
+                .map_or("?".into(), |chance| format!("{:.1}%", 100. * chance));
+            let paths = m
+                .paths
+                .map_or("?".into(), |paths| paths.to_formatted_string(&Locale::en));
+            format!("{}  {chance}  {paths}", m.mov)
+        });
+        std::iter::once("move  chance  ways to win".to_string())
+            .chain(lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    for &child in *panel {
+        if let Ok(mut panel_text) = texts.get_mut(child) {
+            panel_text.0 = text.clone();
+        }
+    }
+}
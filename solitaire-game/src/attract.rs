@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::AccumulatedMouseScroll,
+    prelude::*,
+    window::{CursorMoved, RequestRedraw},
+};
+use rand::{Rng, RngExt, seq::IndexedRandom};
+use solitaire_solver::{Board, Move, Solution};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard, board::NewGame, challenge::Challenge,
+    game_state::AppState, input::RequestPegMove, lifecycle::awake, menu::text_font,
+    replay::Replays, settings::Settings, solver::FeasibleConstellations,
+};
+
+// After a while without input at the main menu, solutions are played on the
+// board by themselves: recorded ones of the player and random ones through
+// the feasible constellations. Any input goes back to the menu.
+//
+// Only an empty start is given up for this, a game in progress is left alone.
+
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastInput>();
+        app.add_systems(Update, track_input.run_if(any_input));
+        app.add_systems(
+            Update,
+            start_attract.run_if(in_state(AppState::Menu).and(idle).and(awake)),
+        );
+        app.add_systems(OnEnter(AppState::Attract), show_hint);
+        app.add_systems(
+            Update,
+            (back_to_menu.run_if(any_input), play_solutions.run_if(awake))
+                .chain()
+                .run_if(in_state(AppState::Attract)),
+        );
+        app.add_systems(OnExit(AppState::Attract), stop_attract);
+    }
+}
+
+/// seconds at the menu until the solutions start
+const IDLE_SECS: u64 = 60;
+
+const MOVE_INTERVAL: f32 = 0.8;
+
+/// the solved board stays a little before the next solution starts
+const SOLVED_SECS: f32 = 3.;
+
+/// time of the last input, in real time
+#[derive(Resource, Default)]
+struct LastInput(Duration);
+
+/// the solution being played
+#[derive(Resource)]
+struct Attract {
+    solution: Solution,
+    timer: Timer,
+}
+
+/// keys, buttons, touches, moving the mouse over the window and scrolling
+#[derive(SystemParam)]
+struct AnyInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    cursor: MessageReader<'w, 's, CursorMoved>,
+    scroll: Res<'w, AccumulatedMouseScroll>,
+}
+
+fn any_input(mut input: AnyInput) -> bool {
+    // read every frame, old movements would count later otherwise
+    let cursor_moved = input.cursor.read().count() > 0;
+    cursor_moved
+        || input.keys.get_just_pressed().next().is_some()
+        || input.mouse.get_just_pressed().next().is_some()
+        || input.touches.any_just_pressed()
+        || input.scroll.delta != Vec2::ZERO
+}
+
+fn track_input(time: Res<Time<Real>>, mut last_input: ResMut<LastInput>) {
+    last_input.0 = time.elapsed();
+}
+
+/// not in low power mode, where nothing should run by itself
+fn idle(time: Res<Time<Real>>, last_input: Res<LastInput>, settings: Res<Settings>) -> bool {
+    !settings.low_power && time.elapsed() - last_input.0 >= Duration::from_secs(IDLE_SECS)
+}
+
+fn start_attract(
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    replays: Res<Replays>,
+    feasible: Option<Res<FeasibleConstellations>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if board.0 != Board::default() || !solution.0.is_empty() {
+        return;
+    }
+    let Some(solution) = pick_solution(&replays, feasible.as_deref(), &mut rand::rng()) else {
+        return;
+    };
+    info!("no input for {IDLE_SECS}s, playing solutions");
+    commands.insert_resource(Challenge::default());
+    commands.insert_resource(Attract {
+        solution,
+        timer: Timer::from_seconds(MOVE_INTERVAL, TimerMode::Repeating),
+    });
+    next_state.set(AppState::Attract);
+}
+
+/// a solution of the player or a random one, if there is any
+fn pick_solution(
+    replays: &Replays,
+    feasible: Option<&FeasibleConstellations>,
+    rng: &mut impl Rng,
+) -> Option<Solution> {
+    let recorded: Vec<&Solution> = replays
+        .0
+        .iter()
+        .filter(|replay| replay.start == Board::default())
+        .map(|replay| &replay.moves)
+        .filter(|moves| {
+            let end = moves
+                .moves()
+                .iter()
+                .fold(Board::default(), |b, &m| b.mov(m));
+            end.is_solved()
+        })
+        .collect();
+    let recorded = recorded.choose(rng).map(|&moves| moves.clone());
+    if recorded.is_some() && (feasible.is_none() || rng.random_bool(0.5)) {
+        return recorded;
+    }
+    feasible
+        .and_then(|feasible| random_solution(feasible, rng))
+        .or(recorded)
+}
+
+/// random feasible moves from the start, which always end solved
+fn random_solution(feasible: &FeasibleConstellations, rng: &mut impl Rng) -> Option<Solution> {
+    let mut board = Board::default();
+    let mut solution = Solution::default();
+    while !board.is_solved() {
+        let moves: Vec<Move> = board
+            .get_legal_moves()
+            .into_iter()
+            .filter(|&mov| feasible.0.contains(&board.mov(mov).normalize()))
+            .collect();
+        let &mov = moves.choose(rng)?;
+        board = board.mov(mov);
+        solution.push(mov);
+    }
+    Some(solution)
+}
+
+fn show_hint(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        DespawnOnExit(AppState::Attract),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            bottom: Val::Px(20.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::new("press any key"),
+            text_font(&asset_server, 30.),
+            TextColor(Color::WHITE.with_alpha(0.6)),
+        )],
+    ));
+}
+
+fn back_to_menu(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Menu);
+}
+
+/// one move per interval, and the next solution after a pause on the solved board
+fn play_solutions(
+    mut commands: Commands,
+    mut attract: ResMut<Attract>,
+    main: Single<(Entity, &CurrentSolution), With<MainBoard>>,
+    replays: Res<Replays>,
+    feasible: Option<Res<FeasibleConstellations>>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let (main, solution) = *main;
+    // keep updating while playing
+    request_redraw.write(RequestRedraw);
+    if !attract.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if let Some(&mov) = attract.solution.moves().get(solution.0.len()) {
+        commands.trigger(RequestPegMove {
+            board: main,
+            src: mov.pos.into(),
+            dst: mov.target.into(),
+        });
+        if solution.0.len() + 1 == attract.solution.len() {
+            attract.timer = Timer::from_seconds(SOLVED_SECS, TimerMode::Repeating);
+        }
+        return;
+    }
+    if let Some(next) = pick_solution(&replays, feasible.as_deref(), &mut rand::rng()) {
+        attract.solution = next;
+    }
+    attract.timer = Timer::from_seconds(MOVE_INTERVAL, TimerMode::Repeating);
+    commands.trigger(NewGame(Board::default()));
+}
+
+/// back to the empty start it began with
+fn stop_attract(mut commands: Commands) {
+    commands.remove_resource::<Attract>();
+    commands.trigger(NewGame(Board::default()));
+}
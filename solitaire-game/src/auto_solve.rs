@@ -0,0 +1,145 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+    window::RequestRedraw,
+};
+use futures_lite::future::{self, block_on};
+use solitaire_solver::{Board, Move, Solution};
+
+use crate::{
+    CurrentBoard, MainBoard, challenge::Challenge, game_state::in_game, input::RequestPegMove,
+};
+
+/// lets the solver play a winning line from the current constellation
+pub struct AutoSolvePlugin;
+
+impl Plugin for AutoSolvePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutoSolveSpeed>();
+        app.add_observer(toggle_auto_solve);
+        app.add_systems(Update, adjust_speed);
+        app.add_systems(
+            Update,
+            auto_solve.run_if(resource_exists::<AutoSolve>.and(in_game)),
+        );
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ToggleAutoSolve;
+
+/// present while the solver is playing
+#[derive(Resource)]
+pub struct AutoSolve {
+    /// the board as left by the solver,
+    /// anything else means the user intervened
+    board: Board,
+    state: AutoSolveState,
+}
+
+enum AutoSolveState {
+    Solving(Task<Option<Solution>>),
+    Playing(VecDeque<Move>, Timer),
+}
+
+/// time between two moves of the solver
+#[derive(Resource)]
+struct AutoSolveSpeed(Duration);
+
+impl Default for AutoSolveSpeed {
+    fn default() -> Self {
+        Self(Duration::from_millis(800))
+    }
+}
+
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_INTERVAL: Duration = Duration::from_millis(3200);
+
+fn toggle_auto_solve(
+    _: On<ToggleAutoSolve>,
+    mut commands: Commands,
+    auto_solve: Option<Res<AutoSolve>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    challenge: Res<Challenge>,
+) {
+    if auto_solve.is_some() {
+        commands.remove_resource::<AutoSolve>();
+        return;
+    }
+    let board = board.0;
+    let target = challenge.0;
+    info!("calculating a solution ...");
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        match target {
+            Some(target) => solitaire_solver::calculate_first_solution_to(board, target),
+            None => solitaire_solver::calculate_first_solution_from(board),
+        }
+    });
+    commands.insert_resource(AutoSolve {
+        board,
+        state: AutoSolveState::Solving(task),
+    });
+}
+
+fn auto_solve(
+    mut commands: Commands,
+    mut auto_solve: ResMut<AutoSolve>,
+    main: Single<(Entity, &CurrentBoard), With<MainBoard>>,
+    speed: Res<AutoSolveSpeed>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let (main, board) = *main;
+    if board.0 != auto_solve.board {
+        info!("auto solve paused");
+        commands.remove_resource::<AutoSolve>();
+        return;
+    }
+    let auto_solve = &mut *auto_solve;
+    match &mut auto_solve.state {
+        AutoSolveState::Solving(task) => match block_on(future::poll_once(task)) {
+            Some(Some(solution)) => {
+                let mut timer = Timer::new(speed.0, TimerMode::Repeating);
+                // the first move is played right away
+                timer.tick(speed.0);
+                auto_solve.state =
+                    AutoSolveState::Playing(solution.moves().iter().copied().collect(), timer);
+            }
+            Some(None) => {
+                info!("there is no solution from here");
+                commands.remove_resource::<AutoSolve>();
+            }
+            None => {}
+        },
+        AutoSolveState::Playing(moves, timer) => {
+            timer.set_duration(speed.0);
+            if timer.tick(time.delta()).just_finished() {
+                match moves.pop_front() {
+                    Some(mov) => {
+                        auto_solve.board = auto_solve.board.mov(mov);
+                        commands.trigger(RequestPegMove {
+                            board: main,
+                            src: mov.pos.into(),
+                            dst: mov.target.into(),
+                        });
+                    }
+                    None => commands.remove_resource::<AutoSolve>(),
+                }
+            }
+        }
+    }
+    // keep updating while waiting for the solver or the next move
+    request_redraw.write(RequestRedraw);
+}
+
+/// + and - to change the speed of the solver
+fn adjust_speed(keys: Res<ButtonInput<KeyCode>>, mut speed: ResMut<AutoSolveSpeed>) {
+    if keys.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        speed.0 = (speed.0 / 2).max(MIN_INTERVAL);
+    }
+    if keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        speed.0 = (speed.0 * 2).min(MAX_INTERVAL);
+    }
+}
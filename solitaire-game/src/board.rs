@@ -1,10 +1,11 @@
-use std::ops::{Add, Mul, Sub};
-
 use bevy::{ecs::entity_disabling::Disabled, prelude::*};
 use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
-use solitaire_solver::{Board, Idx};
+use solitaire_solver::{Board, Coord, Idx, MoveError, Pos};
 
-use crate::{CurrentBoard, MoveEvent, input::RequestPegMove};
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard, MoveEvent, RedoMoves, game_state::AppState,
+    input::RequestPegMove, settings::Settings, theme::Theme,
+};
 
 pub struct BoardPlugin;
 
@@ -13,7 +14,13 @@ impl Plugin for BoardPlugin {
         app.add_systems(Startup, spawn_pegs);
         app.add_observer(on_peg_move_request);
         app.add_observer(on_move_peg);
-        app.add_systems(PostUpdate, draw_pegs);
+        app.add_observer(new_game);
+        app.add_systems(
+            PostUpdate,
+            (apply_theme, draw_pegs.run_if(flat))
+                .chain()
+                .after(TransformSystems::Propagate),
+        );
     }
 }
 
@@ -25,6 +32,7 @@ pub const PEG_POS_RAISED: f32 = 1.0;
 pub const GOLDEN_RATIO: f32 = 1.618_034;
 pub const PEG_RADIUS: f32 = 1. / (2. * GOLDEN_RATIO);
 pub const HOLE_RADIUS: f32 = 0.9 * PEG_RADIUS;
+pub const BOARD_RADIUS: f32 = 3.9;
 
 #[derive(Component)]
 struct BoardMarker;
@@ -32,188 +40,223 @@ struct BoardMarker;
 #[derive(Component)]
 pub struct Peg;
 
-#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
-pub struct BoardPosition {
-    pub x: Idx,
-    pub y: Idx,
-}
-
-impl Sub for BoardPosition {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        let x = self.x - rhs.x;
-        let y = self.y - rhs.y;
-        Self { x, y }
-    }
-}
-
-impl Add for BoardPosition {
-    type Output = Self;
+/// position of a peg, see `Coord` for the conventions
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut)]
+pub struct BoardPosition(pub Coord);
 
-    fn add(self, rhs: Self) -> Self::Output {
-        let x = self.x + rhs.x;
-        let y = self.y + rhs.y;
-        Self { x, y }
-    }
-}
-
-impl Mul<Idx> for BoardPosition {
-    type Output = Self;
+/// replaces the single player game with a new one starting from the given
+/// constellation
+#[derive(Event)]
+pub struct NewGame(pub Board);
 
-    fn mul(self, rhs: Idx) -> Self::Output {
-        let x = self.x * rhs;
-        let y = self.y * rhs;
-        Self { x, y }
-    }
+/// a move rejected by `on_peg_move_request`
+#[derive(Event)]
+pub struct IllegalMove {
+    pub board: Entity,
+    pub src: BoardPosition,
+    pub dst: BoardPosition,
+    pub error: MoveError,
 }
 
-#[derive(Event)]
+#[derive(Event, Clone, Copy)]
 struct MovePeg {
+    board: Entity,
     mov: solitaire_solver::Move,
 }
 
-impl From<BoardPosition> for Vec2 {
-    fn from(board_position: BoardPosition) -> Self {
-        Vec2::new(board_position.x as f32, board_position.y as f32)
-    }
-}
-
-impl From<Vec2> for BoardPosition {
-    fn from(v: Vec2) -> Self {
-        BoardPosition {
-            x: v.x.round() as _,
-            y: v.y.round() as _,
-        }
+impl From<Coord> for BoardPosition {
+    fn from(coord: Coord) -> Self {
+        Self(coord)
     }
 }
 
-impl From<(Idx, Idx)> for BoardPosition {
-    fn from(value: (Idx, Idx)) -> Self {
-        let (y, x) = value;
-        Self { y, x }
+impl From<Pos> for BoardPosition {
+    fn from(pos: Pos) -> Self {
+        Self(pos.into())
     }
 }
 
-impl From<BoardPosition> for (Idx, Idx) {
+impl From<BoardPosition> for Pos {
     fn from(pos: BoardPosition) -> Self {
-        (pos.y, pos.x)
+        pos.0.into()
     }
 }
 
-impl From<&BoardPosition> for (Idx, Idx) {
+impl From<&BoardPosition> for Pos {
     fn from(value: &BoardPosition) -> Self {
         (*value).into()
     }
 }
 
-impl From<&mut BoardPosition> for (Idx, Idx) {
+impl From<&mut BoardPosition> for Pos {
     fn from(value: &mut BoardPosition) -> Self {
         (*value).into()
     }
 }
 
 impl BoardPosition {
+    /// the board is centered at the origin of the world
     pub fn from_world_space(world_pos: Vec2) -> BoardPosition {
-        let pos = world_to_board_transform().transform_point((world_pos, BOARD_POS).into());
-        BoardPosition::from(pos.xy())
+        // anything off the board is as good as far away
+        let world_pos = world_pos.round().clamp(Vec2::splat(-16.), Vec2::splat(16.));
+        Coord::from_centered(world_pos.x as Idx, world_pos.y as Idx).into()
     }
+
     pub fn to_world_space(self) -> Vec2 {
-        board_to_world_transform()
-            .transform_point(Vec3::from((Vec2::from(self), 0.)))
-            .xy()
+        let (x, y) = self.centered();
+        Vec2::new(x as f32, y as f32)
     }
 }
 
-fn board_to_world_transform() -> Transform {
-    Transform::from_scale(Vec3::new(1., -1., 1.)).with_translation(Vec3::new(-3., 3., BOARD_POS))
-    // Transform::from_translation(Vec3::new(-3., 3., 0.)).with_scale(Vec3::new(1., -1., 1.))
+#[derive(Component)]
+pub struct CircleComponent {
+    pub radius: f32,
+    pub color: Color,
 }
 
-fn world_to_board_transform() -> Transform {
-    Transform::from_matrix(board_to_world_transform().to_matrix().inverse())
+/// coloured by the theme, pegs and holes by the type of their position
+#[derive(Component, Clone, Copy)]
+pub enum ThemeColor {
+    Board,
+    Hole(usize),
+    HoleCenter(usize),
+    Peg(usize),
 }
 
-#[derive(Component)]
-struct CircleComponent {
-    radius: f32,
-    color: Color,
+impl ThemeColor {
+    pub fn color(self, theme: &Theme) -> Color {
+        match self {
+            ThemeColor::Board => theme.board,
+            ThemeColor::Hole(t) => theme.pegs[t].with_luminance(theme.hole_luminance),
+            ThemeColor::HoleCenter(t) => theme.pegs[t].with_luminance(theme.hole_center_luminance),
+            ThemeColor::Peg(t) => theme.pegs[t],
+        }
+    }
+
+    /// with the colour of the default theme, until `apply_theme` runs
+    fn circle(self, radius: f32) -> impl Bundle {
+        let color = self.color(&Theme::default());
+        (CircleComponent { radius, color }, self)
+    }
 }
 
-fn spawn_pegs(mut commands: Commands, board: Res<CurrentBoard>) {
-    // the board itself
+fn spawn_pegs(mut commands: Commands, main: Single<(Entity, &CurrentBoard), With<MainBoard>>) {
+    let (main, board) = *main;
+    spawn_board(&mut commands, main, board.0);
+}
+
+/// the disc, holes and pegs of `board`, which is placed by its `Transform`
+pub fn spawn_board(commands: &mut Commands, board: Entity, constellation: Board) {
     commands.spawn((
         BoardMarker,
+        ChildOf(board),
         Transform::from_translation(Vec3::new(0., 0., BOARD_POS)),
-        CircleComponent {
-            radius: 3.9,
-            color: Color::WHITE.with_luminance(0.02),
-        },
+        ThemeColor::Board.circle(BOARD_RADIUS),
     ));
 
-    let board = &board.0;
-    for y in 0..Board::SIZE {
-        for x in 0..Board::SIZE {
-            let board_pos = BoardPosition { y, x };
-            let world_pos = board_pos.to_world_space();
-            let color = color_by_type(x, y);
-            if Board::inbounds((y, x)) {
-                // spawn holes
-                commands.spawn((
-                    CircleComponent {
-                        radius: HOLE_RADIUS * 0.2,
-                        color: color.with_luminance(0.3),
-                    },
-                    Transform::from_translation((world_pos, HOLE_POS + 0.02).into()),
-                ));
-                commands.spawn((
-                    CircleComponent {
-                        radius: HOLE_RADIUS,
-                        color: color.with_luminance(0.05),
-                    },
-                    Transform::from_translation((world_pos, HOLE_POS).into()),
-                ));
-            }
-
-            // spawn pegs
-            if board.occupied((y, x)) {
-                commands.spawn((
-                    CircleComponent {
-                        radius: PEG_RADIUS,
-                        color,
-                    },
-                    BoardPosition { y, x },
-                    Transform::from_translation((world_pos, PEG_POS).into()),
-                    Peg,
-                ));
-            }
-        }
+    for pos in Board::empty().holes() {
+        let world_pos = BoardPosition::from(pos).to_world_space();
+        commands.spawn((
+            ChildOf(board),
+            ThemeColor::HoleCenter(peg_type(pos)).circle(HOLE_RADIUS * 0.2),
+            Transform::from_translation((world_pos, HOLE_POS + 0.02).into()),
+        ));
+        commands.spawn((
+            ChildOf(board),
+            ThemeColor::Hole(peg_type(pos)).circle(HOLE_RADIUS),
+            Transform::from_translation((world_pos, HOLE_POS).into()),
+        ));
+    }
+
+    for pos in constellation.pegs() {
+        commands.spawn((peg(pos), ChildOf(board)));
+    }
+}
+
+fn new_game(
+    new_game: On<NewGame>,
+    pegs: Query<(Entity, &ChildOf), With<Peg>>,
+    main: Single<
+        (
+            Entity,
+            &mut CurrentBoard,
+            &mut CurrentSolution,
+            &mut RedoMoves,
+        ),
+        With<MainBoard>,
+    >,
+    mut commands: Commands,
+) {
+    let (main, mut board, mut solution, mut redo) = main.into_inner();
+    // skipped pegs are disabled and only known to the solution
+    let on_board = pegs
+        .iter()
+        .filter(|(_, board)| board.parent() == main)
+        .map(|(peg, _)| peg);
+    let skipped = solution.1.iter().map(|e| e.skipped);
+    for peg in on_board.chain(skipped) {
+        commands.entity(peg).despawn();
+    }
+    *solution = CurrentSolution::default();
+    redo.0.clear();
+    board.0 = new_game.0;
+    for pos in board.0.pegs() {
+        commands.spawn((peg(pos), ChildOf(main)));
     }
 }
 
+/// looks like the peg at `pos`, without being one
+pub fn peg_ghost(pos: Pos) -> impl Bundle {
+    let world_pos = BoardPosition::from(pos).to_world_space();
+    (
+        ThemeColor::Peg(peg_type(pos)).circle(PEG_RADIUS),
+        Transform::from_translation((world_pos, PEG_POS).into()),
+    )
+}
+
+/// components of a peg at `pos`
+pub fn peg(pos: Pos) -> impl Bundle {
+    let board_pos = BoardPosition::from(pos);
+    (
+        ThemeColor::Peg(peg_type(pos)).circle(PEG_RADIUS),
+        board_pos,
+        Transform::from_translation((board_pos.to_world_space(), PEG_POS).into()),
+        Peg,
+    )
+}
+
 #[allow(unused)]
 fn color_hsl(x: Idx, y: Idx) -> Color {
     Color::hsl(((y * 7 + x) * 16) as f32, 1., 0.9)
 }
 
-fn color_by_type(x: Idx, y: Idx) -> Color {
+/// index of the type mask `pos` belongs to
+pub fn peg_type(pos: Pos) -> usize {
     let masks = Board::type_masks();
-    let col_idx = masks
+    masks
         .iter()
-        .position(|&m| Board::empty().set((y, x)) & m != Board::empty())
-        .unwrap_or(0);
-    let colors = [
-        Srgba::hex("#b7bb26").unwrap().into(),
-        Srgba::hex("#fabe2f").unwrap().into(),
-        Srgba::hex("#fb4934").unwrap().into(),
-        Srgba::hex("#8ec07c").unwrap().into(),
-    ];
-    colors[col_idx]
+        .position(|&m| Board::empty().set(pos) & m != Board::empty())
+        .unwrap_or(0)
+}
+
+fn apply_theme(theme: Res<Theme>, circles: Query<(Ref<ThemeColor>, &mut CircleComponent)>) {
+    for (color, mut circle) in circles {
+        if theme.is_changed() || color.is_added() {
+            circle.color = color.color(&theme);
+        }
+    }
+}
+
+/// the board is drawn flat, unless the 3d scene shows it. races are always flat,
+/// the scene only has room for one board
+pub fn flat(settings: Res<Settings>, state: Res<State<AppState>>) -> bool {
+    !(cfg!(feature = "scene_3d") && settings.scene_3d) || *state.get() == AppState::Race
 }
 
-fn draw_pegs(mut painter: ShapePainter, circles: Query<(&Transform, &CircleComponent)>) {
+/// in world space, pegs are placed relative to their board
+fn draw_pegs(mut painter: ShapePainter, circles: Query<(&GlobalTransform, &CircleComponent)>) {
     for (transform, circle) in circles {
-        painter.transform = *transform;
+        painter.transform = transform.compute_transform();
         painter.set_color(circle.color);
         painter.circle(circle.radius);
     }
@@ -222,38 +265,58 @@ fn draw_pegs(mut painter: ShapePainter, circles: Query<(&Transform, &CircleCompo
 /// request to move peg comming from input system
 fn on_peg_move_request(
     move_request: On<RequestPegMove>,
-    mut board: ResMut<CurrentBoard>,
+    mut boards: Query<&mut CurrentBoard>,
     mut commands: Commands,
 ) {
-    let src = move_request.src;
-    let dst = move_request.dst;
-    if let Some(mov) = board.0.is_legal_move(src.into(), dst.into()) {
-        board.0 = board.0.mov(mov);
-        commands.trigger(MovePeg { mov });
+    let RequestPegMove { board, src, dst } = *move_request;
+    let Ok(mut current) = boards.get_mut(board) else {
+        return;
+    };
+    match current.0.check_move(src.into(), dst.into()) {
+        Ok(mov) => {
+            current.0 = current.0.mov(mov);
+            commands.trigger(MovePeg { board, mov });
+        }
+        // dropping a peg where it was picked up is not an attempted move
+        Err(error) if src != dst => {
+            info!("illegal move: {error}");
+            commands.trigger(IllegalMove {
+                board,
+                src,
+                dst,
+                error,
+            });
+        }
+        Err(_) => {}
     }
 }
 
 fn on_move_peg(
     move_peg: On<MovePeg>,
-    mut pegs: Query<(Entity, &mut BoardPosition), With<Peg>>,
+    mut pegs: Query<(Entity, &mut BoardPosition, &ChildOf), With<Peg>>,
     mut commands: Commands,
 ) {
-    let mov = move_peg.mov;
+    let MovePeg { board, mov } = *move_peg;
     let prev_pos: BoardPosition = mov.pos.into();
     let skipped_pos: BoardPosition = mov.skip.into();
     let new_pos: BoardPosition = mov.target.into();
-    let (skipped, _) = pegs
+    let on_board = |parent: &ChildOf| parent.parent() == board;
+    let (skipped, _, _) = pegs
         .iter()
-        .find(|(_, p)| **p == skipped_pos)
+        .find(|(_, p, parent)| **p == skipped_pos && on_board(parent))
         .expect("skipped");
     // move peg
-    let (moved, mut p) = pegs.iter_mut().find(|(_, p)| **p == prev_pos).expect("peg");
+    let (moved, mut p, _) = pegs
+        .iter_mut()
+        .find(|(_, p, parent)| **p == prev_pos && on_board(parent))
+        .expect("peg");
     *p = new_pos;
     // disable skipped peg
     commands.entity(skipped).insert(Disabled);
 
     // trigger moved event
     commands.trigger(MoveEvent {
+        board,
         mov,
         moved,
         skipped,
@@ -1,3 +1,5 @@
+use std::f32::consts::PI;
+
 use bevy::{
     ecs::entity_disabling::Disabled,
     input::common_conditions::{input_just_pressed, input_just_released},
@@ -7,347 +9,520 @@ use bevy::{
 use bevy_vector_shapes::prelude::*;
 
 use crate::{
-    CurrentBoard, CurrentSolution, PegMoved, WorldSpaceViewPort,
+    CurrentBoard, CurrentSolution, MainBoard, PegMoved, RedoMoves,
+    animation::Motion,
+    auto_solve::{AutoSolve, ToggleAutoSolve},
     board::BoardPosition,
-    hints::ToggleHints,
+    dead_position::RewindToWinnable,
+    game_state::in_game,
+    hints::{ShowBestMove, ToggleHints},
+    input::RequestPegMove,
+    main_changed, screen_reader,
+    settings::{HintLevel, Settings, ToggleSettings},
+    statistics::ToggleStatistics,
     stats::{ToggleBookMarks, ToggleStats},
+    theme::Theme,
     total_progress::TotalProgress,
+    viewport::{ViewPortAnchor, ViewPortRelativeTranslation},
     viewport_to_world,
 };
 
-pub struct Buttons;
+/// the round buttons around the board, laid out after `BUTTONS`
+pub struct GameButtons;
 
-impl Plugin for Buttons {
+impl Plugin for GameButtons {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, add_buttons);
+        app.init_resource::<FocusedButton>();
         app.add_systems(
             Update,
             (
-                handle_button_press::<Undo, UndoEvent>
-                    .run_if(input_just_pressed(MouseButton::Left)),
-                handle_button_press::<Reset, ResetEvent>
-                    .run_if(input_just_pressed(MouseButton::Left)),
-                handle_button_release::<Undo>.run_if(input_just_released(MouseButton::Left)),
-                handle_button_release::<Reset>.run_if(input_just_released(MouseButton::Left)),
-                handle_toggle_press::<Hints, ToggleHints>
-                    .run_if(input_just_pressed(MouseButton::Left)),
-                handle_toggle_press::<Stats, ToggleStats>
-                    .run_if(input_just_pressed(MouseButton::Left)),
-                handle_toggle_press::<BookMark, ToggleBookMarks>
-                    .run_if(input_just_pressed(MouseButton::Left)),
-                handle_touch_press::<Undo, UndoEvent>,
-                handle_touch_press::<Reset, ResetEvent>,
-                handle_touch_release::<Undo>,
-                handle_touch_release::<Reset>,
-                handle_touch_toggle::<Hints, ToggleHints>,
-                handle_touch_toggle::<Stats, ToggleStats>,
-                handle_touch_toggle::<BookMark, ToggleBookMarks>,
-            ),
+                click_buttons.run_if(input_just_pressed(MouseButton::Left)),
+                touch_buttons,
+                release_touches,
+                focus_next.run_if(input_just_pressed(KeyCode::Tab)),
+                press_focused.run_if(input_just_pressed(KeyCode::Space)),
+                release_buttons.run_if(
+                    input_just_released(MouseButton::Left).or(input_just_released(KeyCode::Space)),
+                ),
+            )
+                .run_if(in_game),
         );
-        app.add_systems(Update, (draw_buttons, update_button_pos));
-        app.add_systems(Update, (draw_toggles, update_button_pos));
-        app.add_systems(FixedUpdate, reset);
-        app.add_observer(do_undo);
-        app.add_observer(do_reset);
+        app.add_systems(PostUpdate, (sync_solve_toggle, sync_hints_toggle));
+        app.add_systems(Update, hide_in_zen.run_if(resource_changed::<Settings>));
         app.add_systems(
             Update,
-            draw_bookmark.run_if(resource_changed::<CurrentBoard>),
+            (hover_buttons, update_pressed, animate_press, draw_buttons).chain(),
         );
+        app.add_systems(Update, shortcuts.run_if(in_game));
+        app.add_systems(FixedUpdate, reset);
+        app.add_observer(do_undo);
+        app.add_observer(do_redo);
+        app.add_observer(do_reset);
+        app.add_observer(do_rewind);
+        app.add_systems(Update, draw_bookmark.run_if(main_changed::<CurrentBoard>));
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(unused)]
-enum Pos {
-    TopLeft,
-    Top,
-    TopRight,
-    BottomLeft,
-    BottomRight,
-}
-
-#[derive(Component)]
-struct ViewPortRelativeTranslation(Pos, Vec3);
+#[derive(Event, Default)]
+pub struct UndoEvent;
 
 #[derive(Event, Default)]
-struct UndoEvent;
+pub struct RedoEvent;
 
 #[derive(Event, Default)]
-struct ResetEvent;
+pub struct ResetEvent;
+
+/// undoes moves one after the other like a reset, until this many are left
+#[derive(Event)]
+pub struct RewindTo(pub usize);
 
 #[derive(Component)]
+#[require(Highlight)]
 struct CircleButton {
-    fg_color: Color,
-    bg_color: Color,
+    /// at 100% ui scale
     radius: f32,
+    action: Action,
 }
 
-#[derive(Component)]
+impl CircleButton {
+    /// as large as the icon, which follows the ui scale and the press
+    fn scaled_radius(&self, transform: &Transform) -> f32 {
+        self.radius * transform.scale.x / ICON_SCALE
+    }
+
+    fn contains(&self, transform: &Transform, world_pos: Vec3) -> bool {
+        world_pos.xy().distance(transform.translation.xy()) < self.scaled_radius(transform)
+    }
+}
+
+/// hover and press feedback of a `CircleButton`
+#[derive(Component, Default)]
+struct Highlight {
+    hovered: bool,
+    /// pressed buttons and active toggles are drawn inverted
+    pressed: bool,
+    /// while the button bounces back from being pressed
+    press: Option<Timer>,
+}
+
+impl Highlight {
+    /// of the circle and its icon
+    fn scale(&self) -> f32 {
+        let t = self.press.as_ref().map_or(1., Timer::fraction);
+        1. - 0.15 * (t * PI).sin()
+    }
+}
+
+/// the button selected with the keyboard, pressed with space
+#[derive(Default, Resource)]
+struct FocusedButton(Option<Entity>);
+
+/// size of the icons in world space
+const ICON_SCALE: f32 = 0.003;
+
+const PRESS_SECS: f32 = 0.2;
+
+/// of a push button
+#[derive(Component, Default)]
 struct ButtonState {
     clicked: bool,
     touched: Option<u64>,
 }
 
+/// of a toggle
 #[derive(Component)]
 struct ToggleState(bool);
 
-#[derive(Component)]
-struct Undo;
-
-#[derive(Component)]
-struct Reset;
+/// the event a button triggers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Reset,
+    Undo,
+    Redo,
+    Settings,
+    Hints,
+    BestMove,
+    Stats,
+    BookMarks,
+    AutoSolve,
+    Rewind,
+}
 
-#[derive(Component)]
-struct Hints;
+impl Action {
+    fn trigger(self, commands: &mut Commands) {
+        match self {
+            Action::Reset => commands.trigger(ResetEvent),
+            Action::Undo => commands.trigger(UndoEvent),
+            Action::Redo => commands.trigger(RedoEvent),
+            Action::Settings => commands.trigger(ToggleSettings),
+            Action::Hints => commands.trigger(ToggleHints),
+            Action::BestMove => commands.trigger(ShowBestMove),
+            Action::Stats => commands.trigger(ToggleStats),
+            Action::BookMarks => commands.trigger(ToggleBookMarks),
+            Action::AutoSolve => commands.trigger(ToggleAutoSolve),
+            Action::Rewind => commands.trigger(RewindToWinnable),
+        }
+    }
 
-#[derive(Component)]
-struct Stats;
+    /// hints and statistics, hidden in zen mode
+    fn distracting(self) -> bool {
+        matches!(
+            self,
+            Action::Hints
+                | Action::BestMove
+                | Action::Stats
+                | Action::BookMarks
+                | Action::AutoSolve
+                | Action::Rewind
+        )
+    }
+}
 
-#[derive(Component)]
-struct BookMark;
+#[derive(Clone, Copy)]
+enum ButtonKind {
+    /// drawn pressed while held down
+    Push,
+    /// drawn pressed while on, starting out as given
+    Toggle(bool),
+}
 
-fn update_button_pos(
-    buttons: Query<(&ViewPortRelativeTranslation, &mut Transform), With<CircleButton>>,
-    world_space_view_port: Option<Res<WorldSpaceViewPort>>,
-) {
-    if let Some(vp) = world_space_view_port {
-        for (rt, mut transform) in buttons {
-            let (pos, rt) = (rt.0, rt.1);
-            match pos {
-                Pos::Top => transform.translation = (vp.top_left + vp.top_right) / 2.0 + rt,
-                Pos::TopLeft => transform.translation = vp.top_left + rt,
-                Pos::TopRight => transform.translation = vp.top_right + rt,
-                Pos::BottomLeft => transform.translation = vp.bottom_left + rt,
-                Pos::BottomRight => transform.translation = vp.bottom_right + rt,
-            }
-        }
-    }
+struct ButtonSpec {
+    action: Action,
+    kind: ButtonKind,
+    /// a glyph of font awesome
+    icon: &'static str,
+    anchor: ViewPortAnchor,
+    offset: Vec3,
+    /// at 100% ui scale
+    radius: f32,
+    /// read out by screen readers
+    label: &'static str,
 }
 
+const BUTTONS: [ButtonSpec; 10] = [
+    ButtonSpec {
+        action: Action::Reset,
+        kind: ButtonKind::Push,
+        icon: "\u{f2ea}",
+        anchor: ViewPortAnchor::TopLeft,
+        offset: Vec3::new(1.2, -1.0, 0.0),
+        radius: 0.4,
+        label: "reset",
+    },
+    ButtonSpec {
+        action: Action::Undo,
+        kind: ButtonKind::Push,
+        icon: "\u{f060}",
+        anchor: ViewPortAnchor::TopLeft,
+        offset: Vec3::new(1.2, -2.0, 0.0),
+        radius: 0.3,
+        label: "undo",
+    },
+    ButtonSpec {
+        action: Action::Redo,
+        kind: ButtonKind::Push,
+        icon: "\u{f061}",
+        anchor: ViewPortAnchor::TopLeft,
+        offset: Vec3::new(1.9, -2.0, 0.0),
+        radius: 0.3,
+        label: "redo",
+    },
+    ButtonSpec {
+        action: Action::Rewind,
+        kind: ButtonKind::Push,
+        icon: "\u{f1da}",
+        anchor: ViewPortAnchor::TopLeft,
+        offset: Vec3::new(1.2, -2.8, 0.0),
+        radius: 0.3,
+        label: "rewind to last winnable position",
+    },
+    ButtonSpec {
+        action: Action::Settings,
+        kind: ButtonKind::Push,
+        icon: "\u{f013}",
+        anchor: ViewPortAnchor::TopLeft,
+        offset: Vec3::new(2.1, -1.0, 0.0),
+        radius: 0.3,
+        label: "settings",
+    },
+    ButtonSpec {
+        action: Action::Hints,
+        kind: ButtonKind::Toggle(false),
+        icon: "\u{f0eb}",
+        anchor: ViewPortAnchor::TopRight,
+        offset: Vec3::new(-1., -1.0, 0.0),
+        radius: 0.4,
+        label: "hints",
+    },
+    ButtonSpec {
+        action: Action::BestMove,
+        kind: ButtonKind::Push,
+        icon: "\u{f890}",
+        anchor: ViewPortAnchor::TopRight,
+        offset: Vec3::new(-1., -2.0, 0.0),
+        radius: 0.3,
+        label: "best move",
+    },
+    ButtonSpec {
+        action: Action::Stats,
+        kind: ButtonKind::Toggle(true),
+        icon: "\u{f5dc}",
+        anchor: ViewPortAnchor::TopRight,
+        offset: Vec3::new(-2., -1.0, 1.0),
+        radius: 0.4,
+        label: "stats",
+    },
+    ButtonSpec {
+        action: Action::BookMarks,
+        kind: ButtonKind::Toggle(false),
+        icon: "\u{f02d}",
+        anchor: ViewPortAnchor::Top,
+        offset: Vec3::new(0.0, -1.0, 0.0),
+        radius: 0.4,
+        label: "bookmarks",
+    },
+    ButtonSpec {
+        action: Action::AutoSolve,
+        kind: ButtonKind::Toggle(false),
+        icon: "\u{f544}",
+        anchor: ViewPortAnchor::TopRight,
+        offset: Vec3::new(-3., -1.0, 0.0),
+        radius: 0.4,
+        label: "auto solve",
+    },
+];
+
 fn add_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font_awesome = asset_server.load("fonts/Font Awesome 7 Free-Solid-900.otf");
     let font_awesome = TextFont {
-        font: font_awesome.clone(),
+        font: asset_server.load("fonts/Font Awesome 7 Free-Solid-900.otf"),
         font_size: 100.0,
         ..default()
     };
-    // reset button
-    commands.spawn((
-        ViewPortRelativeTranslation(Pos::TopLeft, Vec3::new(1.2, -1.0, 0.0)),
-        Transform::from_scale(Vec3::new(0.003, 0.003, 0.003)),
-        CircleButton {
-            fg_color: Color::WHITE,
-            bg_color: Color::BLACK,
-            radius: 0.4,
-        },
-        ButtonState {
-            clicked: false,
-            touched: None,
-        },
-        Text2d::new("\u{f2ea}".to_string()),
-        TextColor(Color::BLACK),
-        font_awesome.clone(),
-        Reset,
-    ));
-    // undo button
-    commands.spawn((
-        ViewPortRelativeTranslation(Pos::TopLeft, Vec3::new(1.2, -2.0, 0.0)),
-        Transform::from_scale(Vec3::new(0.003, 0.003, 0.003)),
-        CircleButton {
-            fg_color: Color::WHITE,
-            bg_color: Color::BLACK,
-            radius: 0.3,
-        },
-        ButtonState {
-            clicked: false,
-            touched: None,
-        },
-        Text2d::new("\u{f060}".to_string()),
-        TextColor(Color::BLACK),
-        font_awesome.clone(),
-        Undo,
-    ));
-    // hints button
-    commands.spawn((
-        ViewPortRelativeTranslation(Pos::TopRight, Vec3::new(-1., -1.0, 0.0)),
-        Transform::from_scale(Vec3::new(0.003, 0.003, 0.003)),
-        CircleButton {
-            fg_color: Color::WHITE,
-            bg_color: Color::BLACK,
-            radius: 0.4,
-        },
-        ToggleState(false),
-        Text2d::new("\u{f0eb}".to_string()),
-        TextColor(Color::BLACK),
-        font_awesome.clone(),
-        Hints,
-    ));
-    commands.spawn((
-        ViewPortRelativeTranslation(Pos::TopRight, Vec3::new(-2., -1.0, 1.0)),
-        Transform::from_scale(Vec3::new(0.003, 0.003, 0.003)),
-        CircleButton {
-            fg_color: Color::WHITE,
-            bg_color: Color::BLACK,
-            radius: 0.4,
-        },
-        ToggleState(true),
-        Text2d::new("\u{f5dc}".to_string()),
-        TextColor(Color::WHITE),
-        font_awesome.clone(),
-        Stats,
-    ));
-    // book toggle
-    commands.spawn((
-        ViewPortRelativeTranslation(Pos::Top, Vec3::new(0.0, -1.0, 0.0)),
-        Transform::from_scale(Vec3::new(0.003, 0.003, 0.003)),
-        CircleButton {
-            fg_color: Color::WHITE,
-            bg_color: Color::BLACK,
-            radius: 0.4,
-        },
-        ToggleState(false),
-        Text2d::new("\u{f02d}".to_string()),
-        TextColor(Color::WHITE),
-        font_awesome.clone(),
-        BookMark,
-    ));
-}
-
-fn handle_button_press<'a, T, U: Default + Event>(
-    window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&Camera, &GlobalTransform)>,
-    mut button: Query<(&CircleButton, &mut ButtonState, &Transform), With<T>>,
-    mut commands: Commands,
-) where
-    T: Component + Send + Sync,
-    <U as bevy::prelude::Event>::Trigger<'a>: std::default::Default,
-{
-    if let Some(cursor_pos) = window.cursor_position() {
-        let (camera, transform) = *camera;
-        let Some(world_pos) = viewport_to_world(cursor_pos, camera, transform) else {
-            return;
+    for spec in BUTTONS {
+        let mut button = commands.spawn((
+            ViewPortRelativeTranslation(spec.anchor, spec.offset),
+            Transform::from_scale(Vec3::splat(ICON_SCALE)),
+            CircleButton {
+                radius: spec.radius,
+                action: spec.action,
+            },
+            Text2d::new(spec.icon),
+            // coloured by the theme (see `draw_buttons`)
+            TextColor(Color::BLACK),
+            font_awesome.clone(),
+            screen_reader::button(spec.label),
+        ));
+        match spec.kind {
+            ButtonKind::Push => button.insert(ButtonState::default()),
+            ButtonKind::Toggle(on) => button.insert(ToggleState(on)),
         };
-        for (button, mut state, transform) in &mut button {
-            if world_pos.xy().distance(transform.translation.xy()) < button.radius {
-                commands.trigger(U::default());
-                state.clicked = true;
-            }
+    }
+}
+
+fn sync_hints_toggle(settings: Res<Settings>, buttons: Query<(&CircleButton, &mut ToggleState)>) {
+    let on = settings.hints != HintLevel::Off;
+    for (button, mut state) in buttons {
+        if button.action == Action::Hints && state.0 != on {
+            state.0 = on;
         }
     }
 }
 
-fn handle_button_release<T>(mut button: Query<&mut ButtonState, With<T>>)
-where
-    T: Component + Send + Sync,
-{
-    for mut state in &mut button {
-        state.clicked = false;
+/// disabled buttons are neither drawn nor pressed
+fn hide_in_zen(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    buttons: Query<(Entity, &CircleButton, Has<Disabled>), Allow<Disabled>>,
+) {
+    for (entity, button, disabled) in buttons {
+        let hide = settings.zen && button.action.distracting();
+        if hide && !disabled {
+            commands.entity(entity).insert(Disabled);
+        } else if !hide && disabled {
+            commands.entity(entity).remove::<Disabled>();
+        }
     }
 }
 
-fn handle_toggle_press<'a, T, U: Default + Event>(
+/// the solver stops on its own, when it is done or the user intervenes
+fn sync_solve_toggle(
+    auto_solve: Option<Res<AutoSolve>>,
+    buttons: Query<(&CircleButton, &mut ToggleState)>,
+) {
+    for (button, mut state) in buttons {
+        if button.action == Action::AutoSolve && state.0 != auto_solve.is_some() {
+            state.0 = auto_solve.is_some();
+        }
+    }
+}
+
+/// a push button is held down until `touch` (or the mouse or space) is
+/// released, a toggle flips
+fn press(
+    commands: &mut Commands,
+    action: Action,
+    state: Option<Mut<ButtonState>>,
+    toggle: Option<Mut<ToggleState>>,
+    touch: Option<u64>,
+) {
+    if let Some(mut state) = state {
+        match touch {
+            Some(id) => state.touched = Some(id),
+            None => state.clicked = true,
+        }
+    }
+    if let Some(mut toggle) = toggle {
+        toggle.0 = !toggle.0;
+    }
+    action.trigger(commands);
+}
+
+type Pressable<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static CircleButton,
+        &'static Transform,
+        Option<&'static mut ButtonState>,
+        Option<&'static mut ToggleState>,
+    ),
+>;
+
+fn click_buttons(
     window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&Camera, &GlobalTransform)>,
-    mut button: Query<(&CircleButton, &mut ToggleState, &Transform), With<T>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    buttons: Pressable,
     mut commands: Commands,
-) where
-    T: Component + Send + Sync,
-    <U as bevy::prelude::Event>::Trigger<'a>: std::default::Default,
-{
-    if let Some(cursor_pos) = window.cursor_position() {
-        let (camera, transform) = *camera;
-        let Some(world_pos) = viewport_to_world(cursor_pos, camera, transform) else {
-            return;
-        };
-        for (button, mut state, transform) in &mut button {
-            if world_pos.xy().distance(transform.translation.xy()) < button.radius {
-                state.0 = !state.0;
-                commands.trigger(U::default());
-            }
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|cursor_pos| viewport_to_world(cursor_pos, camera, camera_transform))
+    else {
+        return;
+    };
+    for (button, transform, state, toggle) in buttons {
+        if button.contains(transform, world_pos) {
+            press(&mut commands, button.action, state, toggle, None);
         }
     }
 }
 
-fn handle_touch_press<'a, T, U: Default + Event>(
-    camera: Single<(&Camera, &GlobalTransform)>,
-    mut buttons: Query<(&CircleButton, &mut ButtonState, &Transform), With<T>>,
+fn touch_buttons(
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut buttons: Pressable,
     mut commands: Commands,
     touches: Res<Touches>,
-) where
-    T: Component + Send + Sync,
-    <U as bevy::prelude::Event>::Trigger<'a>: std::default::Default,
-{
+) {
+    let (camera, camera_transform) = *camera;
     for touch in touches.iter_just_pressed() {
-        let (camera, transform) = *camera;
-        let Some(world_pos) = viewport_to_world(touch.position(), camera, transform) else {
+        let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform) else {
             return;
         };
-        for (button, mut state, transform) in &mut buttons {
-            if world_pos.xy().distance(transform.translation.xy()) < button.radius {
-                commands.trigger(U::default());
-                state.touched = Some(touch.id());
+        for (button, transform, state, toggle) in &mut buttons {
+            if button.contains(transform, world_pos) {
+                press(
+                    &mut commands,
+                    button.action,
+                    state,
+                    toggle,
+                    Some(touch.id()),
+                );
             }
         }
     }
 }
 
-fn handle_touch_release<'a, T>(mut buttons: Query<&mut ButtonState, With<T>>, touches: Res<Touches>)
-where
-    T: Component + Send + Sync,
-{
-    for released_id in touches.iter_just_released().map(|t| t.id()) {
-        for mut state in &mut buttons {
-            if let Some(id) = state.touched {
-                if id == released_id {
-                    state.touched = None;
-                }
-            }
+fn release_buttons(buttons: Query<&mut ButtonState>) {
+    for mut state in buttons {
+        state.clicked = false;
+    }
+}
+
+fn release_touches(buttons: Query<&mut ButtonState>, touches: Res<Touches>) {
+    for mut state in buttons {
+        if state.touched.is_some_and(|id| touches.just_released(id)) {
+            state.touched = None;
         }
     }
 }
 
-fn handle_touch_toggle<'a, T, U: Default + Event>(
-    camera: Single<(&Camera, &GlobalTransform)>,
-    mut button: Query<(&CircleButton, &mut ToggleState, &Transform), With<T>>,
-    mut commands: Commands,
-    touches: Res<Touches>,
-) where
-    T: Component + Send + Sync,
-    <U as bevy::prelude::Event>::Trigger<'a>: std::default::Default,
-{
-    for pos in touches.iter_just_pressed().map(|t| t.position()) {
-        let (camera, transform) = *camera;
-        let Some(world_pos) = viewport_to_world(pos, camera, transform) else {
-            return;
-        };
-        for (button, mut state, transform) in &mut button {
-            if world_pos.xy().distance(transform.translation.xy()) < button.radius {
-                commands.trigger(U::default());
-                state.0 = !state.0;
-            }
+/// ctrl+z or u to undo, ctrl+y or ctrl+shift+z to redo,
+/// r to reset, l to rewind to the last winnable position,
+/// h to cycle through the hint levels, b to show the best move
+/// and i for the statistics
+fn shortcuts(keys: Res<ButtonInput<KeyCode>>, mut commands: Commands) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if ctrl {
+        if keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)) {
+            commands.trigger(RedoEvent);
+        } else if keys.just_pressed(KeyCode::KeyZ) {
+            commands.trigger(UndoEvent);
         }
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyU) {
+        commands.trigger(UndoEvent);
+    }
+    if keys.just_pressed(KeyCode::KeyR) {
+        commands.trigger(ResetEvent);
+    }
+    if keys.just_pressed(KeyCode::KeyL) {
+        commands.trigger(RewindToWinnable);
+    }
+    if keys.just_pressed(KeyCode::KeyH) {
+        commands.trigger(ToggleHints);
+    }
+    if keys.just_pressed(KeyCode::KeyB) {
+        commands.trigger(ShowBestMove);
+    }
+    if keys.just_pressed(KeyCode::KeyI) {
+        commands.trigger(ToggleStatistics);
     }
 }
 
 fn do_undo(
     _: On<UndoEvent>,
-    mut solution: ResMut<CurrentSolution>,
-    mut board: ResMut<CurrentBoard>,
+    mut solution: Single<&mut CurrentSolution, With<MainBoard>>,
+    mut redo: Single<&mut RedoMoves, With<MainBoard>>,
+    mut board: Single<&mut CurrentBoard, With<MainBoard>>,
     mut commands: Commands,
 ) {
     info!("undo triggered!");
     if !solution.0.is_empty() {
-        reverse_last_move(&mut solution, &mut board, &mut commands);
+        reverse_last_move(&mut solution, &mut redo, &mut board, &mut commands);
     }
 }
 
-fn reverse_last_move(
+fn do_redo(
+    _: On<RedoEvent>,
+    main: Single<(Entity, &RedoMoves), With<MainBoard>>,
+    mut commands: Commands,
+) {
+    info!("redo triggered!");
+    let (main, redo) = *main;
+    // taken from the redo stack once the move is made (see `update_solution`)
+    if let Some(&mov) = redo.0.last() {
+        commands.trigger(RequestPegMove {
+            board: main,
+            src: mov.pos.into(),
+            dst: mov.target.into(),
+        });
+    }
+}
+
+/// takes back the last move on `board`, its pegs move back with their animation
+pub fn reverse_last_move(
     solution: &mut CurrentSolution,
+    redo: &mut RedoMoves,
     board: &mut CurrentBoard,
     commands: &mut Commands,
 ) {
     let mov = solution.0.pop();
     let pegs = solution.1.pop().unwrap();
+    redo.0.push(mov);
     board.0 = board.0.reverse_mov(mov);
     let prev_pos = BoardPosition::from(mov.pos);
     let skip_pos = BoardPosition::from(mov.skip);
@@ -363,30 +538,48 @@ fn reverse_last_move(
 #[derive(Component)]
 struct ResetComponent {
     elapsed: u64,
+    /// moves left when done, none for a reset
+    keep: usize,
 }
 
-fn do_reset(_: On<ResetEvent>, mut commands: Commands, reset_component: Query<&ResetComponent>) {
+fn do_reset(_: On<ResetEvent>, mut commands: Commands) {
     info!("reset triggered!");
+    commands.trigger(RewindTo(0));
+}
+
+fn do_rewind(
+    rewind: On<RewindTo>,
+    mut commands: Commands,
+    reset_component: Query<&ResetComponent>,
+) {
     if reset_component.is_empty() {
-        commands.spawn(ResetComponent { elapsed: 0 });
+        commands.spawn(ResetComponent {
+            elapsed: 0,
+            keep: rewind.0,
+        });
     }
 }
 
 fn reset(
-    reset_entity: Single<Entity, With<ResetComponent>>,
-    mut reset: Query<&mut ResetComponent>,
-    mut solution: ResMut<CurrentSolution>,
+    reset: Single<(Entity, &mut ResetComponent)>,
+    mut solution: Single<&mut CurrentSolution, With<MainBoard>>,
+    mut redo: Single<&mut RedoMoves, With<MainBoard>>,
     mut commands: Commands,
     mut request_redraw: MessageWriter<RequestRedraw>,
-    mut board: ResMut<CurrentBoard>,
+    mut board: Single<&mut CurrentBoard, With<MainBoard>>,
+    motion: Res<Motion>,
 ) {
-    let entity = *reset_entity;
-    let mut reset = reset.get_mut(entity).unwrap();
+    let (entity, mut reset) = reset.into_inner();
     let ticks = reset.elapsed;
     reset.elapsed += 1;
-    if ticks.is_multiple_of(2) {
-        if !solution.0.is_empty() {
-            reverse_last_move(&mut solution, &mut board, &mut commands);
+    if motion.reset_ticks == 0 {
+        while solution.0.len() > reset.keep {
+            reverse_last_move(&mut solution, &mut redo, &mut board, &mut commands);
+        }
+        commands.entity(entity).despawn();
+    } else if ticks.is_multiple_of(motion.reset_ticks) {
+        if solution.0.len() > reset.keep {
+            reverse_last_move(&mut solution, &mut redo, &mut board, &mut commands);
         } else {
             commands.entity(entity).despawn();
         }
@@ -394,60 +587,164 @@ fn reset(
     request_redraw.write(RequestRedraw);
 }
 
-fn draw_buttons(
-    mut painter: ShapePainter,
-    mut buttons: Query<(&CircleButton, &ButtonState, &Transform, &mut TextColor)>,
+/// highlights the button under the cursor, moving the mouse ends keyboard focus
+fn hover_buttons(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut cursor_moved: MessageReader<CursorMoved>,
+    buttons: Query<(&CircleButton, &Transform, &mut Highlight)>,
+    mut focused: ResMut<FocusedButton>,
 ) {
-    for (button, state, transform, mut col) in &mut buttons {
-        painter.set_translation(transform.translation - 0.1 * Vec3::Z);
-        if state.clicked || state.touched.is_some() {
-            *col = TextColor(button.bg_color);
-            painter.set_color(button.fg_color);
-        } else {
-            *col = TextColor(button.fg_color);
-            painter.set_color(button.bg_color);
+    if cursor_moved.read().count() == 0 {
+        return;
+    }
+    focused.0 = None;
+    let (camera, camera_transform) = *camera;
+    let world_pos = window
+        .cursor_position()
+        .and_then(|cursor_pos| viewport_to_world(cursor_pos, camera, camera_transform));
+    for (button, transform, mut highlight) in buttons {
+        let hovered = world_pos.is_some_and(|world_pos| {
+            world_pos.xy().distance(transform.translation.xy()) < button.scaled_radius(transform)
+        });
+        if highlight.hovered != hovered {
+            highlight.hovered = hovered;
+        }
+    }
+}
+
+/// tab moves the focus through the buttons in reading order, shift+tab back
+fn focus_next(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<(Entity, &Transform), With<CircleButton>>,
+    mut focused: ResMut<FocusedButton>,
+) {
+    let mut buttons: Vec<_> = buttons.iter().collect();
+    buttons.sort_by(|(_, a), (_, b)| {
+        let (a, b) = (a.translation, b.translation);
+        b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x))
+    });
+    let buttons: Vec<Entity> = buttons.into_iter().map(|(e, _)| e).collect();
+    if buttons.is_empty() {
+        return;
+    }
+    let current = focused.0.and_then(|f| buttons.iter().position(|&e| e == f));
+    let back = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let next = match (current, back) {
+        (None, false) => 0,
+        (None, true) => buttons.len() - 1,
+        (Some(i), false) => (i + 1) % buttons.len(),
+        (Some(i), true) => (i + buttons.len() - 1) % buttons.len(),
+    };
+    focused.0 = Some(buttons[next]);
+}
+
+fn press_focused(focused: Res<FocusedButton>, mut buttons: Pressable, mut commands: Commands) {
+    if let Some((button, _, state, toggle)) = focused.0.and_then(|f| buttons.get_mut(f).ok()) {
+        press(&mut commands, button.action, state, toggle, None);
+    }
+}
+
+/// starts the press animation on every press or toggle
+fn update_pressed(
+    buttons: Query<(Ref<ButtonState>, &mut Highlight), Without<ToggleState>>,
+    toggles: Query<(Ref<ToggleState>, &mut Highlight), Without<ButtonState>>,
+) {
+    let press = || Some(Timer::from_seconds(PRESS_SECS, TimerMode::Once));
+    for (state, mut highlight) in buttons {
+        if !state.is_changed() {
+            continue;
+        }
+        highlight.pressed = state.clicked || state.touched.is_some();
+        if highlight.pressed && !state.is_added() {
+            highlight.press = press();
+        }
+    }
+    for (state, mut highlight) in toggles {
+        if !state.is_changed() {
+            continue;
+        }
+        highlight.pressed = state.0;
+        if !state.is_added() {
+            highlight.press = press();
+        }
+    }
+}
+
+/// also resizes the buttons to the ui scale
+fn animate_press(
+    buttons: Query<(&mut Highlight, &mut Transform)>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (mut highlight, mut transform) in buttons {
+        if let Some(press) = &mut highlight.press {
+            if press.tick(time.delta()).is_finished() {
+                highlight.press = None;
+            }
+            request_redraw.write(RequestRedraw);
+        } else if !settings.is_changed() {
+            continue;
         }
-        painter.circle(button.radius);
+        transform.scale = Vec3::splat(ICON_SCALE * settings.ui_scale() * highlight.scale());
     }
 }
 
-fn draw_toggles(
+/// hovered and focused buttons get a ring
+fn draw_buttons(
     mut painter: ShapePainter,
-    mut buttons: Query<(&CircleButton, &ToggleState, &Transform, &mut TextColor)>,
+    buttons: Query<(
+        Entity,
+        &CircleButton,
+        &Highlight,
+        &Transform,
+        &mut TextColor,
+    )>,
+    focused: Res<FocusedButton>,
+    theme: Res<Theme>,
 ) {
-    for (button, state, transform, mut col) in &mut buttons {
-        painter.set_translation(transform.translation - 0.1 * Vec3::Z);
-        if state.0 {
-            *col = TextColor(button.bg_color);
-            painter.set_color(button.fg_color);
+    for (entity, button, highlight, transform, mut col) in buttons {
+        let (fg, bg) = if highlight.pressed {
+            (theme.button_background, theme.button_foreground)
         } else {
-            *col = TextColor(button.fg_color);
-            painter.set_color(button.bg_color);
+            (theme.button_foreground, theme.button_background)
+        };
+        col.set_if_neq(TextColor(fg));
+        let radius = button.scaled_radius(transform);
+        painter.set_translation(transform.translation - 0.1 * Vec3::Z);
+        painter.hollow = false;
+        painter.set_color(bg);
+        painter.circle(radius);
+        let focused = focused.0 == Some(entity);
+        if focused || highlight.hovered {
+            painter.hollow = true;
+            painter.thickness_type = ThicknessType::World;
+            painter.thickness = 0.04;
+            let alpha = if focused { 1. } else { 0.5 };
+            painter.set_color(theme.button_foreground.with_alpha(alpha));
+            painter.circle(radius + 0.08);
         }
-        painter.circle(button.radius);
     }
 }
 
 fn draw_bookmark(
     total_progress: Res<TotalProgress>,
-    current_board: Res<CurrentBoard>,
-    buttons: Query<&mut Text2d, With<BookMark>>,
+    current_board: Single<&CurrentBoard, With<MainBoard>>,
+    buttons: Query<(&CircleButton, &mut Text2d)>,
 ) {
-    match total_progress
+    let icon = match total_progress
         .normalized_explored_states
         .get(&current_board.0.normalize())
     {
-        Some(&c) if c > 1 => {
-            for mut b in buttons {
-                // book-bookmark e0bb
-                b.0 = format!("\u{e0bb} {c}");
-            }
-        }
-        _ => {
-            for mut b in buttons {
-                // book          f02d
-                b.0 = format!("\u{f02d}");
-            }
+        // book-bookmark e0bb
+        Some(&c) if c > 1 => format!("\u{e0bb} {c}"),
+        // book          f02d
+        _ => "\u{f02d}".to_string(),
+    };
+    for (button, mut text) in buttons {
+        if button.action == Action::BookMarks {
+            text.0 = icon.clone();
         }
     }
 }
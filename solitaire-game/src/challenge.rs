@@ -0,0 +1,171 @@
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+    window::RequestRedraw,
+};
+use bevy_vector_shapes::prelude::*;
+use futures_lite::future::{self, block_on};
+use rand::seq::IndexedRandom;
+use solitaire_solver::{Board, Move, Pos};
+
+use crate::{
+    CurrentBoard, MainBoard,
+    board::{BoardPosition, HOLE_RADIUS, MARKER_POS, NewGame},
+    game_state::AppState,
+    lifecycle::awake,
+    main_changed,
+};
+
+/// games, which have to end with the last peg in a marked hole
+pub struct ChallengePlugin;
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Challenge>();
+        app.add_observer(start_challenge);
+        app.add_systems(
+            Update,
+            poll_new_challenge.run_if(resource_exists::<NewChallengeTask>.and(awake)),
+        );
+        app.add_systems(
+            Update,
+            (
+                find_winnable_moves
+                    .run_if(main_changed::<CurrentBoard>.or(resource_changed::<Challenge>)),
+                poll_winnable_moves.run_if(resource_exists::<WinnableMovesTask>.and(awake)),
+            )
+                .chain(),
+        );
+        app.add_systems(PostUpdate, draw_target);
+    }
+}
+
+/// the hole the last peg has to end up in, the center if there is none
+#[derive(Default, Resource)]
+pub struct Challenge(pub Option<Pos>);
+
+impl Challenge {
+    /// the constellation that wins the game
+    pub fn goal(&self) -> Board {
+        self.0
+            .map_or(Board::solved(), |target| Board::empty().set(target))
+    }
+}
+
+/// starts a new game from a random vacancy with a random target
+#[derive(Default, Event)]
+pub struct StartChallenge;
+
+#[derive(Resource)]
+struct NewChallengeTask(Task<(Board, Pos)>);
+
+/// the legal moves after which the target can still be reached
+#[derive(Resource)]
+pub struct WinnableMoves {
+    board: Board,
+    moves: Vec<Move>,
+}
+
+impl WinnableMoves {
+    pub fn contains(&self, board: Board, mov: Move) -> bool {
+        self.board == board && self.moves.contains(&mov)
+    }
+
+    pub fn first(&self, board: Board) -> Option<Move> {
+        (self.board == board).then(|| self.moves.first().copied())?
+    }
+}
+
+#[derive(Resource)]
+struct WinnableMovesTask(Task<WinnableMoves>);
+
+fn start_challenge(_: On<StartChallenge>, mut commands: Commands) {
+    info!("looking for a challenge ...");
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let holes = Board::full().pegs().collect::<Vec<_>>();
+        let mut rng = rand::rng();
+        loop {
+            let vacancy = *holes.choose(&mut rng).unwrap();
+            let target = *holes.choose(&mut rng).unwrap();
+            let start = Board::full() ^ Board::empty().set(vacancy);
+            // the standard game is no challenge
+            if start == Board::default() && target == Pos::new(3, 3) {
+                continue;
+            }
+            // most pairs are ruled out right away by their position class
+            if solitaire_solver::calculate_first_solution_to(start, target).is_some() {
+                return (start, target);
+            }
+        }
+    });
+    commands.insert_resource(NewChallengeTask(task));
+}
+
+fn poll_new_challenge(
+    mut commands: Commands,
+    mut task: ResMut<NewChallengeTask>,
+    mut challenge: ResMut<Challenge>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let Some((start, target)) = block_on(future::poll_once(&mut task.0)) else {
+        request_redraw.write(RequestRedraw);
+        return;
+    };
+    info!("challenge: finish in {target}");
+    commands.trigger(NewGame(start));
+    challenge.0 = Some(target);
+    next_state.set(AppState::Playing);
+    commands.remove_resource::<NewChallengeTask>();
+}
+
+fn find_winnable_moves(
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    challenge: Res<Challenge>,
+) {
+    commands.remove_resource::<WinnableMoves>();
+    let Some(target) = challenge.0 else {
+        commands.remove_resource::<WinnableMovesTask>();
+        return;
+    };
+    let board = board.0;
+    // replacing a running search cancels it
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let moves = board
+            .get_legal_moves()
+            .into_iter()
+            .filter(|&mov| {
+                solitaire_solver::calculate_first_solution_to(board.mov(mov), target).is_some()
+            })
+            .collect();
+        WinnableMoves { board, moves }
+    });
+    commands.insert_resource(WinnableMovesTask(task));
+}
+
+fn poll_winnable_moves(
+    mut commands: Commands,
+    mut task: ResMut<WinnableMovesTask>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    if let Some(moves) = block_on(future::poll_once(&mut task.0)) {
+        commands.insert_resource(moves);
+        commands.remove_resource::<WinnableMovesTask>();
+    }
+    request_redraw.write(RequestRedraw);
+}
+
+/// a ring around the hole the last peg has to end up in
+fn draw_target(mut painter: ShapePainter, challenge: Res<Challenge>) {
+    let Some(target) = challenge.0 else {
+        return;
+    };
+    let world_pos = BoardPosition::from(target).to_world_space();
+    painter.set_translation((world_pos, MARKER_POS).into());
+    painter.set_color(Color::srgb(1., 0.8, 0.));
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.05;
+    painter.circle(HOLE_RADIUS * 1.3);
+}
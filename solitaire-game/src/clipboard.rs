@@ -0,0 +1,24 @@
+// Text is put on the system clipboard by arboard on the desktop and by the
+// browser on the web. Android has neither.
+
+/// `Err` if the clipboard is not available
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn copy(text: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
+
+/// `Err` if the clipboard is not available
+#[cfg(target_arch = "wasm32")]
+pub fn copy(text: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("no window")?;
+    // written asynchronously, if the browser allows it
+    let _ = window.navigator().clipboard().write_text(text);
+    Ok(())
+}
+
+/// `Err` if the clipboard is not available
+#[cfg(target_os = "android")]
+pub fn copy(_text: &str) -> Result<(), String> {
+    Err("there is no clipboard on android".into())
+}
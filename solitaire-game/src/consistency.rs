@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    CurrentBoard,
+    board::{BoardPosition, Peg},
+};
+
+// Moves, undo, redo and new games change `CurrentBoard` and the peg entities
+// separately. In debug builds and with the `dev` feature both are compared
+// for every board after every frame, and the first frame they disagree is logged.
+
+pub struct ConsistencyPlugin;
+
+impl Plugin for ConsistencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, check_pegs);
+    }
+}
+
+/// `None` if the pegs stand exactly on the board, skipped pegs are disabled
+/// and not queried
+fn divergence(board: Board, pegs: &[BoardPosition]) -> Option<String> {
+    let mut from_pegs = Board::empty();
+    let mut stacked = vec![];
+    for &pos in pegs {
+        if from_pegs.occupied(pos.into()) {
+            stacked.push(Pos::from(pos));
+        }
+        from_pegs = from_pegs.set(pos.into());
+    }
+    if from_pegs == board && stacked.is_empty() {
+        return None;
+    }
+    let missing: Vec<_> = board.pegs().filter(|&p| !from_pegs.occupied(p)).collect();
+    let extra: Vec<_> = from_pegs.pegs().filter(|&p| !board.occupied(p)).collect();
+    Some(format!(
+        "pegs diverged from the board {}: missing {missing:?}, extra {extra:?}, stacked {stacked:?}",
+        board.to_notation(),
+    ))
+}
+
+fn check_pegs(
+    boards: Query<(Entity, &CurrentBoard)>,
+    pegs: Query<(&BoardPosition, &ChildOf), With<Peg>>,
+    mut diverged: Local<bool>,
+) {
+    let divergence = boards.iter().find_map(|(entity, board)| {
+        let pegs: Vec<BoardPosition> = pegs
+            .iter()
+            .filter(|(_, parent)| parent.parent() == entity)
+            .map(|(&pos, _)| pos)
+            .collect();
+        divergence(board.0, &pegs)
+    });
+    match divergence {
+        Some(divergence) if !*diverged => {
+            error!("{divergence}");
+            *diverged = true;
+        }
+        Some(_) => {}
+        None if *diverged => {
+            info!("pegs match the board again");
+            *diverged = false;
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    board::{BoardPosition, HOLE_POS, HOLE_RADIUS, ThemeColor, flat, peg_type},
+    game_state::AppState,
+    menu::text_font,
+    settings::{Coordinates, Settings},
+    theme::Theme,
+};
+
+// Columns `a`-`g` and rows `1`-`7` as written in solutions (see `Pos::name`).
+// The labels hug the cross, so they stay on the board, and the names of the
+// holes are hidden by the pegs standing in them.
+
+pub struct CoordinatesPlugin;
+
+impl Plugin for CoordinatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_labels);
+        app.add_systems(
+            Update,
+            update_labels.run_if(resource_changed::<Settings>.or(resource_changed::<Theme>)),
+        );
+    }
+}
+
+#[derive(Component)]
+enum CoordinateLabel {
+    /// a column above its topmost hole or a row left of its leftmost one
+    Edge,
+    /// the name of a hole, below its center
+    Hole(Pos),
+}
+
+/// distance of the edge labels from the holes next to them
+const EDGE_OFFSET: f32 = 0.6;
+
+fn spawn_labels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = text_font(&asset_server, 50.);
+    let holes: Vec<Pos> = Board::empty().holes().collect();
+    let mut label = |text: char, pos: Pos, offset: Vec2| {
+        let at = BoardPosition::from(pos).to_world_space() + offset;
+        commands.spawn((
+            CoordinateLabel::Edge,
+            Text2d::new(text),
+            font.clone(),
+            Transform::from_translation((at, HOLE_POS).into()).with_scale(Vec3::splat(0.006)),
+            Visibility::Hidden,
+        ));
+    };
+    for col in 0..Board::SIZE {
+        if let Some(&top) = holes.iter().filter(|p| p.col == col).min_by_key(|p| p.row) {
+            label(top.column_name(), top, Vec2::Y * EDGE_OFFSET);
+        }
+    }
+    for row in 0..Board::SIZE {
+        if let Some(&left) = holes.iter().filter(|p| p.row == row).min_by_key(|p| p.col) {
+            label(left.row_name(), left, Vec2::NEG_X * EDGE_OFFSET);
+        }
+    }
+    for &pos in &holes {
+        let at = BoardPosition::from(pos).to_world_space() - Vec2::Y * HOLE_RADIUS * 0.55;
+        commands.spawn((
+            CoordinateLabel::Hole(pos),
+            Text2d::new(pos.name()),
+            font.clone(),
+            // above the dot in the middle of the hole
+            Transform::from_translation((at, HOLE_POS + 0.04).into())
+                .with_scale(Vec3::splat(0.0025)),
+            Visibility::Hidden,
+        ));
+    }
+}
+
+fn update_labels(
+    settings: Res<Settings>,
+    state: Res<State<AppState>>,
+    theme: Res<Theme>,
+    labels: Query<(&CoordinateLabel, &mut Visibility, &mut TextColor)>,
+) {
+    // the 3d scene draws a board of its own
+    let flat = flat(Res::clone(&settings), Res::clone(&state));
+    for (label, mut visibility, mut color) in labels {
+        let (shown, theme_color) = match *label {
+            CoordinateLabel::Edge => (
+                settings.coordinates != Coordinates::Off,
+                ThemeColor::Hole(0),
+            ),
+            CoordinateLabel::Hole(pos) => (
+                settings.coordinates == Coordinates::Holes,
+                ThemeColor::HoleCenter(peg_type(pos)),
+            ),
+        };
+        *visibility = if shown && flat {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        color.0 = theme_color.color(&theme);
+    }
+}
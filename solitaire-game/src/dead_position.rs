@@ -0,0 +1,209 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Move};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::{BOARD_RADIUS, MARKER_POS},
+    buttons::RewindTo,
+    challenge::Challenge,
+    game_state::{AppState, in_game},
+    main_changed,
+    menu::text_font,
+    settings::Settings,
+    solver::FeasibleConstellations,
+    theme::Theme,
+};
+
+// Once the board leaves the feasible constellations the game can not be won
+// anymore, which is easily missed with the hints off. The rim of the board
+// turns amber then, and a button offers going back through the moves to the
+// last position that could still be won (see `RewindToWinnable`, also a
+// circle button and a shortcut).
+//
+// Challenges end elsewhere than in the center, their boards are not in the
+// feasible constellations and are left alone.
+
+pub struct DeadPositionPlugin;
+
+impl Plugin for DeadPositionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(rewind);
+        app.add_systems(
+            Update,
+            (
+                update_dead_position.run_if(
+                    resource_exists::<FeasibleConstellations>.and(
+                        main_changed::<CurrentBoard>
+                            .or(resource_added::<FeasibleConstellations>)
+                            .or(resource_changed::<Challenge>)
+                            .or(resource_changed::<Settings>)
+                            .or(state_changed::<AppState>),
+                    ),
+                ),
+                update_rewind_button.run_if(
+                    resource_exists_and_changed::<DeadPosition>
+                        .or(resource_removed::<DeadPosition>),
+                ),
+                press_rewind_button,
+                draw_rim.run_if(resource_exists::<DeadPosition>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// goes back to the last position that could still be won,
+/// if the current one can not
+#[derive(Default, Event)]
+pub struct RewindToWinnable;
+
+/// present while the current board can not be won anymore,
+/// with the number of moves after which it last could
+#[derive(Resource)]
+pub struct DeadPosition(pub Option<usize>);
+
+#[derive(Component)]
+struct RewindButton;
+
+/// where no warning is shown: outside of games, in challenges and in zen mode,
+/// which shows no hints at all
+#[derive(SystemParam)]
+struct Exempt<'w> {
+    settings: Res<'w, Settings>,
+    challenge: Res<'w, Challenge>,
+    app_state: Res<'w, State<AppState>>,
+}
+
+impl Exempt<'_> {
+    fn exempt(&self) -> bool {
+        !in_game(Res::clone(&self.app_state)) || self.settings.zen || self.challenge.0.is_some()
+    }
+}
+
+/// the number of moves played from the start of `moves` to the last
+/// winnable position before `board`, if there was one
+fn last_winnable(board: Board, moves: &[Move], winnable: impl Fn(Board) -> bool) -> Option<usize> {
+    let mut board = board;
+    for (i, &mov) in moves.iter().enumerate().rev() {
+        board = board.reverse_mov(mov);
+        if winnable(board) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn update_dead_position(
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    feasible: Res<FeasibleConstellations>,
+    exempt: Exempt,
+    dead_position: Option<Res<DeadPosition>>,
+) {
+    let winnable = |board: Board| feasible.0.contains(&board.normalize());
+    if exempt.exempt() || winnable(board.0) {
+        if dead_position.is_some() {
+            commands.remove_resource::<DeadPosition>();
+        }
+        return;
+    }
+    let last = last_winnable(board.0, solution.0.moves(), winnable);
+    if dead_position.is_none_or(|dead_position| dead_position.0 != last) {
+        commands.insert_resource(DeadPosition(last));
+    }
+}
+
+/// only if there is a winnable position to go back to
+fn update_rewind_button(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    dead_position: Option<Res<DeadPosition>>,
+    buttons: Query<Entity, With<RewindButton>>,
+    theme: Res<Theme>,
+) {
+    for button in &buttons {
+        commands.entity(button).despawn();
+    }
+    let Some(DeadPosition(Some(_))) = dead_position.as_deref() else {
+        return;
+    };
+    commands.spawn((
+        RewindButton,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            bottom: Val::Px(40.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(15.), Val::Px(8.)),
+                border_radius: BorderRadius::all(Val::Px(10.)),
+                ..default()
+            },
+            BackgroundColor(theme.warning),
+            children![(
+                Text::new("rewind to last winnable position"),
+                text_font(&asset_server, 25.),
+                TextColor(Color::BLACK),
+            )],
+        )],
+    ));
+}
+
+fn press_rewind_button(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &ChildOf), Changed<Interaction>>,
+    rewind_buttons: Query<(), With<RewindButton>>,
+) {
+    for (interaction, child_of) in buttons {
+        if *interaction == Interaction::Pressed && rewind_buttons.contains(child_of.parent()) {
+            commands.trigger(RewindToWinnable);
+        }
+    }
+}
+
+/// walks back through the moves from the current board,
+/// the undos are animated like a reset
+fn rewind(
+    _: On<RewindToWinnable>,
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    feasible: Option<Res<FeasibleConstellations>>,
+    challenge: Res<Challenge>,
+) {
+    if challenge.0.is_some() {
+        info!("only games ending in the center can be rewound");
+        return;
+    }
+    let Some(feasible) = feasible else {
+        info!("still looking for winnable positions ...");
+        return;
+    };
+    let winnable = |board: Board| feasible.0.contains(&board.normalize());
+    if winnable(board.0) {
+        info!("the game can still be won");
+        return;
+    }
+    match last_winnable(board.0, solution.0.moves(), winnable) {
+        Some(moves) => {
+            info!("rewinding to the position after {moves} moves");
+            commands.trigger(RewindTo(moves));
+        }
+        None => info!("there is no winnable position to go back to"),
+    }
+}
+
+fn draw_rim(mut painter: ShapePainter, theme: Res<Theme>) {
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.08;
+    painter.set_color(theme.warning.with_alpha(0.8));
+    painter.set_translation(Vec3::new(0., 0., MARKER_POS));
+    painter.circle(BOARD_RADIUS);
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    ecs::{entity_disabling::Disabled, query::Allow},
+    prelude::*,
+};
+use bevy_egui::{EguiContexts, EguiPlugin, EguiPrimaryContextPass, egui};
+use solitaire_solver::Board;
+
+use crate::{
+    CurrentBoard, MainBoard,
+    board::{BoardPosition, NewGame, Peg},
+    challenge::Challenge,
+    solver::FeasibleConstellations,
+};
+
+/// the state of the board as the game sees it, for finding out where
+/// `CurrentBoard` and the pegs went apart. only with the `dev` feature.
+pub struct DevPanelPlugin;
+
+impl Plugin for DevPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(EguiPlugin::default());
+        app.init_resource::<DevPanel>();
+        app.add_systems(EguiPrimaryContextPass, dev_panel);
+    }
+}
+
+#[derive(Resource, Default)]
+struct DevPanel {
+    /// position to load, in the notation of `Board::try_from`
+    notation: String,
+    error: Option<&'static str>,
+}
+
+/// skipped pegs are disabled until their move is undone
+type AllPegs<'w, 's> = Query<
+    'w,
+    's,
+    (&'static BoardPosition, &'static ChildOf, Has<Disabled>),
+    (With<Peg>, Allow<Disabled>),
+>;
+
+fn dev_panel(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut panel: ResMut<DevPanel>,
+    main: Single<(Entity, &CurrentBoard), With<MainBoard>>,
+    feasible: Option<Res<FeasibleConstellations>>,
+    pegs: AllPegs,
+    entities: Query<()>,
+) -> Result {
+    let (main, board) = *main;
+    let board = board.0;
+    let pegs: Vec<_> = pegs
+        .iter()
+        .filter(|(_, parent, _)| parent.parent() == main)
+        .map(|(pos, _, disabled)| (pos, disabled))
+        .collect();
+    let skipped = pegs.iter().filter(|(_, disabled)| *disabled).count();
+    // the board the enabled pegs stand on
+    let from_pegs = pegs
+        .iter()
+        .filter(|(_, disabled)| !disabled)
+        .fold(Board::empty(), |pegs, (pos, _)| pegs.set((*pos).into()));
+    let feasible = match feasible {
+        Some(feasible) if feasible.0.contains(&board.normalize()) => "yes",
+        Some(_) => "no",
+        None => "still calculating",
+    };
+    egui::Window::new("dev").show(contexts.ctx_mut()?, |ui| {
+        ui.monospace(format!("bits        {:#018x}", board.0));
+        ui.monospace(format!("board       {}", board.to_notation()));
+        ui.monospace(format!("normalized  {}", board.normalize().to_notation()));
+        ui.monospace(format!("feasible    {feasible}"));
+        ui.separator();
+        ui.monospace(format!("pegs        {}", pegs.len() - skipped));
+        ui.monospace(format!("skipped     {skipped}"));
+        ui.monospace(format!("entities    {}", entities.iter().count()));
+        if from_pegs == board {
+            ui.monospace("pegs match the board");
+        } else {
+            ui.colored_label(egui::Color32::RED, "pegs differ from the board:");
+            ui.monospace(from_pegs.to_notation());
+        }
+        ui.separator();
+        ui.text_edit_singleline(&mut panel.notation);
+        ui.horizontal(|ui| {
+            if ui.button("load").clicked() {
+                match Board::try_from(panel.notation.trim()) {
+                    Ok(board) => {
+                        panel.error = None;
+                        commands.trigger(NewGame(board));
+                        commands.insert_resource(Challenge::default());
+                    }
+                    Err(e) => panel.error = Some(e),
+                }
+            }
+            if ui.button("current").clicked() {
+                panel.notation = board.to_notation();
+            }
+            if ui.button("start").clicked() {
+                commands.trigger(NewGame(Board::default()));
+                commands.insert_resource(Challenge::default());
+            }
+        });
+        if let Some(error) = panel.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use bevy::log::{info, warn};
+use solitaire_solver::{Board, HashMap};
+
+use crate::storage;
+
+// Persists results of the solver in the cache directory, so they only
+// have to be computed on the first launch.
+//
+// Every file starts with a header:
+//
+//   magic (4 bytes) | version (u32 le) | number of entries (u64 le)
+//
+// followed by the entries. Boards are stored in their compressed
+// representation as u64 (le), which is the same on every platform,
+// so cache files can be shipped prebuilt.
+
+const MAGIC: [u8; 4] = *b"PEGS";
+/// bump whenever the solver or the file format changes
+const VERSION: u32 = 1;
+
+const FEASIBLE: &str = "feasible.bin";
+const RANDOM_MOVE_CHANCES: &str = "random_move_chances.bin";
+
+pub fn load_feasible() -> Option<Vec<Board>> {
+    read_entries(FEASIBLE, |e: [u8; 8]| {
+        Board::try_from_compressed_repr(u64::from_le_bytes(e))
+    })
+}
+
+pub fn store_feasible(feasible: &[Board]) {
+    write_entries(
+        FEASIBLE,
+        feasible
+            .iter()
+            .map(|b| b.to_compressed_repr().to_le_bytes()),
+    );
+}
+
+pub fn load_random_move_chances() -> Option<HashMap<Board, f64>> {
+    let entries = read_entries(RANDOM_MOVE_CHANCES, |e: [u8; 16]| {
+        let (board, p) = e.split_at(8);
+        let board = u64::from_le_bytes(board.try_into().unwrap());
+        let p = f64::from_le_bytes(p.try_into().unwrap());
+        Some((Board::try_from_compressed_repr(board)?, p))
+    })?;
+    Some(entries.into_iter().collect())
+}
+
+pub fn store_random_move_chances(chances: &HashMap<Board, f64>) {
+    write_entries(
+        RANDOM_MOVE_CHANCES,
+        chances.iter().map(|(b, p)| {
+            let mut e = [0u8; 16];
+            e[..8].copy_from_slice(&b.to_compressed_repr().to_le_bytes());
+            e[8..].copy_from_slice(&p.to_le_bytes());
+            e
+        }),
+    );
+}
+
+fn path(name: &str) -> Option<PathBuf> {
+    storage::cache_dir().map(|dir| dir.join(name))
+}
+
+fn read_entries<const N: usize, T>(
+    name: &str,
+    parse: impl Fn([u8; N]) -> Option<T>,
+) -> Option<Vec<T>> {
+    let path = path(name)?;
+    let file = File::open(&path).ok()?;
+    match try_read_entries(BufReader::new(file), parse) {
+        Ok(entries) => {
+            info!("loaded {} entries from {path:?}", entries.len());
+            Some(entries)
+        }
+        Err(e) => {
+            warn!("ignoring cache file {path:?}: {e}");
+            None
+        }
+    }
+}
+
+fn try_read_entries<const N: usize, T>(
+    mut r: impl Read,
+    parse: impl Fn([u8; N]) -> Option<T>,
+) -> io::Result<Vec<T>> {
+    let mut magic = [0u8; 4];
+    let mut version = [0u8; 4];
+    let mut len = [0u8; 8];
+    r.read_exact(&mut magic)?;
+    r.read_exact(&mut version)?;
+    r.read_exact(&mut len)?;
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+    if magic != MAGIC || u32::from_le_bytes(version) != VERSION {
+        return Err(invalid("outdated"));
+    }
+    // usize may be 32 bit (wasm)
+    let len = usize::try_from(u64::from_le_bytes(len)).map_err(|_| invalid("too many entries"))?;
+    // dont trust the header with the allocation
+    let mut entries = Vec::with_capacity(len.min(1 << 24));
+    let mut entry = [0u8; N];
+    for _ in 0..len {
+        r.read_exact(&mut entry)?;
+        entries.push(parse(entry).ok_or_else(|| invalid("invalid entry"))?);
+    }
+    Ok(entries)
+}
+
+fn write_entries<const N: usize>(name: &str, entries: impl ExactSizeIterator<Item = [u8; N]>) {
+    let Some(path) = path(name) else {
+        return;
+    };
+    if let Err(e) = try_write_entries(&path, entries) {
+        warn!("could not write cache file {path:?}: {e}");
+        // dont leave a truncated file behind
+        let _ = fs::remove_file(&path);
+    }
+}
+
+fn try_write_entries<const N: usize>(
+    path: &Path,
+    entries: impl ExactSizeIterator<Item = [u8; N]>,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut w = BufWriter::new(File::create(path)?);
+    w.write_all(&MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&(entries.len() as u64).to_le_bytes())?;
+    for entry in entries {
+        w.write_all(&entry)?;
+    }
+    w.flush()
+}
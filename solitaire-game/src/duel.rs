@@ -0,0 +1,206 @@
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+    window::RequestRedraw,
+};
+use futures_lite::future::{self, block_on};
+use solitaire_solver::{Board, DuelOutcome, Move};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    game_state::AppState,
+    input::RequestPegMove,
+    lifecycle::awake,
+    menu::{MenuButton, spawn_menu, text_font},
+};
+
+// Two players take turns jumping on the same board, the one left without a
+// move loses. Whose turn it is follows from the number of moves made, so
+// undoing a move hands the turn back.
+
+pub struct DuelPlugin;
+
+impl Plugin for DuelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_duel_text);
+        app.add_systems(OnEnter(AppState::Menu), end_duel);
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            show_duel_over.run_if(resource_exists::<Duel>),
+        );
+        app.add_systems(
+            Update,
+            (
+                computer_move.run_if(
+                    resource_exists::<Duel>
+                        .and(in_state(AppState::Playing))
+                        .and(awake),
+                ),
+                update_duel_text,
+            ),
+        );
+    }
+}
+
+/// present while a duel is played
+#[derive(Resource)]
+pub struct Duel {
+    /// the second player is the computer
+    pub computer: bool,
+}
+
+impl Duel {
+    /// 0 for the first player, 1 for the second
+    pub fn player_to_move(&self, solution: &CurrentSolution) -> usize {
+        solution.0.len() % 2
+    }
+
+    pub fn player_name(&self, player: usize) -> String {
+        match player {
+            1 if self.computer => "the computer".into(),
+            _ => format!("player {}", player + 1),
+        }
+    }
+}
+
+/// moves searched ahead by the computer, enough to see the end coming
+const SEARCH_DEPTH: usize = 5;
+
+/// the computer thinking about its move on `board`
+#[derive(Resource)]
+struct ComputerMove {
+    board: Board,
+    task: Task<Option<(Move, DuelOutcome)>>,
+}
+
+#[derive(Component)]
+struct DuelText;
+
+/// whether the board takes input, not while the computer is to move
+pub fn players_turn(
+    duel: Option<Res<Duel>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) -> bool {
+    duel.is_none_or(|duel| !duel.computer || duel.player_to_move(&solution) == 0)
+}
+
+fn end_duel(mut commands: Commands) {
+    commands.remove_resource::<Duel>();
+    commands.remove_resource::<ComputerMove>();
+}
+
+fn computer_move(
+    mut commands: Commands,
+    duel: Res<Duel>,
+    main: Single<Entity, With<MainBoard>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    computer_move: Option<ResMut<ComputerMove>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    if players_turn(Some(duel), solution) {
+        return;
+    }
+    let board = board.0;
+    match computer_move {
+        Some(mut computer_move) if computer_move.board == board => {
+            let Some(result) = block_on(future::poll_once(&mut computer_move.task)) else {
+                // keep updating while the computer is thinking
+                request_redraw.write(RequestRedraw);
+                return;
+            };
+            commands.remove_resource::<ComputerMove>();
+            if let Some((mov, outcome)) = result {
+                debug!("computer plays {mov}, {outcome:?}");
+                commands.trigger(RequestPegMove {
+                    board: *main,
+                    src: mov.pos.into(),
+                    dst: mov.target.into(),
+                });
+            }
+        }
+        // the board changed since, e.g. by an undo
+        _ => {
+            let task = AsyncComputeTaskPool::get()
+                .spawn(async move { solitaire_solver::duel_move(board, SEARCH_DEPTH) });
+            commands.insert_resource(ComputerMove { board, task });
+            request_redraw.write(RequestRedraw);
+        }
+    }
+}
+
+/// the player to move has no move left and loses
+fn show_duel_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    duel: Res<Duel>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    let winner = 1 - duel.player_to_move(&solution);
+    let text = format!(
+        "{} wins!\n{} moves, {} pegs left",
+        duel.player_name(winner),
+        solution.0.len(),
+        board.0.count_pegs(),
+    );
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        AppState::GameOver,
+        text,
+        &[
+            MenuButton::Undo,
+            MenuButton::StartOver,
+            MenuButton::MainMenu,
+        ],
+    );
+}
+
+fn spawn_duel_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        DuelText,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(50.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::default(),
+            text_font(&asset_server, 30.),
+            TextColor::WHITE,
+        )],
+    ));
+}
+
+/// whose turn it is, while playing a duel
+fn update_duel_text(
+    duel: Option<Res<Duel>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    app_state: Res<State<AppState>>,
+    panel: Single<(&mut Visibility, &Children), With<DuelText>>,
+    mut texts: Query<&mut Text>,
+) {
+    let (mut visibility, children) = panel.into_inner();
+    let Some(duel) = duel.filter(|_| *app_state.get() == AppState::Playing) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    let player = duel.player_to_move(&solution);
+    let text = if duel.computer && player == 1 {
+        "the computer is thinking ...".to_string()
+    } else {
+        format!("{} to move", duel.player_name(player))
+    };
+    for &child in children {
+        if let Ok(mut duel_text) = texts.get_mut(child)
+            && duel_text.0 != text
+        {
+            duel_text.0 = text.clone();
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+    window::{PrimaryWindow, RequestRedraw},
+};
+use futures_lite::future::{self, block_on};
+use solitaire_solver::{Board, Pos, Solution};
+
+use crate::{
+    BoardCamera, CurrentBoard, MainBoard,
+    board::{BoardPosition, NewGame},
+    challenge::Challenge,
+    game_state::AppState,
+    main_changed,
+    menu::text_font,
+    viewport_to_world,
+};
+
+/// lets the player set up the start position by toggling pegs,
+/// while the solver tells whether it can still be won
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Editor), (start_editing, show_editor));
+        app.add_systems(OnExit(AppState::Editor), stop_checking);
+        app.add_systems(
+            Update,
+            (
+                toggle_peg_mouse.run_if(input_just_pressed(MouseButton::Left)),
+                toggle_peg_touch,
+                handle_editor_buttons,
+                check_winnable.run_if(main_changed::<CurrentBoard>),
+                poll_winnable.run_if(resource_exists::<WinnableCheck>),
+            )
+                .chain()
+                .run_if(in_state(AppState::Editor)),
+        );
+    }
+}
+
+/// the solver looking for a solution from `CurrentBoard`
+#[derive(Resource)]
+struct WinnableCheck(Task<Option<Solution>>);
+
+#[derive(Component)]
+struct WinnableText;
+
+#[derive(Component, Clone, Copy)]
+enum EditorButton {
+    Play,
+    Clear,
+    Fill,
+}
+
+impl EditorButton {
+    fn label(self) -> &'static str {
+        match self {
+            EditorButton::Play => "play",
+            EditorButton::Clear => "clear",
+            EditorButton::Fill => "fill",
+        }
+    }
+}
+
+/// the edited position replaces the game, moves can not be undone past it
+fn start_editing(mut commands: Commands, board: Single<&CurrentBoard, With<MainBoard>>) {
+    commands.trigger(NewGame(board.0));
+    commands.insert_resource(Challenge::default());
+}
+
+fn stop_checking(mut commands: Commands) {
+    // dropping the task cancels it
+    commands.remove_resource::<WinnableCheck>();
+}
+
+/// a box at the bottom of the screen, out of the way of the board
+fn show_editor(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = |font_size| text_font(&asset_server, font_size);
+    commands
+        .spawn((
+            DespawnOnExit(AppState::Editor),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::End,
+                padding: UiRect::bottom(Val::Px(20.)),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.),
+                        padding: UiRect::all(Val::Px(20.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((WinnableText, Text::default(), font(30.), TextColor::WHITE));
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for button in
+                                [EditorButton::Clear, EditorButton::Fill, EditorButton::Play]
+                            {
+                                parent
+                                    .spawn((
+                                        Button,
+                                        button,
+                                        Node {
+                                            padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                            border_radius: BorderRadius::all(Val::Px(10.)),
+                                            ..default()
+                                        },
+                                        BackgroundColor(Color::WHITE),
+                                    ))
+                                    .with_child((
+                                        Text::new(button.label()),
+                                        font(30.),
+                                        TextColor(Color::BLACK),
+                                    ));
+                            }
+                        });
+                });
+        });
+}
+
+fn toggle_peg_mouse(
+    mut commands: Commands,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    ui: Query<&Interaction>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+) {
+    // clicks on the buttons dont reach the board
+    if ui.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+    let (camera, camera_transform) = *camera_query;
+    if let Some(world_pos) = window
+        .cursor_position()
+        .and_then(|cursor_pos| viewport_to_world(cursor_pos, camera, camera_transform))
+    {
+        toggle_peg(&mut commands, board.0, world_pos.xy());
+    }
+}
+
+fn toggle_peg_touch(
+    mut commands: Commands,
+    touches: Res<Touches>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    ui: Query<&Interaction>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+) {
+    if ui.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+    let (camera, camera_transform) = *camera_query;
+    // several touches in one frame toggle one after the other
+    let mut board = board.0;
+    for touch in touches.iter_just_pressed() {
+        if let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform) {
+            board = toggle_peg(&mut commands, board, world_pos.xy());
+        }
+    }
+}
+
+/// toggles the peg at `world_pos`, if that is a hole of the board
+fn toggle_peg(commands: &mut Commands, board: Board, world_pos: Vec2) -> Board {
+    let pos: Pos = BoardPosition::from_world_space(world_pos).into();
+    if !Board::inbounds(pos) {
+        return board;
+    }
+    let board = board ^ Board::empty().set(pos);
+    commands.trigger(NewGame(board));
+    board
+}
+
+fn handle_editor_buttons(
+    buttons: Query<(&Interaction, &EditorButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            EditorButton::Play => next_state.set(AppState::Playing),
+            EditorButton::Clear => commands.trigger(NewGame(Board::empty())),
+            EditorButton::Fill => commands.trigger(NewGame(Board::full())),
+        }
+    }
+}
+
+fn check_winnable(
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    mut text: Single<&mut Text, With<WinnableText>>,
+) {
+    let board = board.0;
+    if board == Board::empty() {
+        commands.remove_resource::<WinnableCheck>();
+        text.0 = "place some pegs".into();
+        return;
+    }
+    text.0 = "checking ...".into();
+    // replacing a running check cancels it
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { solitaire_solver::calculate_first_solution_from(board) });
+    commands.insert_resource(WinnableCheck(task));
+}
+
+fn poll_winnable(
+    mut commands: Commands,
+    mut check: ResMut<WinnableCheck>,
+    mut text: Single<&mut Text, With<WinnableText>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    match block_on(future::poll_once(&mut check.0)) {
+        Some(solution) => {
+            text.0 = match solution {
+                Some(_) => "this position is winnable",
+                None => "this position is not winnable",
+            }
+            .into();
+            commands.remove_resource::<WinnableCheck>();
+        }
+        // keep updating while waiting for the solver
+        None => {
+            request_redraw.write(RequestRedraw);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, window::RequestRedraw};
+use bevy_vector_shapes::prelude::*;
+
+use crate::{
+    CurrentBoard, MainBoard, MoveEvent,
+    board::{BoardPosition, PEG_POS, PEG_RADIUS},
+    game_state::GameState,
+};
+
+/// expanding rings and sparks for captured pegs and solved boards
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(capture_burst);
+        app.add_systems(OnEnter(GameState::Won), celebrate);
+        app.add_systems(Update, (expand_rings, move_sparks));
+        app.add_systems(PostUpdate, (draw_rings, draw_sparks));
+    }
+}
+
+/// grows to `radius` while fading out
+#[derive(Component)]
+struct Ring {
+    radius: f32,
+    color: Color,
+    timer: Timer,
+}
+
+/// flies off while fading out
+#[derive(Component)]
+struct Spark {
+    velocity: Vec2,
+    color: Color,
+    timer: Timer,
+}
+
+/// above the pegs
+const EFFECT_POS: f32 = PEG_POS + 0.1;
+
+fn capture_burst(
+    move_event: On<MoveEvent>,
+    boards: Query<&GlobalTransform>,
+    mut commands: Commands,
+) {
+    // on the board the move was made on
+    let board = boards
+        .get(move_event.board)
+        .map_or(Vec2::ZERO, |board| board.translation().xy());
+    let pos = board + BoardPosition::from(move_event.mov.skip).to_world_space();
+    let color = Color::WHITE;
+    spawn_ring(&mut commands, pos, PEG_RADIUS * 2., color, 0.4);
+    spawn_sparks(&mut commands, pos, 8, 1.5, |_| color, 0.4);
+}
+
+fn celebrate(mut commands: Commands, board: Single<&CurrentBoard, With<MainBoard>>) {
+    let Some(last) = board.0.pegs().next() else {
+        return;
+    };
+    let pos = BoardPosition::from(last).to_world_space();
+    let gold = Color::srgb(1., 0.8, 0.);
+    for (radius, secs) in [(2., 0.8), (3., 1.2), (4., 1.6)] {
+        spawn_ring(&mut commands, pos, radius, gold, secs);
+    }
+    let rainbow = |angle: f32| Color::hsl(angle.to_degrees(), 1., 0.6);
+    spawn_sparks(&mut commands, pos, 32, 3., rainbow, 1.5);
+}
+
+fn spawn_ring(commands: &mut Commands, pos: Vec2, radius: f32, color: Color, secs: f32) {
+    commands.spawn((
+        Ring {
+            radius,
+            color,
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+        },
+        Transform::from_translation((pos, EFFECT_POS).into()),
+    ));
+}
+
+/// `count` sparks flying off evenly spread, coloured by their angle
+fn spawn_sparks(
+    commands: &mut Commands,
+    pos: Vec2,
+    count: usize,
+    speed: f32,
+    color: impl Fn(f32) -> Color,
+    secs: f32,
+) {
+    for i in 0..count {
+        let angle = i as f32 / count as f32 * TAU;
+        commands.spawn((
+            Spark {
+                velocity: Vec2::from_angle(angle) * speed,
+                color: color(angle),
+                timer: Timer::from_seconds(secs, TimerMode::Once),
+            },
+            Transform::from_translation((pos, EFFECT_POS).into()),
+        ));
+    }
+}
+
+fn expand_rings(
+    mut commands: Commands,
+    rings: Query<(Entity, &mut Ring)>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (entity, mut ring) in rings {
+        if ring.timer.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+        request_redraw.write(RequestRedraw);
+    }
+}
+
+fn move_sparks(
+    mut commands: Commands,
+    sparks: Query<(Entity, &mut Spark, &mut Transform)>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (entity, mut spark, mut transform) in sparks {
+        if spark.timer.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+        // slowing down
+        let speed = spark.timer.fraction_remaining();
+        transform.translation += (spark.velocity * speed * time.delta_secs()).extend(0.);
+        request_redraw.write(RequestRedraw);
+    }
+}
+
+fn draw_rings(mut painter: ShapePainter, rings: Query<(&Ring, &Transform)>) {
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.05;
+    for (ring, transform) in rings {
+        let t = EaseFunction::QuadraticOut.sample_clamped(ring.timer.fraction());
+        painter.set_translation(transform.translation);
+        painter.set_color(ring.color.with_alpha(1. - t));
+        painter.circle(ring.radius * t);
+    }
+}
+
+fn draw_sparks(mut painter: ShapePainter, sparks: Query<(&Spark, &Transform)>) {
+    for (spark, transform) in sparks {
+        let alpha = spark.timer.fraction_remaining();
+        painter.set_translation(transform.translation);
+        painter.set_color(spark.color.with_alpha(alpha));
+        painter.circle(0.04);
+    }
+}
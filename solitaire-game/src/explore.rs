@@ -0,0 +1,289 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    CurrentBoard, MainBoard, menu::text_font, solver::FeasibleConstellations, theme::Theme,
+};
+
+/// the positions reachable from a board within two moves,
+/// coloured by whether they can still be won.
+/// clicking a position explores it, without changing the game.
+pub struct ExplorePlugin;
+
+impl Plugin for ExplorePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(toggle_explore);
+        app.add_systems(
+            Update,
+            (
+                explore_key.run_if(input_just_pressed(KeyCode::KeyX)),
+                handle_explore_buttons,
+                update_explore_screen.run_if(resource_exists_and_changed::<Explored>),
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ToggleExplore;
+
+/// the position in the middle of the screen, while it is shown
+#[derive(Resource)]
+struct Explored(Board);
+
+#[derive(Component)]
+struct ExploreScreen;
+
+/// explores its position when clicked
+#[derive(Component)]
+struct ExploreNode(Board);
+
+#[derive(Component, Clone, Copy)]
+enum ExploreButton {
+    Current,
+    Close,
+}
+
+impl ExploreButton {
+    fn label(self) -> &'static str {
+        match self {
+            ExploreButton::Current => "current",
+            ExploreButton::Close => "close",
+        }
+    }
+}
+
+fn explore_key(mut commands: Commands) {
+    commands.trigger(ToggleExplore);
+}
+
+/// size of a hole in pixels
+const CELL: f32 = 6.;
+
+fn toggle_explore(
+    _: On<ToggleExplore>,
+    mut commands: Commands,
+    explored: Option<Res<Explored>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    screen: Query<Entity, With<ExploreScreen>>,
+) {
+    if explored.is_some() {
+        commands.remove_resource::<Explored>();
+        for screen in screen {
+            commands.entity(screen).despawn();
+        }
+    } else {
+        commands.insert_resource(Explored(board.0));
+    }
+}
+
+fn handle_explore_buttons(
+    mut commands: Commands,
+    nodes: Query<(&Interaction, &ExploreNode), Changed<Interaction>>,
+    buttons: Query<(&Interaction, &ExploreButton), Changed<Interaction>>,
+    explored: Option<ResMut<Explored>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+) {
+    let Some(mut explored) = explored else {
+        return;
+    };
+    for (interaction, node) in nodes {
+        if *interaction == Interaction::Pressed {
+            explored.0 = node.0;
+        }
+    }
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            ExploreButton::Current => explored.0 = board.0,
+            ExploreButton::Close => commands.trigger(ToggleExplore),
+        }
+    }
+}
+
+/// the positions after every legal move
+fn children(board: Board) -> Vec<Board> {
+    board
+        .get_legal_moves()
+        .into_iter()
+        .map(|mov| board.mov(mov))
+        .collect()
+}
+
+/// `None` while the feasible constellations are still being calculated
+fn winnable(board: Board, feasible: Option<&FeasibleConstellations>) -> Option<bool> {
+    feasible.map(|feasible| feasible.0.contains(&board.normalize()))
+}
+
+/// one row per move: its position followed by the positions one move later
+fn update_explore_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    explored: Res<Explored>,
+    feasible: Option<Res<FeasibleConstellations>>,
+    theme: Res<Theme>,
+    screen: Query<Entity, With<ExploreScreen>>,
+) {
+    for screen in screen {
+        commands.entity(screen).despawn();
+    }
+    let font = |font_size| text_font(&asset_server, font_size);
+    let feasible = feasible.as_deref();
+    let node = |board: Board| {
+        let border = match winnable(board, feasible) {
+            Some(true) => theme.good,
+            Some(false) => theme.bad,
+            None => Color::WHITE.with_alpha(0.3),
+        };
+        (
+            Button,
+            ExploreNode(board),
+            Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(Board::SIZE as u16, CELL),
+                grid_template_rows: RepeatedGridTrack::px(Board::SIZE as u16, CELL),
+                padding: UiRect::all(Val::Px(3.)),
+                border: UiRect::all(Val::Px(2.)),
+                border_radius: BorderRadius::all(Val::Px(6.)),
+                ..default()
+            },
+            BorderColor::all(border),
+            BackgroundColor(theme.board),
+        )
+    };
+    let holes = |parent: &mut ChildSpawnerCommands, board: Board| {
+        for row in 0..Board::SIZE {
+            for col in 0..Board::SIZE {
+                let color = match Pos::try_new(row, col) {
+                    Some(pos) if board.occupied(pos) => Color::WHITE,
+                    Some(_) => Color::WHITE.with_alpha(0.2),
+                    None => Color::NONE,
+                };
+                parent.spawn((
+                    Node {
+                        margin: UiRect::all(Val::Px(1.)),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+            }
+        }
+    };
+    let explored = explored.0;
+    commands
+        .spawn((
+            ExploreScreen,
+            // above the menus
+            GlobalZIndex(1),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.),
+                        max_width: Val::Percent(95.),
+                        max_height: Val::Percent(95.),
+                        padding: UiRect::all(Val::Px(20.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("explore"), font(40.), TextColor::WHITE));
+                    parent.spawn((
+                        Text::new("framed like the hints: whether a position can still be won"),
+                        font(20.),
+                        TextColor::WHITE,
+                    ));
+                    parent
+                        .spawn(Node {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(30.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(node(explored))
+                                .with_children(|parent| holes(parent, explored));
+                            parent
+                                .spawn(Node {
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: Val::Px(8.),
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    let moves = children(explored);
+                                    if moves.is_empty() {
+                                        parent.spawn((
+                                            Text::new("no moves left"),
+                                            font(25.),
+                                            TextColor::WHITE,
+                                        ));
+                                    }
+                                    for child in moves {
+                                        parent
+                                            .spawn(Node {
+                                                align_items: AlignItems::Center,
+                                                column_gap: Val::Px(4.),
+                                                ..default()
+                                            })
+                                            .with_children(|parent| {
+                                                parent
+                                                    .spawn(node(child))
+                                                    .with_children(|parent| holes(parent, child));
+                                                parent.spawn((
+                                                    Text::new("→"),
+                                                    font(20.),
+                                                    TextColor::WHITE,
+                                                ));
+                                                for grandchild in children(child) {
+                                                    parent.spawn(node(grandchild)).with_children(
+                                                        |parent| holes(parent, grandchild),
+                                                    );
+                                                }
+                                            });
+                                    }
+                                });
+                        });
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for b in [ExploreButton::Current, ExploreButton::Close] {
+                                parent.spawn((
+                                    Button,
+                                    b,
+                                    Node {
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        border_radius: BorderRadius::all(Val::Px(10.)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::WHITE),
+                                    children![(
+                                        Text::new(b.label()),
+                                        font(30.),
+                                        TextColor(Color::BLACK)
+                                    )],
+                                ));
+                            }
+                        });
+                });
+        });
+}
@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::view::screenshot::{Screenshot, save_to_disk},
+    tasks::AsyncComputeTaskPool,
+};
+use image::{
+    Delay, Frame, ImageResult, Rgba, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+};
+use solitaire_solver::{Board, Solution};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::{BOARD_RADIUS, BoardPosition, HOLE_RADIUS, PEG_RADIUS, ThemeColor, peg_type},
+    storage,
+    theme::Theme,
+};
+
+/// F12 saves a screenshot and `ExportGif` an animation of the current game,
+/// both into the pictures directory
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(export_gif);
+        app.add_systems(
+            Update,
+            take_screenshot.run_if(input_just_pressed(KeyCode::F12)),
+        );
+    }
+}
+
+/// every position of the current game, from its start
+#[derive(Default, Event)]
+pub struct ExportGif;
+
+/// width and height of the animation in pixels
+const GIF_SIZE: u32 = 400;
+
+/// pixels per unit of world space, leaving a margin around the board
+const GIF_SCALE: f32 = GIF_SIZE as f32 / (2. * BOARD_RADIUS + 0.2);
+
+const MOVE_DELAY_MS: u32 = 600;
+
+/// the final position stays a little longer before the animation loops
+const END_DELAY_MS: u32 = 2000;
+
+/// a new file in the pictures directory, named after the current time
+fn export_path(extension: &str) -> Option<PathBuf> {
+    let Some(dir) = storage::pictures_dir() else {
+        info!("no directory to export to");
+        return None;
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        warn!("could not create {}: {e}", dir.display());
+        return None;
+    }
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    Some(dir.join(format!("peg-solitaire-{secs}.{extension}")))
+}
+
+fn take_screenshot(mut commands: Commands) {
+    let Some(path) = export_path("png") else {
+        return;
+    };
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path));
+}
+
+fn export_gif(
+    _: On<ExportGif>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    theme: Res<Theme>,
+) {
+    let Some(path) = export_path("gif") else {
+        return;
+    };
+    let start = solution.start(board.0);
+    let moves = solution.0.clone();
+    let theme = theme.clone();
+    // encoding takes a moment, the game goes on meanwhile
+    AsyncComputeTaskPool::get()
+        .spawn(async move {
+            match write_gif(&path, start, &moves, &theme) {
+                Ok(()) => info!("gif saved to {}", path.display()),
+                Err(e) => warn!("could not save gif: {e}"),
+            }
+        })
+        .detach();
+}
+
+/// drawn off-screen from the moves, independent of the window and animations
+fn write_gif(path: &Path, start: Board, moves: &Solution, theme: &Theme) -> ImageResult<()> {
+    let mut encoder = GifEncoder::new_with_speed(fs::File::create(path)?, 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    let boards = moves.moves().iter().scan(start, |board, &mov| {
+        *board = board.mov(mov);
+        Some(*board)
+    });
+    let boards: Vec<Board> = std::iter::once(start).chain(boards).collect();
+    let frames = boards.iter().enumerate().map(|(i, &board)| {
+        let delay = if i + 1 == boards.len() {
+            END_DELAY_MS
+        } else {
+            MOVE_DELAY_MS
+        };
+        Frame::from_parts(
+            render(board, theme),
+            0,
+            0,
+            Delay::from_numer_denom_ms(delay, 1),
+        )
+    });
+    encoder.encode_frames(frames)
+}
+
+/// the board as seen from above, like the flat renderer draws it
+fn render(board: Board, theme: &Theme) -> RgbaImage {
+    let background = theme.background.unwrap_or(Color::BLACK);
+    let mut image = RgbaImage::from_pixel(
+        GIF_SIZE,
+        GIF_SIZE,
+        Rgba(background.to_srgba().to_u8_array()),
+    );
+    disc(
+        &mut image,
+        Vec2::ZERO,
+        BOARD_RADIUS,
+        ThemeColor::Board.color(theme),
+    );
+    for pos in Board::empty().holes() {
+        let center = BoardPosition::from(pos).to_world_space();
+        let t = peg_type(pos);
+        disc(
+            &mut image,
+            center,
+            HOLE_RADIUS,
+            ThemeColor::Hole(t).color(theme),
+        );
+        disc(
+            &mut image,
+            center,
+            HOLE_RADIUS * 0.2,
+            ThemeColor::HoleCenter(t).color(theme),
+        );
+    }
+    for pos in board.pegs() {
+        let center = BoardPosition::from(pos).to_world_space();
+        disc(
+            &mut image,
+            center,
+            PEG_RADIUS,
+            ThemeColor::Peg(peg_type(pos)).color(theme),
+        );
+    }
+    image
+}
+
+/// a filled circle in world space, with a smooth edge
+fn disc(image: &mut RgbaImage, center: Vec2, radius: f32, color: Color) {
+    // y points down in the image
+    let center = Vec2::splat(GIF_SIZE as f32 / 2.) + GIF_SCALE * Vec2::new(center.x, -center.y);
+    let radius = GIF_SCALE * radius;
+    let color = color.to_srgba();
+    let min = (center - radius).floor().max(Vec2::ZERO);
+    let max = (center + radius)
+        .ceil()
+        .min(Vec2::splat(GIF_SIZE as f32 - 1.));
+    for y in min.y as u32..=max.y as u32 {
+        for x in min.x as u32..=max.x as u32 {
+            let distance = Vec2::new(x as f32 + 0.5, y as f32 + 0.5).distance(center);
+            let coverage = (radius - distance + 0.5).clamp(0., 1.);
+            if coverage == 0. {
+                continue;
+            }
+            let pixel = image.get_pixel_mut(x, y);
+            let below = Srgba::from_u8_array(pixel.0);
+            pixel.0 = below.mix(&color, coverage).to_u8_array();
+        }
+    }
+}
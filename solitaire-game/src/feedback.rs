@@ -0,0 +1,106 @@
+use bevy::{prelude::*, window::RequestRedraw};
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    animation::Shake,
+    board::{BoardPosition, HOLE_RADIUS, IllegalMove, PEG_POS, Peg},
+    menu::text_font,
+    ui_scale::UiScaled,
+};
+
+/// shows why a move was rejected: the peg shakes,
+/// the target hole flashes red and the reason fades out next to it
+pub struct MoveFeedback;
+
+impl Plugin for MoveFeedback {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_illegal_move);
+        app.add_systems(Update, fade_out);
+        app.add_systems(PostUpdate, draw_flashes.after(TransformSystems::Propagate));
+    }
+}
+
+const FADE_SECS: f32 = 1.5;
+
+/// removed once the timer finishes
+#[derive(Component)]
+struct FadeOut(Timer);
+
+/// a red disc on a hole
+#[derive(Component)]
+struct Flash;
+
+fn on_illegal_move(
+    illegal_move: On<IllegalMove>,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let board = illegal_move.board;
+    let src = illegal_move.src;
+    let dst = illegal_move.dst;
+    if let Some((peg, _, _)) = pegs
+        .iter()
+        .find(|(_, p, parent)| **p == src && parent.parent() == board)
+    {
+        commands.entity(peg).insert(Shake::default());
+    }
+    let fade_out = || FadeOut(Timer::from_seconds(FADE_SECS, TimerMode::Once));
+    // there is no hole to flash off the board
+    let on_board = Board::inbounds(Pos::from(dst));
+    let at = if on_board { dst } else { src }.to_world_space();
+    if on_board {
+        commands.spawn((
+            Flash,
+            fade_out(),
+            // over an occupied target as well
+            Transform::from_translation((at, PEG_POS + 0.01).into()),
+            ChildOf(board),
+        ));
+    }
+    commands.spawn((
+        fade_out(),
+        Text2d::new(illegal_move.error.to_string()),
+        text_font(&asset_server, 50.),
+        TextColor(Color::srgb(1., 0.3, 0.3)),
+        // above the pegs
+        Transform::from_translation((at + Vec2::Y * 0.6, 1.5).into()),
+        UiScaled(0.004),
+        ChildOf(board),
+    ));
+}
+
+fn fade_out(
+    mut commands: Commands,
+    fading: Query<(Entity, &mut FadeOut, Option<&mut TextColor>)>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for (entity, mut fade_out, text_color) in fading {
+        if fade_out.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(mut text_color) = text_color {
+            text_color.0.set_alpha(fade_out.0.fraction_remaining());
+        }
+        request_redraw.write(RequestRedraw);
+    }
+}
+
+fn draw_flashes(
+    mut painter: ShapePainter,
+    flashes: Query<(&GlobalTransform, &FadeOut), With<Flash>>,
+) {
+    for (transform, fade_out) in flashes {
+        painter.set_translation(transform.translation());
+        painter.set_color(Color::srgba(
+            1.,
+            0.,
+            0.,
+            0.8 * fade_out.0.fraction_remaining(),
+        ));
+        painter.circle(HOLE_RADIUS);
+    }
+}
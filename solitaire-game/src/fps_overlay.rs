@@ -3,6 +3,8 @@ use bevy::{
     prelude::*,
 };
 
+use crate::settings::Settings;
+
 pub struct FpsOverlay;
 
 impl Plugin for FpsOverlay {
@@ -24,11 +26,20 @@ impl Plugin for FpsOverlay {
             },
         });
         app.add_systems(Update, toggle_fps_overlay);
+        app.add_systems(
+            Update,
+            show_fps_overlay.run_if(resource_changed::<Settings>),
+        );
     }
 }
 
-fn toggle_fps_overlay(input: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<FpsOverlayConfig>) {
+fn toggle_fps_overlay(input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
     if input.just_pressed(KeyCode::KeyD) {
-        overlay.enabled = !overlay.enabled;
+        settings.fps_overlay = !settings.fps_overlay;
     }
 }
+
+/// never in zen mode
+fn show_fps_overlay(settings: Res<Settings>, mut overlay: ResMut<FpsOverlayConfig>) {
+    overlay.enabled = settings.fps_overlay && !settings.zen;
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::AppLifecycle};
+use solitaire_solver::Board;
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::NewGame,
+    challenge::Challenge,
+    duel::Duel,
+    main_changed,
+    menu::{MenuButton, spawn_menu},
+    total_progress::NewSolution,
+};
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppState>();
+        app.init_state::<GameState>();
+        app.init_resource::<GameTime>();
+        app.add_observer(reset_game_time);
+        app.add_systems(
+            Update,
+            update_game_state
+                .run_if(main_changed::<CurrentBoard>.or(resource_changed::<Challenge>)),
+        );
+        app.add_systems(Update, update_app_state);
+        app.add_systems(Update, update_game_time.run_if(in_state(AppState::Playing)));
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            show_game_over.run_if(not(resource_exists::<Duel>)),
+        );
+    }
+}
+
+/// where the player is in the app
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    Menu,
+    Playing,
+    Paused,
+    /// setting up the start position
+    Editor,
+    /// the game is won or lost, until a move is undone
+    GameOver,
+    /// browsing finished games
+    Replays,
+    /// watching a finished game
+    Replay,
+    /// learning the rules
+    Tutorial,
+    /// two players solving side by side
+    Race,
+    /// solutions playing by themselves after a while at the menu
+    Attract,
+}
+
+/// whether the board can take input
+pub fn in_game(state: Res<State<AppState>>) -> bool {
+    matches!(
+        state.get(),
+        AppState::Playing | AppState::GameOver | AppState::Tutorial
+    )
+}
+
+/// the state of the board
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Playing,
+    /// the board is solved
+    Won,
+    /// there are no legal moves left
+    NoMoves,
+}
+
+/// time spent on the current game
+#[derive(Default, Resource)]
+pub struct GameTime(pub Duration);
+
+fn update_game_state(
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let state = if board.0 == challenge.goal() {
+        GameState::Won
+    } else if board.0.get_legal_moves().is_empty() {
+        GameState::NoMoves
+    } else {
+        GameState::Playing
+    };
+    // not `DetectChangesMut::set_if_neq`, which compares the whole `NextState`
+    NextState::set_if_neq(&mut next_state, state);
+}
+
+/// runs while playing, so the clock stops in the pause menu
+fn update_game_time(
+    time: Res<Time<Real>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    mut game_time: ResMut<GameTime>,
+    mut lifecycle: MessageReader<AppLifecycle>,
+) {
+    // the frame after resuming spans the time the app was suspended
+    let suspended = lifecycle.read().count() > 0;
+    // the clock starts with the first move and keeps running when it is undone
+    let started = !solution.0.is_empty() || !game_time.0.is_zero();
+    if started && !suspended {
+        game_time.0 += time.delta();
+    }
+}
+
+fn reset_game_time(_: On<NewGame>, mut game_time: ResMut<GameTime>) {
+    game_time.0 = Duration::ZERO;
+}
+
+/// the game is over as soon as the board is, and goes on after an undo
+fn update_app_state(
+    game_state: Res<State<GameState>>,
+    app_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    match (app_state.get(), game_state.get()) {
+        (AppState::Playing, GameState::Won | GameState::NoMoves) => {
+            next_state.set(AppState::GameOver)
+        }
+        (AppState::GameOver, GameState::Playing) => next_state.set(AppState::Playing),
+        _ => {}
+    }
+}
+
+fn show_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    game_time: Res<GameTime>,
+    new_solution: Res<NewSolution>,
+    challenge: Res<Challenge>,
+) {
+    let (text, buttons) = if board.0 == challenge.goal() {
+        let secs = game_time.0.as_secs();
+        let found = if challenge.0.is_some() {
+            "challenge completed"
+        } else if solution.start(board.0) != Board::default() {
+            "from your own start position"
+        } else if new_solution.0 {
+            "you have never found this solution before!"
+        } else {
+            "you have found this solution before"
+        };
+        let text = format!(
+            "solved!\n{} moves in {}:{:02}\n{found}",
+            solution.0.len(),
+            secs / 60,
+            secs % 60,
+        );
+        let mut buttons = vec![MenuButton::Share];
+        // animations are saved to a file, which the web has not
+        #[cfg(not(target_arch = "wasm32"))]
+        buttons.push(MenuButton::ExportGif);
+        buttons.push(MenuButton::StartOver);
+        (text, buttons)
+    } else {
+        let text = format!("no more moves\n{} pegs left", board.0.count_pegs());
+        (text, vec![MenuButton::Undo, MenuButton::StartOver])
+    };
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        AppState::GameOver,
+        text,
+        &buttons,
+    );
+}
@@ -1,68 +1,404 @@
-use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use bevy::{ecs::system::SystemParam, prelude::*, window::RequestRedraw};
 use bevy_vector_shapes::prelude::*;
-use solitaire_solver::{Board, Dir};
+use solitaire_solver::{Board, Dir, Move};
 
-use crate::{BoardPosition, CurrentBoard, board::MARKER_POS, solver::FeasibleConstellations};
+use crate::{
+    BoardPosition, CurrentBoard, CurrentSolution, MainBoard, Selected,
+    board::{HOLE_RADIUS, MARKER_POS},
+    challenge::{Challenge, WinnableMoves},
+    game_state::{AppState, in_game},
+    main_changed,
+    menu::text_font,
+    settings::{HintLevel, Settings},
+    solver::{FeasibleConstellations, RandomMoveChances},
+    stats::UpdateStats,
+    theme::Theme,
+};
 
 pub struct HintsPlugin;
 
 impl Plugin for HintsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Shape2dPlugin::default());
-        app.add_observer(update_hints);
+        app.init_resource::<HintCount>();
+        app.add_observer(cycle_hints);
+        app.add_observer(show_best_move);
         app.add_systems(
             Update,
-            draw_possible_moves.run_if(
-                resource_exists::<ShowHints>.and(resource_exists::<FeasibleConstellations>),
+            reset_hint_count.run_if(main_changed::<CurrentSolution>),
+        );
+        app.add_systems(
+            Update,
+            (
+                hide_best_move.run_if(
+                    resource_exists::<BestMove>
+                        .and(resource_changed::<Settings>.or(state_changed::<AppState>)),
+                ),
+                update_best_move.run_if(
+                    hints_are(HintLevel::BestMove)
+                        .and(resource_exists::<FeasibleConstellations>)
+                        .and(in_game),
+                ),
+                draw_best_move.run_if(resource_exists::<BestMove>.and(in_game)),
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Update,
+            update_chance_labels.run_if(
+                main_changed::<CurrentBoard>
+                    .or(resource_changed::<Settings>)
+                    .or(resource_changed::<Challenge>)
+                    .or(resource_added::<RandomMoveChances>),
             ),
         );
+        app.add_systems(PostUpdate, draw_landing_holes);
+        app.add_systems(
+            Update,
+            draw_possible_moves
+                .run_if(hints_are(HintLevel::Feasibility).or(hints_are(HintLevel::Heatmap))),
+        );
     }
 }
 
+/// switches to the next `HintLevel`
 #[derive(Default, Event)]
 pub struct ToggleHints;
 
+/// asks for a single good move instead of all of them
+#[derive(Default, Event)]
+pub struct ShowBestMove;
+
+/// the move shown until the board, the settings or the app state change
 #[derive(Resource)]
-struct ShowHints;
+struct BestMove {
+    board: Board,
+    mov: Move,
+}
 
-fn update_hints(_: On<ToggleHints>, mut commands: Commands, show_hints: Option<Res<ShowHints>>) {
-    if show_hints.is_none() {
-        commands.insert_resource(ShowHints);
-    } else {
-        commands.remove_resource::<ShowHints>();
-    }
+/// chance of winning after a move, shown next to its hint
+#[derive(Component)]
+struct ChanceLabel;
+
+/// number of best moves shown in the current game
+#[derive(Default, Resource)]
+pub struct HintCount(pub u32);
+
+/// hints are not shown in zen mode, whatever the level
+fn hints_are(level: HintLevel) -> impl FnMut(Res<Settings>) -> bool + Clone {
+    move |settings: Res<Settings>| !settings.zen && settings.hints == level
+}
+
+fn cycle_hints(_: On<ToggleHints>, mut settings: ResMut<Settings>) {
+    settings.hints = settings.hints.next();
 }
 
+/// on every board, those of a race as well
 fn draw_possible_moves(
     mut painter: ShapePainter,
-    board: Res<CurrentBoard>,
-    feasible: Res<FeasibleConstellations>,
+    boards: Query<(&CurrentBoard, &GlobalTransform, Has<MainBoard>)>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    good_moves: GoodMoves,
 ) {
-    let feasible = &feasible.0;
-    for y in 0..Board::SIZE {
-        for x in 0..Board::SIZE {
-            for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
-                if !board.0.occupied((y, x)) {
-                    continue;
-                }
-                if let Some(mov) = board.0.get_legal_move((y, x), dir) {
-                    let start = BoardPosition::from(mov.pos).to_world_space();
-                    let start = Vec3::from((start, MARKER_POS));
-                    let target = BoardPosition::from(mov.target).to_world_space();
-                    let target = Vec3::from((target, MARKER_POS));
-                    painter.set_color(if feasible.contains(&board.0.mov(mov).normalize()) {
-                        Color::srgba(0., 1., 0., 1.)
-                    } else {
-                        Color::srgba(1., 0., 0., 1.)
-                    });
-                    painter.set_translation(Vec3::new(0., 0., 0.1));
-                    painter.thickness_type = ThicknessType::World;
-                    painter.thickness = 0.075;
-                    painter.line(start, start + (target - start) * 0.2);
-                    painter.set_translation(start.xyz());
-                    painter.circle(0.1);
-                }
+    for (board, transform, main) in &boards {
+        let offset = transform.translation().with_z(0.);
+        draw_board_moves(
+            &mut painter,
+            board.0,
+            offset,
+            main,
+            &settings,
+            &theme,
+            &good_moves,
+        );
+    }
+}
+
+fn draw_board_moves(
+    painter: &mut ShapePainter,
+    board: Board,
+    offset: Vec3,
+    main: bool,
+    settings: &Settings,
+    theme: &Theme,
+    good_moves: &GoodMoves,
+) {
+    let chance = |mov: Move| {
+        let chances = good_moves.chances.as_ref();
+        let chances = chances.filter(|_| !good_moves.challenged(main))?;
+        Some(*chances.0.get(&board.mov(mov).normalize()).unwrap_or(&0.))
+    };
+    // relative to the best move, as chances differ by orders of magnitude
+    let best = board
+        .get_legal_moves()
+        .into_iter()
+        .filter_map(chance)
+        .fold(0., f64::max);
+    for pos in board.pegs() {
+        for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
+            if let Some(mov) = board.get_legal_move(pos, dir) {
+                let start = BoardPosition::from(mov.pos).to_world_space();
+                let start = offset + Vec3::from((start, MARKER_POS));
+                let target = BoardPosition::from(mov.target).to_world_space();
+                let target = offset + Vec3::from((target, MARKER_POS));
+                let feasible = good_moves.feasible(board, mov, main);
+                painter.set_color(match chance(mov) {
+                    Some(p) if feasible && best > 0. && settings.hints == HintLevel::Heatmap => {
+                        theme.chance((p / best) as f32)
+                    }
+                    _ if feasible => theme.good,
+                    _ => theme.bad,
+                });
+                painter.set_translation(Vec3::new(0., 0., 0.1));
+                painter.thickness_type = ThicknessType::World;
+                painter.thickness = 0.075;
+                painter.line(start, start + (target - start) * 0.2);
+                painter.set_translation(start.xyz());
+                painter.circle(0.1);
             }
         }
     }
 }
+
+/// rings on the holes the selected peg can jump to,
+/// green ones lead to feasible constellations if hints are on
+fn draw_landing_holes(
+    mut painter: ShapePainter,
+    selected: Query<(&BoardPosition, &ChildOf), With<Selected>>,
+    boards: Query<(&CurrentBoard, &GlobalTransform, Has<MainBoard>)>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    good_moves: GoodMoves,
+) {
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.04;
+    for (&pos, board) in &selected {
+        let Ok((board, transform, main)) = boards.get(board.parent()) else {
+            continue;
+        };
+        let offset = transform.translation().xy();
+        for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
+            let Some(mov) = board.0.get_legal_move(pos.into(), dir) else {
+                continue;
+            };
+            let feasible = settings.hints != HintLevel::Off
+                && !settings.zen
+                && good_moves.feasible(board.0, mov, main);
+            painter.set_color(if feasible {
+                theme.good.with_alpha(0.8)
+            } else {
+                Color::srgba(1., 1., 1., 0.4)
+            });
+            let target = offset + BoardPosition::from(mov.target).to_world_space();
+            painter.set_translation((target, MARKER_POS).into());
+            painter.circle(HOLE_RADIUS);
+        }
+    }
+}
+
+fn update_chance_labels(
+    mut commands: Commands,
+    labels: Query<Entity, With<ChanceLabel>>,
+    asset_server: Res<AssetServer>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    chances: Option<Res<RandomMoveChances>>,
+    settings: Res<Settings>,
+    challenge: Res<Challenge>,
+) {
+    for label in labels {
+        commands.entity(label).despawn();
+    }
+    // the chances are those of ending in the center
+    let Some(chances) = chances.filter(|_| challenge.0.is_none()) else {
+        return;
+    };
+    if settings.hints != HintLevel::Heatmap || settings.zen {
+        return;
+    }
+    let font = text_font(&asset_server, 50.);
+    for mov in board.0.get_legal_moves() {
+        let p = *chances.0.get(&board.0.mov(mov).normalize()).unwrap_or(&0.);
+        let text = if p > 0. {
+            format!("1/{:.0}", 1. / p)
+        } else {
+            "0".to_string()
+        };
+        let start = BoardPosition::from(mov.pos).to_world_space();
+        let target = BoardPosition::from(mov.target).to_world_space();
+        // next to the arrow, above the pegs
+        let pos = start + (target - start) * 0.2 + (target - start).perp() * 0.1;
+        commands.spawn((
+            ChanceLabel,
+            Text2d::new(text),
+            Transform::from_translation((pos, 1.5).into()).with_scale(Vec3::splat(0.003)),
+            font.clone(),
+            TextColor::WHITE,
+        ));
+    }
+}
+
+/// the feasible move with the best chance of winning by moving at random
+/// afterwards (just feasible, while the chances are still being calculated)
+fn find_best_move(
+    board: Board,
+    feasible: &FeasibleConstellations,
+    chances: Option<&RandomMoveChances>,
+) -> Option<Move> {
+    let chance = |mov: &Move| {
+        let next = board.mov(*mov).normalize();
+        chances.and_then(|c| c.0.get(&next)).copied().unwrap_or(0.)
+    };
+    board
+        .get_legal_moves()
+        .into_iter()
+        .filter(|m| feasible.0.contains(&board.mov(*m).normalize()))
+        .max_by(|a, b| chance(a).total_cmp(&chance(b)))
+}
+
+/// while the feasible constellations are being calculated: whether `board`
+/// is in the class of the center and not stuck, which every winnable board is
+fn plausible(board: Board) -> bool {
+    board.position_class() == Board::solved().position_class()
+        && (board.is_solved() || !board.get_legal_moves().is_empty())
+}
+
+/// what it takes to find a good move
+#[derive(SystemParam)]
+struct GoodMoves<'w> {
+    feasible: Option<Res<'w, FeasibleConstellations>>,
+    chances: Option<Res<'w, RandomMoveChances>>,
+    challenge: Res<'w, Challenge>,
+    winnable: Option<Res<'w, WinnableMoves>>,
+}
+
+impl GoodMoves<'_> {
+    /// challenges are only played on the single player board
+    fn challenged(&self, main: bool) -> bool {
+        main && self.challenge.0.is_some()
+    }
+
+    /// whether the game can still be won after `mov`,
+    /// the feasible constellations all lead to the center
+    fn feasible(&self, board: Board, mov: Move, main: bool) -> bool {
+        match self.challenge.0 {
+            Some(_) if main => self
+                .winnable
+                .as_ref()
+                .is_some_and(|w| w.contains(board, mov)),
+            _ => match &self.feasible {
+                Some(feasible) => feasible.0.contains(&board.mov(mov).normalize()),
+                None => plausible(board.mov(mov)),
+            },
+        }
+    }
+
+    /// the best move or `None`, while the moves are still being looked at
+    fn best(&self, board: Board) -> Option<Option<Move>> {
+        match self.challenge.0 {
+            Some(_) => self.winnable.as_ref().map(|w| w.first(board)),
+            None => self
+                .feasible
+                .as_ref()
+                .map(|feasible| find_best_move(board, feasible, self.chances.as_deref())),
+        }
+    }
+}
+
+fn show_best_move(
+    _: On<ShowBestMove>,
+    mut commands: Commands,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    good_moves: GoodMoves,
+    mut hint_count: ResMut<HintCount>,
+) {
+    let Some(mov) = good_moves.best(board.0) else {
+        info!("still looking for good moves ...");
+        return;
+    };
+    let Some(mov) = mov else {
+        info!("there is no good move left");
+        return;
+    };
+    commands.insert_resource(BestMove {
+        board: board.0,
+        mov,
+    });
+    hint_count.0 += 1;
+    commands.trigger(UpdateStats);
+}
+
+/// keeps showing the best move, without counting it as a hint
+fn update_best_move(
+    mut commands: Commands,
+    best_move: Option<Res<BestMove>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    good_moves: GoodMoves,
+) {
+    if best_move.is_some_and(|best_move| best_move.board == board.0) {
+        return;
+    }
+    if let Some(Some(mov)) = good_moves.best(board.0) {
+        commands.insert_resource(BestMove {
+            board: board.0,
+            mov,
+        });
+    }
+}
+
+/// a move shown on request is dropped along with the hints (or zen mode),
+/// and when leaving the game. with best move hints it is found again
+fn hide_best_move(mut commands: Commands) {
+    commands.remove_resource::<BestMove>();
+}
+
+fn reset_hint_count(
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    mut hint_count: ResMut<HintCount>,
+    mut commands: Commands,
+) {
+    if solution.0.is_empty() && hint_count.0 > 0 {
+        hint_count.0 = 0;
+        commands.trigger(UpdateStats);
+    }
+}
+
+/// pulsing arc from the peg over the skipped one to the target
+fn draw_best_move(
+    mut commands: Commands,
+    mut painter: ShapePainter,
+    best_move: Res<BestMove>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    if board.0 != best_move.board {
+        commands.remove_resource::<BestMove>();
+        return;
+    }
+    let world = |pos| BoardPosition::from(pos).to_world_space();
+    let mov = best_move.mov;
+    let (start, center, target) = (world(mov.pos), world(mov.skip), world(mov.target));
+    let radius = start - center;
+    let bulge = radius.perp() * 0.6;
+    let alpha = 0.6 + 0.4 * (time.elapsed_secs() * 4.).sin();
+    painter.set_color(Color::srgba(1., 0.8, 0., alpha));
+    painter.set_translation(Vec3::new(0., 0., 0.1));
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.075;
+    const SEGMENTS: usize = 16;
+    let point = |i| {
+        let t = i as f32 / SEGMENTS as f32 * PI;
+        Vec3::from((center + radius * t.cos() + bulge * t.sin(), MARKER_POS))
+    };
+    for i in 0..SEGMENTS {
+        painter.line(point(i), point(i + 1));
+    }
+    painter.set_translation(Vec3::from((target, MARKER_POS)));
+    painter.circle(0.1);
+    // keep pulsing
+    request_redraw.write(RequestRedraw);
+}
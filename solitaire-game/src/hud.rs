@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    winit::{UpdateMode, WinitSettings},
+};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    game_state::{AppState, GameTime},
+    menu::text_font,
+    race::RaceClock,
+    settings::Settings,
+    window::idle_settings,
+};
+
+/// elapsed time, moves and captures left above the board
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_hud);
+        app.add_systems(Update, (show_hud, update_hud, tick_every_second));
+    }
+}
+
+#[derive(Component)]
+struct Hud;
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Hud,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::default(),
+            text_font(&asset_server, 30.),
+            TextColor::WHITE,
+        )],
+    ));
+}
+
+fn show_hud(
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut hud: Single<&mut Visibility, With<Hud>>,
+) {
+    let in_game = matches!(
+        app_state.get(),
+        AppState::Playing | AppState::Paused | AppState::GameOver
+    );
+    hud.set_if_neq(if settings.hud && in_game {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
+
+fn update_hud(
+    hud: Single<&Children, With<Hud>>,
+    mut texts: Query<&mut Text>,
+    game_time: Res<GameTime>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    let secs = game_time.0.as_secs();
+    // every game ends with a single peg
+    let captures_left = board.0.count_pegs().saturating_sub(1);
+    let text = format!(
+        "{}:{:02}  ·  {} moves  ·  {captures_left} captures left",
+        secs / 60,
+        secs % 60,
+        solution.0.len()
+    );
+    for &child in *hud {
+        if let Ok(mut hud_text) = texts.get_mut(child) {
+            // only changes are laid out again
+            if hud_text.0 != text {
+                hud_text.0 = text.clone();
+            }
+        }
+    }
+}
+
+/// the app only wakes up every few seconds by itself,
+/// which is too slow for a running clock, the one of a race as well
+fn tick_every_second(
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    race: Option<Res<RaceClock>>,
+    mut winit_settings: ResMut<WinitSettings>,
+) {
+    let playing = settings.hud && *app_state.get() == AppState::Playing && !solution.0.is_empty();
+    let running = playing || race.is_some();
+    let focused_mode = if !running {
+        idle_settings(settings.low_power).focused_mode
+    } else if settings.low_power {
+        UpdateMode::reactive_low_power(Duration::from_secs(1))
+    } else {
+        UpdateMode::reactive(Duration::from_secs(1))
+    };
+    if winit_settings.focused_mode != focused_mode {
+        winit_settings.focused_mode = focused_mode;
+    }
+}
@@ -5,9 +5,14 @@ use bevy::{
     winit::{EventLoopProxyWrapper, WinitUserEvent},
 };
 
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Coord, Dir, Pos};
+
 use crate::{
-    Selected,
-    board::{BoardPosition, Peg},
+    BoardCamera, CurrentBoard, MainBoard, Selected,
+    board::{BoardPosition, HOLE_RADIUS, MARKER_POS, PEG_RADIUS, Peg},
+    duel::players_turn,
+    game_state::{AppState, in_game},
     viewport_to_world,
 };
 
@@ -18,36 +23,87 @@ impl Plugin for Input {
     fn build(&self, app: &mut App) {
         app.add_systems(
             PreUpdate,
-            grab_peg.run_if(input_just_pressed(MouseButton::Left)),
+            grab_peg
+                .run_if(input_just_pressed(MouseButton::Left))
+                .run_if(dragging.and(players_turn)),
+        );
+        app.add_systems(
+            PreUpdate,
+            release_peg
+                .run_if(input_just_released(MouseButton::Left))
+                .run_if(dragging.and(players_turn)),
         );
         app.add_systems(
             PreUpdate,
-            release_peg.run_if(input_just_released(MouseButton::Left)),
+            peg_selection_touch.run_if(dragging.and(players_turn)),
         );
-        app.add_systems(PreUpdate, peg_selection_touch);
-        app.add_systems(PreUpdate, keyboard_input);
+        app.add_systems(PreUpdate, keyboard_input.run_if(in_game.and(players_turn)));
+        app.init_resource::<KeyboardCursor>();
+        app.add_systems(PreUpdate, keyboard_cursor.run_if(in_game));
+        app.add_systems(PostUpdate, draw_keyboard_cursor);
+        app.init_resource::<Hovered>();
+        app.add_systems(PreUpdate, hover.run_if(in_game.and(players_turn)));
+        app.add_systems(PostUpdate, draw_hover.run_if(in_game));
         app.add_systems(PreUpdate, wake_on_touch_release);
     }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone, Copy)]
 pub struct RequestPegMove {
+    pub board: Entity,
     pub src: BoardPosition,
     pub dst: BoardPosition,
 }
 
+/// the boards of a race are played by dragging as well
+fn dragging(state: Res<State<AppState>>) -> bool {
+    *state.get() == AppState::Race || in_game(state)
+}
+
+/// every board and where it is placed
+pub type Boards<'w, 's> = Query<'w, 's, (Entity, &'static GlobalTransform), With<CurrentBoard>>;
+
+/// the board closest to `world_pos` and the position on it
+pub fn board_at(boards: &Boards, world_pos: Vec2) -> Option<(Entity, BoardPosition)> {
+    boards
+        .iter()
+        .map(|(board, transform)| (board, world_pos - transform.translation().xy()))
+        .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()))
+        .map(|(board, local)| (board, BoardPosition::from_world_space(local)))
+}
+
+/// `world_pos` on the board of a peg
+fn on_board(boards: &Boards, board: &ChildOf, world_pos: Vec2) -> Option<BoardPosition> {
+    let (_, transform) = boards.get(board.parent()).ok()?;
+    let local = world_pos - transform.translation().xy();
+    Some(BoardPosition::from_world_space(local))
+}
+
+/// the peg at `pos` of `board`
+fn peg_at(
+    pegs: &Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
+    board: Entity,
+    pos: BoardPosition,
+) -> Option<Entity> {
+    pegs.iter()
+        .find(|(_, p, parent)| **p == pos && parent.parent() == board)
+        .map(|(peg, _, _)| peg)
+}
+
 fn grab_peg(
     mut commands: Commands,
     window: Single<&Window, With<PrimaryWindow>>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    pegs: Query<(Entity, &BoardPosition), With<Peg>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    boards: Boards,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
     let (camera, camera_transform) = *camera_query;
     if let Some(cursor_pos) = window.cursor_position() {
         if let Some(world_pos_cursor) = viewport_to_world(cursor_pos, camera, camera_transform) {
-            let board_pos = BoardPosition::from_world_space(world_pos_cursor.xy());
-            if let Some(peg) = pegs.iter().find(|(_, p)| **p == board_pos).map(|(p, _)| p) {
+            if let Some((board, board_pos)) = board_at(&boards, world_pos_cursor.xy())
+                && let Some(peg) = peg_at(&pegs, board, board_pos)
+            {
                 commands.entity(peg).insert(Selected);
                 request_redraw.write(RequestRedraw);
             }
@@ -58,16 +114,19 @@ fn grab_peg(
 fn release_peg(
     mut commands: Commands,
     window: Single<&Window, With<PrimaryWindow>>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    selected_pegs: Query<(Entity, &BoardPosition), (With<Peg>, With<Selected>)>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    boards: Boards,
+    selected_pegs: Query<(Entity, &BoardPosition, &ChildOf), (With<Peg>, With<Selected>)>,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
     let (camera, camera_transform) = *camera_query;
     if let Some(cursor_pos) = window.cursor_position() {
         if let Some(world_pos_cursor) = viewport_to_world(cursor_pos, camera, camera_transform) {
-            let board_pos = BoardPosition::from_world_space(world_pos_cursor.xy());
-            for (selected_peg, &current_pos) in selected_pegs {
-                move_peg(&mut commands, selected_peg, current_pos, board_pos);
+            for (selected_peg, &current_pos, board) in selected_pegs {
+                if let Some(board_pos) = on_board(&boards, board, world_pos_cursor.xy()) {
+                    let board = board.parent();
+                    move_peg(&mut commands, board, selected_peg, current_pos, *board_pos);
+                }
             }
             request_redraw.write(RequestRedraw);
         };
@@ -77,18 +136,19 @@ fn release_peg(
 fn peg_selection_touch(
     mut commands: Commands,
     touches: Res<Touches>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    pegs: Query<(Entity, &BoardPosition), With<Peg>>,
-    selected_pegs: Query<(Entity, &BoardPosition), (With<Peg>, With<Selected>)>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    boards: Boards,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
+    selected_pegs: Query<(Entity, &BoardPosition, &ChildOf), (With<Peg>, With<Selected>)>,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
     let (camera, camera_transform) = *camera_query;
     for touch in touches.iter_just_pressed() {
-        if let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform) {
-            let board_pos = BoardPosition::from_world_space(world_pos.xy());
-            if let Some(peg) = pegs.iter().find(|(_, p)| **p == board_pos).map(|(p, _)| p) {
-                commands.entity(peg).insert(Selected);
-            }
+        if let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform)
+            && let Some((board, board_pos)) = board_at(&boards, world_pos.xy())
+            && let Some(peg) = peg_at(&pegs, board, board_pos)
+        {
+            commands.entity(peg).insert(Selected);
         }
         request_redraw.write(RequestRedraw);
     }
@@ -96,34 +156,36 @@ fn peg_selection_touch(
         .iter_just_released()
         .chain(touches.iter_just_canceled())
     {
-        if let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform) {
-            let board_pos = BoardPosition::from_world_space(world_pos.xy());
-            for (selected_peg, &current_pos) in selected_pegs {
-                move_peg(&mut commands, selected_peg, current_pos, board_pos);
+        // two players may hold a peg each, a finger releases the one on its board
+        if let Some(world_pos) = viewport_to_world(touch.position(), camera, camera_transform)
+            && let Some((board, board_pos)) = board_at(&boards, world_pos.xy())
+        {
+            for (selected_peg, &current_pos, parent) in selected_pegs {
+                if parent.parent() == board {
+                    move_peg(&mut commands, board, selected_peg, current_pos, *board_pos);
+                }
             }
         }
         request_redraw.write(RequestRedraw);
     }
 }
 
-fn move_peg(commands: &mut Commands, selected: Entity, src: BoardPosition, dst: BoardPosition) {
-    let diff = dst - src;
-    let normalized = if diff.x.abs() > diff.y.abs() {
-        BoardPosition {
-            x: diff.x.signum(),
-            y: 0,
-        }
-    } else if diff.y.abs() > diff.x.abs() {
-        BoardPosition {
-            x: 0,
-            y: diff.y.signum(),
-        }
-    } else {
-        BoardPosition { x: 0, y: 0 }
+fn move_peg(
+    commands: &mut Commands,
+    board: Entity,
+    selected: Entity,
+    src: BoardPosition,
+    dst: Coord,
+) {
+    // pegs always jump two holes along the axis the peg was dragged
+    let dst = match (dst - *src).dir() {
+        Some(dir) => *src + Coord::from(dir) * 2,
+        None => *src,
     };
     commands.trigger(RequestPegMove {
+        board,
         src,
-        dst: src + normalized * 2,
+        dst: dst.into(),
     });
     commands.entity(selected).remove::<Selected>();
 }
@@ -131,10 +193,12 @@ fn move_peg(commands: &mut Commands, selected: Entity, src: BoardPosition, dst:
 fn keyboard_input(
     mut commands: Commands,
     window: Single<&Window, With<PrimaryWindow>>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
-    pegs: Query<(Entity, &BoardPosition), With<Peg>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    main: Single<Entity, With<MainBoard>>,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
+    let board = *main;
     let (camera, transform) = *camera_query;
     let Some(cursor_pos) = window.cursor_position() else {
         return;
@@ -142,29 +206,156 @@ fn keyboard_input(
     let Some(world_pos_cursor) = viewport_to_world(cursor_pos, camera, transform) else {
         return;
     };
-    let board_pos = BoardPosition::from_world_space(world_pos_cursor.xy());
-    let Some((peg, pos)) = pegs
-        .iter()
-        .find(|(_, p)| **p == board_pos)
-        .map(|(peg, pos)| (peg, *pos))
-    else {
+    let pos = BoardPosition::from_world_space(world_pos_cursor.xy());
+    let Some(peg) = peg_at(&pegs, board, pos) else {
         return;
     };
 
-    if keys.just_pressed(KeyCode::KeyW) || keys.just_pressed(KeyCode::ArrowUp) {
-        move_peg(&mut commands, peg, pos, pos + BoardPosition { x: 0, y: -2 });
+    if keys.just_pressed(KeyCode::KeyW) {
+        move_peg(
+            &mut commands,
+            board,
+            peg,
+            pos,
+            *pos + Coord::from(Dir::North) * 2,
+        );
     }
-    if keys.just_pressed(KeyCode::KeyS) || keys.just_pressed(KeyCode::ArrowDown) {
-        move_peg(&mut commands, peg, pos, pos + BoardPosition { x: 0, y: 2 });
+    if keys.just_pressed(KeyCode::KeyS) {
+        move_peg(
+            &mut commands,
+            board,
+            peg,
+            pos,
+            *pos + Coord::from(Dir::South) * 2,
+        );
     }
-    if keys.just_pressed(KeyCode::KeyA) || keys.just_pressed(KeyCode::ArrowLeft) {
-        move_peg(&mut commands, peg, pos, pos + BoardPosition { x: -2, y: 0 });
+    if keys.just_pressed(KeyCode::KeyA) {
+        move_peg(
+            &mut commands,
+            board,
+            peg,
+            pos,
+            *pos + Coord::from(Dir::West) * 2,
+        );
     }
-    if keys.just_pressed(KeyCode::KeyD) || keys.just_pressed(KeyCode::ArrowRight) {
-        move_peg(&mut commands, peg, pos, pos + BoardPosition { x: 2, y: 0 });
+    if keys.just_pressed(KeyCode::KeyD) {
+        move_peg(
+            &mut commands,
+            board,
+            peg,
+            pos,
+            *pos + Coord::from(Dir::East) * 2,
+        );
     }
 }
 
+/// hole selected with the arrow keys, shown after the first key press
+#[derive(Default, Resource)]
+struct KeyboardCursor(Option<Coord>);
+
+/// arrows move the cursor, enter picks up the peg under it
+/// or moves the picked up peg towards it
+fn keyboard_cursor(
+    mut commands: Commands,
+    mut cursor: ResMut<KeyboardCursor>,
+    main: Single<Entity, With<MainBoard>>,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf, Has<Selected>), With<Peg>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let board = *main;
+    let dirs = [
+        (KeyCode::ArrowUp, Dir::North),
+        (KeyCode::ArrowDown, Dir::South),
+        (KeyCode::ArrowLeft, Dir::West),
+        (KeyCode::ArrowRight, Dir::East),
+    ];
+    for (key, dir) in dirs {
+        if keys.just_pressed(key) {
+            let center = Coord::from(Pos::new(Board::SIZE / 2, Board::SIZE / 2));
+            let mut pos = cursor.0.unwrap_or(center);
+            // skip the corners, stay on the board
+            let mut next = pos + dir.into();
+            while (0..Board::SIZE).contains(&next.x) && (0..Board::SIZE).contains(&next.y) {
+                if Board::inbounds(next.into()) {
+                    pos = next;
+                    break;
+                }
+                next = next + dir.into();
+            }
+            cursor.0 = Some(pos);
+        }
+    }
+
+    let Some(pos) = cursor.0 else {
+        return;
+    };
+    if !keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+        return;
+    }
+    let selected = pegs
+        .iter()
+        .find(|(_, _, parent, selected)| *selected && parent.parent() == board);
+    if let Some((selected, &src, _, _)) = selected {
+        move_peg(&mut commands, board, selected, src, pos);
+    } else if let Some((peg, _, _, _)) = pegs
+        .iter()
+        .find(|(_, p, parent, _)| ***p == pos && parent.parent() == board)
+    {
+        commands.entity(peg).insert(Selected);
+    }
+}
+
+fn draw_keyboard_cursor(mut painter: ShapePainter, cursor: Res<KeyboardCursor>) {
+    let Some(pos) = cursor.0 else {
+        return;
+    };
+    painter.set_translation((BoardPosition(pos).to_world_space(), MARKER_POS).into());
+    painter.set_color(Color::WHITE);
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.05;
+    painter.circle(PEG_RADIUS * 1.25);
+}
+
+/// the hole under the mouse, with or without a peg
+#[derive(Default, Resource, PartialEq)]
+struct Hovered(Option<BoardPosition>);
+
+fn hover(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    mut hovered: ResMut<Hovered>,
+) {
+    let (camera, camera_transform) = *camera_query;
+    let board_pos = window
+        .cursor_position()
+        .and_then(|cursor_pos| viewport_to_world(cursor_pos, camera, camera_transform))
+        .map(|world_pos| BoardPosition::from_world_space(world_pos.xy()))
+        .filter(|&board_pos| Board::inbounds(board_pos.into()));
+    hovered.set_if_neq(Hovered(board_pos));
+}
+
+/// a faint ring, the keyboard cursor stands out more
+fn draw_hover(
+    mut painter: ShapePainter,
+    hovered: Res<Hovered>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+) {
+    let Some(pos) = hovered.0 else {
+        return;
+    };
+    painter.set_translation((pos.to_world_space(), MARKER_POS).into());
+    painter.set_color(Color::WHITE.with_alpha(0.4));
+    painter.hollow = true;
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.03;
+    painter.circle(if board.0.occupied(pos.into()) {
+        PEG_RADIUS * 1.1
+    } else {
+        HOLE_RADIUS
+    });
+}
+
 #[derive(Default, Resource)]
 struct NeedsWake;
 
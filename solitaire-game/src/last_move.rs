@@ -0,0 +1,87 @@
+use bevy::{prelude::*, window::RequestRedraw};
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Move};
+
+use crate::{
+    CurrentBoard, MainBoard, MoveEvent,
+    board::{BoardPosition, PEG_POS, PEG_RADIUS, flat},
+    theme::Theme,
+};
+
+/// an arrow along the last move and a cross on the captured peg, fading out,
+/// to follow the solver or a replay as well
+pub struct LastMovePlugin;
+
+impl Plugin for LastMovePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_last_move);
+        app.add_systems(
+            PostUpdate,
+            draw_last_move.run_if(resource_exists::<LastMove>.and(flat)),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct LastMove {
+    mov: Move,
+    /// the board after the move, it is stale once that changes
+    board: Board,
+    timer: Timer,
+}
+
+const FADE_SECS: f32 = 1.5;
+
+/// above the pegs, the arrow ends at the moved one
+const ARROW_POS: f32 = PEG_POS + 0.05;
+
+fn show_last_move(
+    move_event: On<MoveEvent>,
+    mut commands: Commands,
+    main: Single<(Entity, &CurrentBoard), With<MainBoard>>,
+) {
+    let (main, board) = *main;
+    if move_event.board != main {
+        return;
+    }
+    commands.insert_resource(LastMove {
+        mov: move_event.mov,
+        board: board.0,
+        timer: Timer::from_seconds(FADE_SECS, TimerMode::Once),
+    });
+}
+
+fn draw_last_move(
+    mut commands: Commands,
+    mut painter: ShapePainter,
+    mut last_move: ResMut<LastMove>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    // undone, or a new game
+    if last_move.board != board.0 || last_move.timer.tick(time.delta()).is_finished() {
+        commands.remove_resource::<LastMove>();
+        return;
+    }
+    let world = |pos| BoardPosition::from(pos).to_world_space();
+    let at = |pos: Vec2| Vec3::from((pos, ARROW_POS));
+    let mov = last_move.mov;
+    let (start, skip, target) = (world(mov.pos), world(mov.skip), world(mov.target));
+    let dir = (target - start).normalize();
+    let tip = target - dir * PEG_RADIUS;
+    // visible for most of the time
+    let alpha = EaseFunction::QuadraticOut.sample_clamped(last_move.timer.fraction_remaining());
+    painter.set_color(theme.button_foreground.with_alpha(0.8 * alpha));
+    painter.thickness_type = ThicknessType::World;
+    painter.thickness = 0.06;
+    painter.line(at(start), at(tip));
+    for side in [dir.perp(), -dir.perp()] {
+        painter.line(at(tip), at(tip + (side - dir) * 0.15));
+    }
+    for diagonal in [Vec2::ONE, Vec2::new(1., -1.)] {
+        painter.line(at(skip - diagonal * 0.12), at(skip + diagonal * 0.12));
+    }
+    request_redraw.write(RequestRedraw);
+}
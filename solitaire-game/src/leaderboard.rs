@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+    window::RequestRedraw,
+};
+use futures_lite::future::{self, block_on};
+use serde::{Deserialize, Serialize};
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    auto_solve::AutoSolve,
+    board::NewGame,
+    challenge::Challenge,
+    duel::Duel,
+    game_state::{AppState, GameState, GameTime},
+    hints::HintCount,
+    menu::spawn_dialog,
+    settings::Settings,
+};
+
+// Wins are submitted to an online leaderboard if the player opted in (see
+// `Settings::leaderboard`). A result only holds the time, the number of moves
+// and hints and the variant played, nothing that tells players apart.
+//
+// The server speaks JSON:
+// - `POST {LEADERBOARD_URL}/results` takes a `Submission`
+// - `GET {LEADERBOARD_URL}/top/{variant}` answers with the `Top` results
+//
+// There is no default server, its address is set at build time through
+// `LEADERBOARD_URL`. Without it, nothing is submitted and the leaderboard
+// screen says so.
+
+const LEADERBOARD_URL: Option<&str> = option_env!("LEADERBOARD_URL");
+
+/// where the last peg ends up without a challenge
+const CENTER: Pos = Pos::new(3, 3);
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Submitted>();
+        app.add_observer(toggle_leaderboard);
+        app.add_observer(reset_submitted);
+        app.add_systems(
+            OnEnter(GameState::Won),
+            submit_win.run_if(has_server.and(opted_in).and(ranked).and(not_submitted)),
+        );
+        app.add_systems(
+            Update,
+            (
+                show_top.run_if(resource_exists::<TopTask>),
+                close_leaderboard,
+            ),
+        );
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ToggleLeaderboard;
+
+/// one win
+#[derive(Serialize)]
+struct Submission {
+    variant: String,
+    time_ms: u64,
+    moves: usize,
+    hints: u32,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    time_ms: u64,
+    moves: usize,
+    hints: u32,
+}
+
+/// the best results of a variant
+#[derive(Deserialize)]
+struct Top {
+    fastest: Vec<Entry>,
+    fewest_hints: Vec<Entry>,
+}
+
+/// whether the game in progress has been submitted,
+/// so undoing and redoing its last move does not submit it again
+#[derive(Default, Resource)]
+struct Submitted(bool);
+
+#[derive(Resource)]
+struct TopTask(Task<Result<Top, String>>);
+
+#[derive(Component)]
+struct LeaderboardScreen;
+
+/// "loading" until the results arrive
+#[derive(Component)]
+struct TopText;
+
+#[derive(Component, Clone, Copy)]
+struct CloseButton;
+
+/// the start and the hole of the last peg, fit for a url
+fn variant(start: Board, target: Pos) -> String {
+    format!("{:x}-{}", start.to_compressed_repr(), target.name())
+}
+
+fn has_server() -> bool {
+    LEADERBOARD_URL.is_some()
+}
+
+fn opted_in(settings: Res<Settings>) -> bool {
+    settings.leaderboard
+}
+
+/// played alone, not by the solver or in a replay
+fn ranked(
+    app_state: Res<State<AppState>>,
+    auto_solve: Option<Res<AutoSolve>>,
+    duel: Option<Res<Duel>>,
+) -> bool {
+    *app_state.get() == AppState::Playing && auto_solve.is_none() && duel.is_none()
+}
+
+fn not_submitted(submitted: Res<Submitted>) -> bool {
+    !submitted.0
+}
+
+fn reset_submitted(_: On<NewGame>, mut submitted: ResMut<Submitted>) {
+    submitted.0 = false;
+}
+
+fn submit_win(
+    mut submitted: ResMut<Submitted>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    game_time: Res<GameTime>,
+    hint_count: Res<HintCount>,
+) {
+    let target = challenge.0.unwrap_or(CENTER);
+    let submission = Submission {
+        variant: variant(solution.start(board.0), target),
+        time_ms: game_time.0.as_millis() as u64,
+        moves: solution.0.len(),
+        hints: hint_count.0,
+    };
+    submitted.0 = true;
+    let Some(url) = LEADERBOARD_URL else {
+        return;
+    };
+    let request = match ehttp::Request::json(format!("{url}/results"), &submission) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("could not encode the result: {e}");
+            return;
+        }
+    };
+    IoTaskPool::get()
+        .spawn(async move {
+            match ehttp::fetch_async(request).await {
+                Ok(response) if response.ok => info!("submitted the win to the leaderboard"),
+                Ok(response) => warn!("leaderboard rejected the win: {}", response.status_text),
+                Err(e) => warn!("could not reach the leaderboard: {e}"),
+            }
+        })
+        .detach();
+}
+
+async fn fetch_top(url: &str, variant: String) -> Result<Top, String> {
+    let request = ehttp::Request::get(format!("{url}/top/{variant}"));
+    let response = ehttp::fetch_async(request).await?;
+    if !response.ok {
+        return Err(format!("{} {}", response.status, response.status_text));
+    }
+    response.json().map_err(|e| e.to_string())
+}
+
+fn toggle_leaderboard(
+    _: On<ToggleLeaderboard>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    screen: Query<Entity, With<LeaderboardScreen>>,
+) {
+    if let Ok(screen) = screen.single() {
+        commands.entity(screen).despawn();
+        commands.remove_resource::<TopTask>();
+        return;
+    }
+    let text = match LEADERBOARD_URL {
+        Some(url) => {
+            // the classic game, all others are too rare to compare
+            let task = IoTaskPool::get().spawn(fetch_top(url, variant(Board::default(), CENTER)));
+            commands.insert_resource(TopTask(task));
+            "loading ..."
+        }
+        None => "this build has no leaderboard server",
+    };
+    spawn_dialog(
+        &mut commands,
+        &asset_server,
+        LeaderboardScreen,
+        "leaderboard",
+        (TopText, Text::new(text)),
+        &[(CloseButton, "close")],
+    );
+}
+
+fn format_entry(entry: &Entry) -> String {
+    let time = Duration::from_millis(entry.time_ms).as_secs();
+    format!(
+        "{}:{:02} in {} moves, {} hints",
+        time / 60,
+        time % 60,
+        entry.moves,
+        entry.hints
+    )
+}
+
+fn show_top(
+    mut commands: Commands,
+    mut task: ResMut<TopTask>,
+    mut text: Single<&mut Text, With<TopText>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let Some(top) = block_on(future::poll_once(&mut task.0)) else {
+        request_redraw.write(RequestRedraw);
+        return;
+    };
+    commands.remove_resource::<TopTask>();
+    text.0 = match top {
+        Ok(top) => {
+            let list = |entries: &[Entry]| -> String {
+                if entries.is_empty() {
+                    return "-\n".into();
+                }
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| format!("{}. {}\n", i + 1, format_entry(entry)))
+                    .collect()
+            };
+            format!(
+                "fastest\n{}\nfewest hints\n{}",
+                list(&top.fastest),
+                list(&top.fewest_hints)
+            )
+        }
+        Err(e) => {
+            warn!("could not fetch the leaderboard: {e}");
+            format!("could not fetch the leaderboard:\n{e}")
+        }
+    };
+}
+
+fn close_leaderboard(
+    mut commands: Commands,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<CloseButton>)>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        commands.trigger(ToggleLeaderboard);
+    }
+}
@@ -4,29 +4,112 @@ use solitaire_solver::Board;
 
 use crate::{
     animation::PegAnimation,
+    assist::AssistPlugin,
+    attract::AttractPlugin,
+    auto_solve::AutoSolvePlugin,
     board::{BoardPlugin, BoardPosition, PEG_RADIUS},
-    buttons::Buttons,
+    buttons::GameButtons,
+    challenge::ChallengePlugin,
+    coordinates::CoordinatesPlugin,
+    dead_position::DeadPositionPlugin,
+    duel::DuelPlugin,
+    editor::EditorPlugin,
+    effects::EffectsPlugin,
+    explore::ExplorePlugin,
+    feedback::MoveFeedback,
     fps_overlay::FpsOverlay,
+    game_state::{AppState, GameStatePlugin},
     hints::HintsPlugin,
+    hud::HudPlugin,
     input::Input,
+    last_move::LastMovePlugin,
+    lifecycle::LifecyclePlugin,
+    loading::LoadingPlugin,
+    menu::MenuPlugin,
+    move_log::MoveLogPlugin,
+    quit::QuitPlugin,
+    race::RacePlugin,
+    replay::ReplayPlugin,
+    save_game::SaveGame,
+    screen_reader::ScreenReaderPlugin,
+    settings::SettingsPlugin,
+    share::SharePlugin,
+    skins::SkinsPlugin,
     solver::Solver,
+    statistics::StatisticsPlugin,
     stats::StatsPlugin,
     status::StatusPlugin,
+    theme::ThemePlugin,
     total_progress::TotalProgressPlugin,
+    tutorial::TutorialPlugin,
+    ui_scale::UiScalePlugin,
+    vacancy::VacancyPlugin,
+    viewport::ViewPortPlugin,
     window::MainWindow,
+    zen::ZenPlugin,
 };
 
 mod animation;
+mod assist;
+mod attract;
+mod auto_solve;
 mod board;
 mod buttons;
+mod challenge;
+mod clipboard;
+#[cfg(any(debug_assertions, feature = "dev"))]
+mod consistency;
+mod coordinates;
+mod dead_position;
+#[cfg(feature = "dev")]
+mod dev_panel;
+mod disk_cache;
+mod duel;
+mod editor;
+mod effects;
+mod explore;
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
+mod feedback;
 mod fps_overlay;
+mod game_state;
 mod hints;
+mod hud;
 mod input;
+mod last_move;
+#[cfg(feature = "leaderboard")]
+mod leaderboard;
+mod lifecycle;
+mod loading;
+mod menu;
+mod move_log;
+#[cfg(not(target_arch = "wasm32"))]
+mod progress_file;
+mod quit;
+mod race;
+mod replay;
+mod save_game;
+#[cfg(feature = "scene_3d")]
+mod scene_3d;
+mod screen_reader;
+mod settings;
+mod share;
+mod skins;
 mod solver;
+mod statistics;
 mod stats;
 mod status;
+mod storage;
+mod theme;
 mod total_progress;
+mod tutorial;
+mod ui_scale;
+mod vacancy;
+mod viewport;
+#[cfg(target_arch = "wasm32")]
+mod web_cache;
 mod window;
+mod zen;
 
 #[bevy_main]
 fn main() {
@@ -39,22 +122,41 @@ pub fn run() {
     app.add_plugins(PegSolitaire);
     #[cfg(not(target_arch = "wasm32"))]
     app.add_plugins(FpsOverlay);
-    app.add_plugins(TotalProgressPlugin);
+    app.add_plugins(SaveGame);
     app.run();
 }
 
-#[derive(Default, Resource)]
-/// represents the currently active board
+/// the position on a board, its holes and pegs are its children
+#[derive(Default, Component)]
+#[require(CurrentSolution, RedoMoves, Transform, Visibility)]
 struct CurrentBoard(Board);
 
+/// the board of the single player game, at the origin
+#[derive(Component)]
+#[require(CurrentBoard)]
+struct MainBoard;
+
+/// whether `T` of the single player board changed, like `resource_changed`
+fn main_changed<T: Component>(changed: Query<(), (With<MainBoard>, Changed<T>)>) -> bool {
+    !changed.is_empty()
+}
+
 #[derive(Component)]
 struct Selected;
 
+/// the camera pegs are picked up and dragged with,
+/// the one of the 3d scene while it is shown
+#[derive(Component)]
+struct BoardCamera;
+
 fn camera_setup(mut commands: Commands) {
-    commands.spawn(Camera2d);
+    commands.spawn((Camera2d, BoardCamera));
 }
 
-fn scale_viewport(mut camera_query: Query<(&mut Projection, &Camera)>) {
+fn scale_viewport(
+    mut camera_query: Query<(&mut Projection, &Camera), With<Camera2d>>,
+    app_state: Res<State<AppState>>,
+) {
     let Ok((mut projection, camera)) = camera_query.single_mut() else {
         return;
     };
@@ -67,9 +169,14 @@ fn scale_viewport(mut camera_query: Query<(&mut Projection, &Camera)>) {
         }
         None => 7.0,
     };
+    // the boards of a race stand side by side
+    let boards = match app_state.get() {
+        AppState::Race => race::PLAYERS as f32,
+        _ => 1.,
+    };
     if let Projection::Orthographic(projection2d) = &mut *projection {
         projection2d.scaling_mode = ScalingMode::AutoMin {
-            min_width: scale,
+            min_width: scale * boards,
             min_height: scale,
         }
     }
@@ -77,13 +184,29 @@ fn scale_viewport(mut camera_query: Query<(&mut Projection, &Camera)>) {
 
 fn update_solution(
     move_event: On<MoveEvent>,
-    mut solution: ResMut<CurrentSolution>,
-    board: Res<CurrentBoard>,
+    mut boards: Query<(
+        &CurrentBoard,
+        &mut CurrentSolution,
+        &mut RedoMoves,
+        Has<MainBoard>,
+    )>,
+    app_state: Res<State<AppState>>,
     mut commands: Commands,
 ) {
+    let Ok((board, mut solution, mut redo, main)) = boards.get_mut(move_event.board) else {
+        return;
+    };
     solution.0.push(move_event.mov);
     solution.1.push(*move_event);
-    if board.0.is_solved() {
+    // redoing (or repeating) the last undone move keeps the others
+    if redo.0.last() == Some(&move_event.mov) {
+        redo.0.pop();
+    } else {
+        redo.0.clear();
+    }
+    // only games from the standard start count as solutions, watching one does not
+    let replay = matches!(app_state.get(), AppState::Replay | AppState::Attract);
+    if main && board.0.is_solved() && solution.start(board.0) == Board::default() && !replay {
         assert!(solution.0.len() == Board::SLOTS - 2);
         commands.trigger(SolutionEvent(solution.0.clone()));
     }
@@ -92,11 +215,27 @@ fn update_solution(
 #[derive(Event)]
 pub struct SolutionEvent(solitaire_solver::Solution);
 
-#[derive(Default, Resource)]
+#[derive(Default, Component)]
 struct CurrentSolution(solitaire_solver::Solution, Vec<MoveEvent>);
 
+impl CurrentSolution {
+    /// the constellation the game started from, given the current `board`
+    fn start(&self, board: Board) -> Board {
+        self.0
+            .moves()
+            .iter()
+            .rev()
+            .fold(board, |board, &mov| board.reverse_mov(mov))
+    }
+}
+
+/// undone moves, the last one is redone first
+#[derive(Default, Component)]
+struct RedoMoves(Vec<solitaire_solver::Move>);
+
 #[derive(Clone, Copy, Debug, Event)]
 struct MoveEvent {
+    board: Entity,
     mov: solitaire_solver::Move,
     moved: Entity,
     skipped: Entity,
@@ -111,29 +250,79 @@ struct PegSolitaire;
 
 impl Plugin for PegSolitaire {
     fn build(&self, app: &mut App) {
-        app.init_resource::<CurrentBoard>();
-        app.init_resource::<CurrentSolution>();
+        // there before anything looks at it
+        app.world_mut().spawn(MainBoard);
 
         app.add_plugins(BoardPlugin);
         app.add_plugins(Solver);
         app.add_plugins(HintsPlugin);
         app.add_plugins(StatsPlugin);
         app.add_plugins(StatusPlugin);
+        app.add_plugins(HudPlugin);
+        app.add_plugins(AssistPlugin);
         app.add_plugins(PegAnimation);
+        app.add_plugins(MoveFeedback);
+        app.add_plugins(EffectsPlugin);
         app.add_plugins(Input);
-        app.add_plugins(Buttons);
+        app.add_plugins(GameButtons);
+        app.add_plugins(TotalProgressPlugin);
+        app.add_plugins(GameStatePlugin);
+        app.add_plugins(AutoSolvePlugin);
+        app.add_plugins(SettingsPlugin);
+        app.add_plugins(ThemePlugin);
+        app.add_plugins(StatisticsPlugin);
+        app.add_plugins(MenuPlugin);
+        app.add_plugins(EditorPlugin);
+        app.add_plugins(VacancyPlugin);
+        app.add_plugins(ChallengePlugin);
+        app.add_plugins(ReplayPlugin);
+        app.add_plugins(SharePlugin);
+        app.add_plugins(ViewPortPlugin);
+        app.add_plugins(TutorialPlugin);
+        app.add_plugins(RacePlugin);
+        app.add_plugins(DuelPlugin);
+        app.add_plugins(LifecyclePlugin);
+        app.add_plugins(LoadingPlugin);
+        app.add_plugins(CoordinatesPlugin);
+        app.add_plugins(LastMovePlugin);
+        app.add_plugins(MoveLogPlugin);
+        app.add_plugins(AttractPlugin);
+        app.add_plugins(ScreenReaderPlugin);
+        app.add_plugins(UiScalePlugin);
+        app.add_plugins(SkinsPlugin);
+        app.add_plugins(ZenPlugin);
+        app.add_plugins(DeadPositionPlugin);
+        app.add_plugins(QuitPlugin);
+        #[cfg(feature = "scene_3d")]
+        app.add_plugins(scene_3d::Scene3dPlugin);
+        #[cfg(feature = "leaderboard")]
+        app.add_plugins(leaderboard::LeaderboardPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(export::ExportPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(progress_file::ProgressFilePlugin);
+        app.add_plugins(ExplorePlugin);
+        #[cfg(feature = "dev")]
+        app.add_plugins(dev_panel::DevPanelPlugin);
+        #[cfg(any(debug_assertions, feature = "dev"))]
+        app.add_plugins(consistency::ConsistencyPlugin);
 
         app.add_observer(update_solution);
         app.add_systems(Startup, (camera_setup, scale_viewport).chain());
         app.add_systems(Update, scale_viewport);
-        app.add_systems(PostUpdate, highlight_selected);
-        app.add_systems(PreUpdate, calc_view_port);
+        app.add_systems(
+            PostUpdate,
+            highlight_selected.after(TransformSystems::Propagate),
+        );
     }
 }
 
-fn highlight_selected(mut painter: ShapePainter, selected: Query<&Transform, With<Selected>>) {
+fn highlight_selected(
+    mut painter: ShapePainter,
+    selected: Query<&GlobalTransform, With<Selected>>,
+) {
     for selected in selected {
-        painter.set_translation(selected.translation - Vec3::Z * 0.1);
+        painter.set_translation(selected.translation() - Vec3::Z * 0.1);
         painter.set_color(Color::WHITE);
         painter.circle(PEG_RADIUS * 1.1);
     }
@@ -150,39 +339,3 @@ fn viewport_to_world(
     let point = ray.get_point(distance);
     Some(point)
 }
-
-#[derive(Resource)]
-pub struct WorldSpaceViewPort {
-    pub top_left: Vec3,
-    pub bottom_left: Vec3,
-    pub top_right: Vec3,
-    pub bottom_right: Vec3,
-}
-
-fn calc_view_port(mut commands: Commands, camera: Single<(&Camera, &GlobalTransform)>) {
-    let (camera, transform) = *camera;
-    if let Some(view_port) = camera.logical_viewport_rect() {
-        let top_left = view_port.min;
-        let bottom_right = view_port.max;
-        let top_right = top_left + Vec2::new(view_port.width(), 0.);
-        let bottom_left = top_left + Vec2::new(0., view_port.height());
-        let Some(top_left) = viewport_to_world(top_left, camera, transform) else {
-            return;
-        };
-        let Some(top_right) = viewport_to_world(top_right, camera, transform) else {
-            return;
-        };
-        let Some(bottom_left) = viewport_to_world(bottom_left, camera, transform) else {
-            return;
-        };
-        let Some(bottom_right) = viewport_to_world(bottom_right, camera, transform) else {
-            return;
-        };
-        commands.insert_resource(WorldSpaceViewPort {
-            top_left,
-            bottom_left,
-            top_right,
-            bottom_right,
-        });
-    }
-}
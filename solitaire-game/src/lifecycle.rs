@@ -0,0 +1,97 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    window::{AppLifecycle, RequestRedraw},
+};
+
+use crate::{auto_solve::AutoSolve, game_state::AppState, settings::Settings};
+
+// Mobile apps are sent to the background at any time and may be killed there
+// without notice. The game is saved by `SaveGame` and paused, and nothing
+// computes or redraws until the app is in the foreground again.
+
+pub struct LifecyclePlugin;
+
+impl Plugin for LifecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SolverPause>();
+        app.add_systems(First, handle_lifecycle);
+    }
+}
+
+/// present while the app is in the background
+#[derive(Resource)]
+pub struct Suspended;
+
+/// run condition for systems that keep the app busy, e.g. by polling tasks
+pub fn awake(suspended: Option<Res<Suspended>>) -> bool {
+    suspended.is_none()
+}
+
+/// holds long calculations of the solver while the app is in the background,
+/// shared with the tasks running them
+#[derive(Resource, Clone, Default)]
+pub struct SolverPause(Arc<AtomicBool>);
+
+impl SolverPause {
+    /// interval in which a paused calculation checks whether it may go on
+    const POLL: Duration = Duration::from_millis(200);
+
+    /// blocks the calling thread while paused,
+    /// called by the solver between two steps of its calculations
+    pub fn wait(&self) {
+        // tasks run on the main thread on the web, which must not sleep
+        if cfg!(target_arch = "wasm32") {
+            return;
+        }
+        while self.0.load(Ordering::Relaxed) {
+            thread::sleep(Self::POLL);
+        }
+    }
+
+    pub fn set(&self, paused: bool) {
+        self.0.store(paused, Ordering::Relaxed);
+    }
+}
+
+fn handle_lifecycle(
+    mut commands: Commands,
+    mut lifecycle: MessageReader<AppLifecycle>,
+    solver_pause: Res<SolverPause>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    for lifecycle in lifecycle.read() {
+        match lifecycle {
+            AppLifecycle::WillSuspend => {
+                info!("suspending");
+                commands.insert_resource(Suspended);
+                solver_pause.set(true);
+                // the solver would go on playing unseen
+                commands.remove_resource::<AutoSolve>();
+                if *app_state.get() == AppState::Playing {
+                    next_state.set(AppState::Paused);
+                }
+            }
+            AppLifecycle::WillResume => {
+                info!("resuming");
+                commands.remove_resource::<Suspended>();
+                // the solver sleeps on in zen mode
+                solver_pause.set(settings.zen);
+                // the game stays paused until the player is back
+                request_redraw.write(RequestRedraw);
+            }
+            AppLifecycle::Idle | AppLifecycle::Running | AppLifecycle::Suspended => {}
+        }
+    }
+}
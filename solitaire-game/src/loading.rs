@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use crate::{
+    menu::text_font,
+    solver::{FeasibleConstellations, FeasibleProgress},
+};
+
+/// a line at the bottom of the screen while the winnable positions are not
+/// known yet, until then hints only rule out what can never be won
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_loading_text);
+        app.add_systems(
+            Update,
+            (
+                update_loading_text.run_if(not(resource_exists::<FeasibleConstellations>)),
+                despawn_loading_text.run_if(resource_added::<FeasibleConstellations>),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct LoadingText;
+
+fn spawn_loading_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        LoadingText,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::new("loading winnable positions ..."),
+            text_font(&asset_server, 20.),
+            TextColor(Color::WHITE.with_alpha(0.7)),
+        )],
+    ));
+}
+
+fn update_loading_text(
+    progress: Res<FeasibleProgress>,
+    loading: Single<&Children, With<LoadingText>>,
+    mut texts: Query<&mut Text>,
+) {
+    // nothing is reported while loading from the cache
+    let Some(step) = *progress.0.lock().unwrap() else {
+        return;
+    };
+    let percent = 100 * step.done / step.total.max(1);
+    let text = format!("calculating winnable positions: {} {percent}%", step.stage);
+    for &child in *loading {
+        if let Ok(mut loading_text) = texts.get_mut(child)
+            && loading_text.0 != text
+        {
+            loading_text.0 = text.clone();
+        }
+    }
+}
+
+fn despawn_loading_text(mut commands: Commands, loading: Query<Entity, With<LoadingText>>) {
+    for loading in loading {
+        commands.entity(loading).despawn();
+    }
+}
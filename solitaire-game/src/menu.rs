@@ -0,0 +1,350 @@
+use bevy::prelude::*;
+use rand::{RngExt, seq::IndexedRandom};
+use solitaire_solver::Board;
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::NewGame,
+    buttons::{ResetEvent, UndoEvent},
+    challenge::{Challenge, StartChallenge},
+    duel::Duel,
+    explore::ToggleExplore,
+    game_state::AppState,
+    settings::{Settings, ToggleSettings},
+    share::{Import, Share},
+    solver::FeasibleByPegs,
+    statistics::ToggleStatistics,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{export::ExportGif, quit::RequestQuit};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Menu), show_main_menu);
+        app.add_systems(OnEnter(AppState::Paused), show_pause_menu);
+        app.add_systems(Update, (handle_menu_buttons, toggle_pause));
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub enum MenuButton {
+    Continue,
+    NewGame,
+    Challenge,
+    Practice,
+    EditBoard,
+    Replays,
+    Import,
+    Share,
+    #[cfg(not(target_arch = "wasm32"))]
+    ExportGif,
+    Explore,
+    Settings,
+    Statistics,
+    Tutorial,
+    Race,
+    Duel,
+    DuelComputer,
+    #[cfg(not(target_arch = "wasm32"))]
+    Quit,
+    Resume,
+    MainMenu,
+    Undo,
+    StartOver,
+}
+
+impl MenuButton {
+    fn label(self) -> &'static str {
+        match self {
+            MenuButton::Continue => "continue",
+            MenuButton::NewGame => "new game",
+            MenuButton::Challenge => "challenge",
+            MenuButton::Practice => "practice",
+            MenuButton::EditBoard => "edit board",
+            MenuButton::Replays => "replays",
+            MenuButton::Import => "import",
+            MenuButton::Share => "share",
+            #[cfg(not(target_arch = "wasm32"))]
+            MenuButton::ExportGif => "gif",
+            MenuButton::Explore => "explore",
+            MenuButton::Settings => "settings",
+            MenuButton::Statistics => "statistics",
+            MenuButton::Tutorial => "tutorial",
+            MenuButton::Race => "race",
+            MenuButton::Duel => "duel",
+            MenuButton::DuelComputer => "duel vs computer",
+            #[cfg(not(target_arch = "wasm32"))]
+            MenuButton::Quit => "quit",
+            MenuButton::Resume => "resume",
+            MenuButton::MainMenu => "main menu",
+            MenuButton::Undo => "undo",
+            MenuButton::StartOver => "start over",
+        }
+    }
+}
+
+fn show_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    let mut buttons = vec![];
+    // a restored, paused or edited game
+    if !solution.0.is_empty() || board.0 != Board::default() {
+        buttons.push(MenuButton::Continue);
+    }
+    buttons.extend([
+        MenuButton::NewGame,
+        MenuButton::Challenge,
+        MenuButton::Practice,
+        MenuButton::EditBoard,
+        MenuButton::Replays,
+        MenuButton::Import,
+        MenuButton::Settings,
+        MenuButton::Statistics,
+        MenuButton::Tutorial,
+        MenuButton::Race,
+        MenuButton::Duel,
+        MenuButton::DuelComputer,
+    ]);
+    #[cfg(not(target_arch = "wasm32"))]
+    buttons.push(MenuButton::Quit);
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        AppState::Menu,
+        "peg solitaire".into(),
+        &buttons,
+    );
+}
+
+fn show_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        AppState::Paused,
+        "paused".into(),
+        &[
+            MenuButton::Resume,
+            MenuButton::Share,
+            MenuButton::Settings,
+            MenuButton::Statistics,
+            MenuButton::Explore,
+            MenuButton::MainMenu,
+        ],
+    );
+}
+
+/// the font of all text
+pub fn text_font(asset_server: &AssetServer, font_size: f32) -> TextFont {
+    TextFont {
+        font: asset_server.load("fonts/latinmodern-math.otf"),
+        font_size,
+        ..default()
+    }
+}
+
+/// a box in the center of the screen, which is removed
+/// as soon as the app leaves `state`
+pub fn spawn_menu(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    state: AppState,
+    text: String,
+    buttons: &[MenuButton],
+) {
+    let font = |font_size| text_font(asset_server, font_size);
+    let buttons: Vec<_> = buttons
+        .iter()
+        .map(|&b| button(b, b.label(), font(30.)))
+        .collect();
+    commands.spawn((
+        DespawnOnExit(state),
+        overlay(),
+        children![(
+            panel(),
+            Children::spawn((
+                Spawn((
+                    Text::new(text),
+                    font(40.),
+                    TextLayout::new_with_justify(Justify::Center),
+                    TextColor::WHITE,
+                )),
+                SpawnIter(buttons.into_iter()),
+            )),
+        )],
+    ));
+}
+
+/// a box above the menus and screens with a title, a text and a row of
+/// `buttons`, which is told apart by `marker` to close it
+pub fn spawn_dialog<B: Component + Copy>(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    marker: impl Bundle,
+    title: &str,
+    text: impl Bundle,
+    buttons: &[(B, &str)],
+) {
+    let font = |font_size| text_font(asset_server, font_size);
+    let buttons: Vec<_> = buttons
+        .iter()
+        .map(|&(b, label)| button(b, label, font(30.)))
+        .collect();
+    commands.spawn((
+        marker,
+        GlobalZIndex(2),
+        overlay(),
+        children![(
+            panel(),
+            children![
+                (Text::new(title), font(40.), TextColor::WHITE),
+                (
+                    text,
+                    font(25.),
+                    TextLayout::new(Justify::Center, LineBreak::WordOrCharacter),
+                    TextColor::WHITE,
+                ),
+                (
+                    Node {
+                        column_gap: Val::Px(20.),
+                        ..default()
+                    },
+                    Children::spawn(SpawnIter(buttons.into_iter())),
+                ),
+            ],
+        )],
+    ));
+}
+
+/// the whole screen, to center a box on
+fn overlay() -> Node {
+    Node {
+        width: Val::Percent(100.),
+        height: Val::Percent(100.),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+fn panel() -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.),
+            max_width: Val::Px(600.),
+            padding: UiRect::all(Val::Px(30.)),
+            border_radius: BorderRadius::all(Val::Px(20.)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.8)),
+    )
+}
+
+fn button<B: Component>(button: B, label: &str, font: TextFont) -> impl Bundle + use<B> {
+    (
+        Button,
+        button,
+        Node {
+            padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+            border_radius: BorderRadius::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE),
+        children![(Text::new(label), font, TextColor(Color::BLACK))],
+    )
+}
+
+fn handle_menu_buttons(
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    feasible: Option<Res<FeasibleByPegs>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            // a finished game goes straight to `GameOver`
+            MenuButton::Continue | MenuButton::Resume => next_state.set(AppState::Playing),
+            MenuButton::NewGame => {
+                commands.trigger(NewGame(Board::default()));
+                commands.insert_resource(Challenge::default());
+                next_state.set(AppState::Playing);
+            }
+            // starts playing once a challenge is found
+            MenuButton::Challenge => commands.trigger(StartChallenge),
+            MenuButton::Practice => {
+                let Some(feasible) = &feasible else {
+                    info!("still calculating feasible constellations ...");
+                    continue;
+                };
+                let pegs = settings.practice_pegs;
+                let mut rng = rand::rng();
+                let Some(board) = feasible.0.get(pegs).and_then(|b| b.choose(&mut rng)) else {
+                    info!("there is no winnable constellation with {pegs} pegs");
+                    continue;
+                };
+                // in a random orientation
+                let board = board.symmetries()[rng.random_range(0..8)];
+                commands.trigger(NewGame(board));
+                commands.insert_resource(Challenge::default());
+                next_state.set(AppState::Playing);
+            }
+            MenuButton::EditBoard => next_state.set(AppState::Editor),
+            MenuButton::Replays => next_state.set(AppState::Replays),
+            MenuButton::Import => commands.trigger(Import),
+            MenuButton::Share => commands.trigger(Share),
+            #[cfg(not(target_arch = "wasm32"))]
+            MenuButton::ExportGif => commands.trigger(ExportGif),
+            MenuButton::Explore => commands.trigger(ToggleExplore),
+            MenuButton::Settings => commands.trigger(ToggleSettings),
+            MenuButton::Statistics => commands.trigger(ToggleStatistics),
+            MenuButton::Tutorial => next_state.set(AppState::Tutorial),
+            MenuButton::Race => next_state.set(AppState::Race),
+            MenuButton::Duel | MenuButton::DuelComputer => {
+                commands.trigger(NewGame(Board::default()));
+                commands.insert_resource(Challenge::default());
+                commands.insert_resource(Duel {
+                    computer: matches!(button, MenuButton::DuelComputer),
+                });
+                next_state.set(AppState::Playing);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            MenuButton::Quit => commands.trigger(RequestQuit),
+            MenuButton::MainMenu => next_state.set(AppState::Menu),
+            MenuButton::Undo => commands.trigger(UndoEvent),
+            MenuButton::StartOver => commands.trigger(ResetEvent),
+        }
+    }
+}
+
+/// escape to pause and resume the game or to leave any other screen
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.get() {
+        AppState::Playing | AppState::GameOver => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::Playing),
+        AppState::Editor
+        | AppState::Replays
+        | AppState::Tutorial
+        | AppState::Race
+        | AppState::Attract => next_state.set(AppState::Menu),
+        AppState::Replay => next_state.set(AppState::Replays),
+        AppState::Menu => {}
+    }
+}
@@ -0,0 +1,235 @@
+use std::iter;
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::UiGlobalTransform,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    CurrentSolution, MainBoard, RedoMoves,
+    buttons::{RedoEvent, UndoEvent},
+    clipboard,
+    duel::players_turn,
+    game_state::{AppState, in_game},
+    main_changed,
+    menu::text_font,
+    settings::Settings,
+};
+
+/// the moves made so far in the notation of written solutions (see
+/// `Move::name`), followed by the undone ones. clicking a move undoes or
+/// redoes the moves up to it.
+pub struct MoveLogPlugin;
+
+impl Plugin for MoveLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_move_log);
+        app.add_systems(
+            Update,
+            (
+                show_move_log,
+                update_move_log
+                    .run_if(main_changed::<CurrentSolution>.or(main_changed::<RedoMoves>)),
+                scroll_move_log,
+                copy_moves,
+                jump_to_move.run_if(in_game.and(players_turn)),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct MoveLog;
+
+/// the entries, scrolled with the mouse wheel
+#[derive(Component)]
+struct MoveList;
+
+/// the board after this many moves
+#[derive(Component)]
+struct MoveEntry(usize);
+
+#[derive(Component)]
+struct CopyButton;
+
+/// scrolled per line of the mouse wheel
+const LINE_HEIGHT: f32 = 25.;
+
+fn font(asset_server: &AssetServer) -> TextFont {
+    text_font(asset_server, 20.)
+}
+
+fn spawn_move_log(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        MoveLog,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.),
+            top: Val::Percent(25.),
+            max_height: Val::Percent(50.),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.),
+            padding: UiRect::all(Val::Px(10.)),
+            border_radius: BorderRadius::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        children![
+            (
+                CopyButton,
+                Button,
+                Node {
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::axes(Val::Px(10.), Val::Px(5.)),
+                    border_radius: BorderRadius::all(Val::Px(10.)),
+                    ..default()
+                },
+                BackgroundColor(Color::WHITE),
+                children![(
+                    Text::new("copy"),
+                    font(&asset_server),
+                    TextColor(Color::BLACK)
+                )],
+            ),
+            (
+                MoveList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    // shrinks below its entries to scroll them
+                    min_height: Val::Px(0.),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ScrollPosition::default(),
+            ),
+        ],
+    ));
+}
+
+fn show_move_log(
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut panel: Single<&mut Visibility, With<MoveLog>>,
+) {
+    let in_game = matches!(
+        app_state.get(),
+        AppState::Playing | AppState::Paused | AppState::GameOver
+    );
+    panel.set_if_neq(if settings.move_log && in_game {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
+
+fn update_move_log(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    list: Single<(Entity, &mut ScrollPosition), With<MoveList>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    redo: Single<&RedoMoves, With<MainBoard>>,
+) {
+    let (list, mut scroll) = list.into_inner();
+    let font = font(&asset_server);
+    let played = solution.0.len();
+    // the last undone move is redone first
+    let moves = solution.0.moves().iter().chain(redo.0.iter().rev());
+    let entries = iter::once("start".to_string()).chain(
+        moves
+            .enumerate()
+            .map(|(i, mov)| format!("{}. {}", i + 1, mov.name())),
+    );
+    commands.entity(list).despawn_children();
+    commands.entity(list).with_children(|parent| {
+        for (n, text) in entries.enumerate() {
+            let current = n == played;
+            let color = match n {
+                _ if current => Color::BLACK,
+                // undone
+                n if n > played => Color::WHITE.with_alpha(0.4),
+                _ => Color::WHITE,
+            };
+            parent.spawn((
+                Button,
+                MoveEntry(n),
+                Node {
+                    padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+                    border_radius: BorderRadius::all(Val::Px(5.)),
+                    ..default()
+                },
+                BackgroundColor(if current { Color::WHITE } else { Color::NONE }),
+                children![(Text::new(text), font.clone(), TextColor(color))],
+            ));
+        }
+    });
+    // following new moves, the layout keeps the position in range
+    if redo.0.is_empty() {
+        scroll.y = f32::MAX;
+    }
+}
+
+fn scroll_move_log(
+    mut mouse_wheel: MessageReader<MouseWheel>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    list: Single<(&ComputedNode, &UiGlobalTransform, &mut ScrollPosition), With<MoveList>>,
+) {
+    let (node, transform, mut scroll) = list.into_inner();
+    let hovered = window
+        .physical_cursor_position()
+        .is_some_and(|cursor| node.contains_point(*transform, cursor));
+    // in logical pixels, like the scroll position
+    let max = (node.content_size.y - node.size.y).max(0.) * node.inverse_scale_factor;
+    for wheel in mouse_wheel.read() {
+        if !hovered {
+            continue;
+        }
+        let dy = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y * LINE_HEIGHT,
+            MouseScrollUnit::Pixel => wheel.y,
+        };
+        scroll.y = (scroll.y.min(max) - dy).clamp(0., max);
+    }
+}
+
+fn jump_to_move(
+    mut commands: Commands,
+    entries: Query<(&Interaction, &MoveEntry), Changed<Interaction>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    for (interaction, entry) in entries {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let played = solution.0.len();
+        for _ in entry.0..played {
+            commands.trigger(UndoEvent);
+        }
+        for _ in played..entry.0 {
+            commands.trigger(RedoEvent);
+        }
+    }
+}
+
+/// one numbered move per line, without the undone ones
+fn copy_moves(
+    button: Query<&Interaction, (With<CopyButton>, Changed<Interaction>)>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    if !button.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    let text: String = solution
+        .0
+        .moves()
+        .iter()
+        .enumerate()
+        .map(|(i, mov)| format!("{}. {}\n", i + 1, mov.name()))
+        .collect();
+    match clipboard::copy(&text) {
+        Ok(()) => info!("copied {} moves", solution.0.len()),
+        Err(e) => warn!("could not copy the moves: {e}"),
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs, io,
+    path::PathBuf,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use solitaire_solver::{Board, HashMap, Solution};
+
+use crate::{
+    menu::spawn_dialog,
+    replay::{Replay, Replays},
+    save_game::FlushSave,
+    settings::Settings,
+    storage,
+    total_progress::TotalProgress,
+};
+
+// Everything worth keeping when moving to another device goes into a single
+// RON file in the documents directory: the settings, the total progress and
+// the replays. Importing it merges the progress and replays with the ones on
+// this device and takes over the settings.
+//
+// The file starts with its version. Sets and maps are written sorted, one
+// entry per line, so two exports only differ where the progress does and a
+// sync could get by with diffing them.
+
+const PROGRESS_FILE: &str = "progress.ron";
+
+/// written by this build, files of older versions can still be imported
+const VERSION: u32 = 1;
+
+pub struct ProgressFilePlugin;
+
+impl Plugin for ProgressFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(export_progress);
+        app.add_observer(import_progress);
+        app.add_systems(Update, close_message);
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ExportProgress;
+
+/// merges the exported progress of another device
+#[derive(Default, Event)]
+pub struct ImportProgress;
+
+#[derive(Serialize)]
+struct ExportedProgress<'a> {
+    version: u32,
+    settings: &'a Settings,
+    progress: SortedProgress,
+    /// oldest first
+    replays: &'a [Replay],
+}
+
+#[derive(Deserialize)]
+struct ImportedProgress {
+    version: u32,
+    settings: Settings,
+    progress: SortedProgress,
+    #[serde(default)]
+    replays: Vec<Replay>,
+}
+
+/// `TotalProgress` in a stable order
+#[derive(Serialize, Deserialize)]
+struct SortedProgress {
+    explored_states: BTreeMap<Board, usize>,
+    normalized_explored_states: BTreeMap<Board, usize>,
+    explored_states_by_pegs: Vec<BTreeSet<Board>>,
+    unique_solutions: BTreeSet<Solution>,
+    num_solutions: u64,
+    games_played: u64,
+    best_time: Option<Duration>,
+    fewest_hints: Option<u32>,
+}
+
+impl From<&TotalProgress> for SortedProgress {
+    fn from(progress: &TotalProgress) -> Self {
+        Self {
+            explored_states: sorted(&progress.explored_states),
+            normalized_explored_states: sorted(&progress.normalized_explored_states),
+            explored_states_by_pegs: progress
+                .explored_states_by_pegs
+                .iter()
+                .map(|states| states.iter().copied().collect())
+                .collect(),
+            unique_solutions: progress.unique_solutions.iter().cloned().collect(),
+            num_solutions: progress.num_solutions,
+            games_played: progress.games_played,
+            best_time: progress.best_time,
+            fewest_hints: progress.fewest_hints,
+        }
+    }
+}
+
+fn sorted(states: &HashMap<Board, usize>) -> BTreeMap<Board, usize> {
+    states
+        .iter()
+        .map(|(&board, &count)| (board, count))
+        .collect()
+}
+
+impl TryFrom<SortedProgress> for TotalProgress {
+    type Error = &'static str;
+
+    fn try_from(progress: SortedProgress) -> Result<Self, Self::Error> {
+        let by_pegs: Vec<HashSet<Board>> = progress
+            .explored_states_by_pegs
+            .into_iter()
+            .map(|states| states.into_iter().collect())
+            .collect();
+        Ok(Self {
+            explored_states: progress.explored_states.into_iter().collect(),
+            normalized_explored_states: progress.normalized_explored_states.into_iter().collect(),
+            explored_states_by_pegs: by_pegs
+                .try_into()
+                .map_err(|_| "wrong number of peg counts")?,
+            unique_solutions: progress.unique_solutions.into_iter().collect(),
+            num_solutions: progress.num_solutions,
+            games_played: progress.games_played,
+            best_time: progress.best_time,
+            fewest_hints: progress.fewest_hints,
+        })
+    }
+}
+
+/// shows where the progress went or what went wrong
+#[derive(Component)]
+struct ProgressMessage;
+
+#[derive(Component, Clone, Copy)]
+struct OkButton;
+
+fn path() -> io::Result<PathBuf> {
+    storage::documents_dir()
+        .map(|dir| dir.join(PROGRESS_FILE))
+        .ok_or_else(|| io::Error::other("no documents directory"))
+}
+
+fn export_progress(
+    _: On<ExportProgress>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    total_progress: Res<TotalProgress>,
+    replays: Res<Replays>,
+) {
+    let exported = ExportedProgress {
+        version: VERSION,
+        settings: &settings,
+        progress: SortedProgress::from(&*total_progress),
+        replays: &replays.0,
+    };
+    let message = match try_export(&exported) {
+        Ok(path) => {
+            info!("exported progress to {path:?}");
+            format!("exported to\n{}", path.display())
+        }
+        Err(e) => {
+            warn!("could not export progress: {e}");
+            format!("could not export:\n{e}")
+        }
+    };
+    spawn_message(&mut commands, &asset_server, "export", message);
+}
+
+fn try_export(exported: &ExportedProgress) -> io::Result<PathBuf> {
+    let path = path()?;
+    let ron = ron::ser::to_string_pretty(exported, default()).map_err(io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, ron)?;
+    Ok(path)
+}
+
+fn import_progress(
+    _: On<ImportProgress>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut settings: ResMut<Settings>,
+    mut total_progress: ResMut<TotalProgress>,
+    mut replays: ResMut<Replays>,
+) {
+    let message = match try_import() {
+        Ok((path, imported_settings, progress, imported_replays)) => {
+            let known = replays.0.len();
+            for replay in imported_replays {
+                if !replays.0.contains(&replay) {
+                    replays.0.push(replay);
+                }
+            }
+            replays.0.sort_by_key(|replay| replay.finished);
+            total_progress.merge(progress);
+            *settings = imported_settings;
+            // the progress is only saved on exit otherwise
+            commands.trigger(FlushSave);
+            info!("imported progress from {path:?}");
+            format!(
+                "imported the progress and {} new replays from\n{}",
+                replays.0.len() - known,
+                path.display()
+            )
+        }
+        Err(e) => {
+            warn!("could not import progress: {e}");
+            format!("could not import:\n{e}")
+        }
+    };
+    spawn_message(&mut commands, &asset_server, "import", message);
+}
+
+/// the settings, progress and replays in the file
+fn try_import() -> io::Result<(PathBuf, Settings, TotalProgress, Vec<Replay>)> {
+    let path = path()?;
+    let ron = fs::read_to_string(&path)?;
+    let imported: ImportedProgress = ron::from_str(&ron).map_err(io::Error::other)?;
+    if imported.version > VERSION {
+        return Err(io::Error::other("written by a newer version of the game"));
+    }
+    let progress = TotalProgress::try_from(imported.progress).map_err(io::Error::other)?;
+    Ok((path, imported.settings, progress, imported.replays))
+}
+
+fn spawn_message(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    title: &str,
+    message: String,
+) {
+    spawn_dialog(
+        commands,
+        asset_server,
+        ProgressMessage,
+        title,
+        Text::new(message),
+        &[(OkButton, "ok")],
+    );
+}
+
+fn close_message(
+    mut commands: Commands,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<OkButton>)>,
+    messages: Query<Entity, With<ProgressMessage>>,
+) {
+    if !buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for message in &messages {
+        commands.entity(message).despawn();
+    }
+}
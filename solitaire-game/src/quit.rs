@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::{
+    CurrentSolution, MainBoard, game_state::GameState, menu::spawn_dialog, save_game::FlushSave,
+};
+
+/// quitting asks first while a game is in progress,
+/// and saves before the app exits
+pub struct QuitPlugin;
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(request_quit);
+        app.add_systems(Update, handle_quit_buttons);
+    }
+}
+
+/// Q, Alt+F4 or the quit button
+#[derive(Default, Event)]
+pub struct RequestQuit;
+
+#[derive(Component)]
+struct QuitDialog;
+
+#[derive(Component, Clone, Copy)]
+enum QuitButton {
+    Quit,
+    Cancel,
+}
+
+fn quit(commands: &mut Commands, exit: &mut MessageWriter<AppExit>) {
+    // the save at the end of the frame may not happen anymore
+    commands.trigger(FlushSave);
+    exit.write(AppExit::Success);
+}
+
+fn request_quit(
+    _: On<RequestQuit>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    game_state: Res<State<GameState>>,
+    dialog: Query<(), With<QuitDialog>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    // asking again confirms
+    let in_progress = !solution.0.is_empty() && *game_state.get() == GameState::Playing;
+    if !in_progress || !dialog.is_empty() {
+        quit(&mut commands, &mut exit);
+        return;
+    }
+    spawn_dialog(
+        &mut commands,
+        &asset_server,
+        QuitDialog,
+        "quit the game?",
+        Text::new("it is saved and goes on next time"),
+        &[
+            (QuitButton::Quit, "quit"),
+            (QuitButton::Cancel, "keep playing"),
+        ],
+    );
+}
+
+fn handle_quit_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &QuitButton), Changed<Interaction>>,
+    dialog: Query<Entity, With<QuitDialog>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            QuitButton::Quit => quit(&mut commands, &mut exit),
+            QuitButton::Cancel => {
+                for dialog in &dialog {
+                    commands.entity(dialog).despawn();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use solitaire_solver::Board;
+
+use crate::{
+    CurrentBoard, CurrentSolution, RedoMoves,
+    board::{BOARD_RADIUS, spawn_board},
+    buttons::reverse_last_move,
+    game_state::AppState,
+    menu::text_font,
+};
+
+// Two players race side by side to solve the same start. Every player has a
+// board of their own with its own position and moves, played like the single
+// player board by dragging pegs, so both players can play on one touch screen
+// at the same time. The boards stand far off the single player board, the
+// camera moves over to them for the race.
+
+pub struct RacePlugin;
+
+impl Plugin for RacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Race), spawn_race);
+        app.add_systems(OnExit(AppState::Race), end_race);
+        app.add_systems(
+            Update,
+            (
+                tick_clock,
+                check_solved,
+                handle_race_buttons,
+                update_race_texts,
+            )
+                .chain()
+                .run_if(in_state(AppState::Race)),
+        );
+    }
+}
+
+pub const PLAYERS: usize = 2;
+
+/// center of the boards, out of sight of the single player board
+const RACE_ORIGIN: Vec2 = Vec2::new(0., 1000.);
+
+/// between the centers of two boards next to each other
+const BOARD_SPACING: f32 = 2. * BOARD_RADIUS + 0.2;
+
+/// the board of one player
+#[derive(Component)]
+struct RaceBoard {
+    player: usize,
+    /// the time it took to solve the board
+    solved: Option<Duration>,
+}
+
+/// time since the start of the race, the hud wakes the app up for it every second
+#[derive(Resource, Default)]
+pub struct RaceClock(Duration);
+
+/// time and pegs left below a board
+#[derive(Component)]
+struct RaceText(usize);
+
+/// who is ahead, above the boards
+#[derive(Component)]
+struct RaceTitle;
+
+#[derive(Component, Clone, Copy)]
+enum RaceButton {
+    Undo(usize),
+    Restart,
+    MainMenu,
+}
+
+impl RaceButton {
+    fn label(self) -> &'static str {
+        match self {
+            RaceButton::Undo(_) => "undo",
+            RaceButton::Restart => "restart",
+            RaceButton::MainMenu => "main menu",
+        }
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// the boards, the title above and the buttons below them
+fn spawn_race(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    commands.insert_resource(RaceClock::default());
+    camera.translation = RACE_ORIGIN.extend(camera.translation.z);
+    spawn_boards(&mut commands);
+    let font = |font_size| text_font(&asset_server, font_size);
+    let button = |button: RaceButton| {
+        (
+            Button,
+            button,
+            Node {
+                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                border_radius: BorderRadius::all(Val::Px(10.)),
+                ..default()
+            },
+            BackgroundColor(Color::WHITE),
+            children![(
+                Text::new(button.label()),
+                font(30.),
+                TextColor(Color::BLACK)
+            )],
+        )
+    };
+    commands
+        .spawn((
+            DespawnOnExit(AppState::Race),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(20.)),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((RaceTitle, Text::default(), font(40.), TextColor::WHITE));
+            parent
+                .spawn(Node {
+                    width: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.),
+                            justify_content: JustifyContent::SpaceEvenly,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for player in 0..PLAYERS {
+                                parent
+                                    .spawn(Node {
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::Center,
+                                        row_gap: Val::Px(10.),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            RaceText(player),
+                                            Text::default(),
+                                            font(25.),
+                                            TextColor::WHITE,
+                                        ));
+                                        parent.spawn(button(RaceButton::Undo(player)));
+                                    });
+                            }
+                        });
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(button(RaceButton::Restart));
+                            parent.spawn(button(RaceButton::MainMenu));
+                        });
+                });
+        });
+}
+
+/// a board with the standard start for every player, next to each other
+fn spawn_boards(commands: &mut Commands) {
+    for player in 0..PLAYERS {
+        let offset = (player as f32 - (PLAYERS - 1) as f32 / 2.) * BOARD_SPACING;
+        let board = commands
+            .spawn((
+                RaceBoard {
+                    player,
+                    solved: None,
+                },
+                CurrentBoard(Board::default()),
+                Transform::from_translation((RACE_ORIGIN + Vec2::X * offset).extend(0.)),
+                DespawnOnExit(AppState::Race),
+            ))
+            .id();
+        spawn_board(commands, board, Board::default());
+    }
+}
+
+/// back to the single player board
+fn end_race(mut commands: Commands, mut camera: Single<&mut Transform, With<Camera2d>>) {
+    commands.remove_resource::<RaceClock>();
+    camera.translation = Vec2::ZERO.extend(camera.translation.z);
+}
+
+fn tick_clock(time: Res<Time>, mut clock: ResMut<RaceClock>) {
+    clock.0 += time.delta();
+}
+
+fn check_solved(
+    clock: Res<RaceClock>,
+    boards: Query<(&CurrentBoard, &mut RaceBoard), Changed<CurrentBoard>>,
+) {
+    for (board, mut race_board) in boards {
+        if race_board.solved.is_none() && board.0.is_solved() {
+            race_board.solved = Some(clock.0);
+        }
+    }
+}
+
+fn handle_race_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &RaceButton), Changed<Interaction>>,
+    mut boards: Query<(
+        Entity,
+        &RaceBoard,
+        &mut CurrentSolution,
+        &mut RedoMoves,
+        &mut CurrentBoard,
+    )>,
+    mut clock: ResMut<RaceClock>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            // a solved board stays solved
+            RaceButton::Undo(player) => {
+                if let Some((_, _, mut solution, mut redo, mut board)) = boards
+                    .iter_mut()
+                    .find(|(_, race_board, ..)| race_board.player == player)
+                    .filter(|(_, race_board, solution, ..)| {
+                        race_board.solved.is_none() && !solution.0.is_empty()
+                    })
+                {
+                    reverse_last_move(&mut solution, &mut redo, &mut board, &mut commands);
+                }
+            }
+            // new boards, the old pegs go with their board
+            RaceButton::Restart => {
+                for (board, ..) in &boards {
+                    commands.entity(board).despawn();
+                }
+                spawn_boards(&mut commands);
+                clock.0 = Duration::ZERO;
+            }
+            RaceButton::MainMenu => next_state.set(AppState::Menu),
+        }
+    }
+}
+
+fn update_race_texts(
+    boards: Query<(&RaceBoard, &CurrentBoard)>,
+    clock: Res<RaceClock>,
+    mut texts: Query<(&RaceText, &mut Text), Without<RaceTitle>>,
+    mut title: Single<&mut Text, With<RaceTitle>>,
+) {
+    let winner = boards
+        .iter()
+        .filter_map(|(b, _)| b.solved.map(|time| (time, b.player)))
+        .min();
+    let title_text = match winner {
+        Some((time, player)) => format!("player {} wins in {}!", player + 1, format_time(time)),
+        None => format!("race  ·  {}", format_time(clock.0)),
+    };
+    if title.0 != title_text {
+        title.0 = title_text;
+    }
+    for (text_player, mut text) in &mut texts {
+        let Some((race_board, board)) = boards
+            .iter()
+            .find(|(race_board, _)| race_board.player == text_player.0)
+        else {
+            continue;
+        };
+        let status = match race_board.solved {
+            Some(time) => format!("solved in {}", format_time(time)),
+            None if board.0.get_legal_moves().is_empty() => "no moves left, undo!".into(),
+            None => format!("{} pegs left", board.0.count_pegs()),
+        };
+        let new_text = format!("player {}: {status}", race_board.player + 1);
+        if text.0 != new_text {
+            text.0 = new_text;
+        }
+    }
+}
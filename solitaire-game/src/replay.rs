@@ -0,0 +1,520 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::RequestRedraw};
+use serde::{Deserialize, Serialize};
+use solitaire_solver::{Board, Move, Pos, Solution};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard, RedoMoves,
+    board::NewGame,
+    buttons::UndoEvent,
+    challenge::Challenge,
+    game_state::{AppState, GameTime},
+    input::RequestPegMove,
+    main_changed,
+    menu::text_font,
+    save_game::RestoredMoves,
+    share,
+};
+
+/// records finished games, so they can be watched again
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Replays>();
+        app.add_observer(watch_replay);
+        app.add_systems(OnEnter(AppState::GameOver), record_replay);
+        app.add_systems(OnEnter(AppState::Replays), first_page);
+        app.add_systems(OnEnter(AppState::Replay), show_viewer);
+        app.add_systems(OnExit(AppState::Replay), stop_watching);
+        app.add_systems(
+            Update,
+            show_browser.run_if(in_state(AppState::Replays).and(resource_changed::<ReplayPage>)),
+        );
+        app.add_systems(
+            Update,
+            (
+                handle_replay_buttons,
+                autoplay,
+                update_viewer.run_if(
+                    main_changed::<CurrentSolution>.or(resource_exists_and_changed::<Watching>),
+                ),
+            )
+                .chain()
+                .run_if(in_state(AppState::Replay).or(in_state(AppState::Replays))),
+        );
+    }
+}
+
+/// a finished game
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub start: Board,
+    pub moves: Solution,
+    /// unix timestamp of the end of the game, unknown on the web
+    pub finished: Option<u64>,
+}
+
+impl Replay {
+    /// the code to share this game with (see `share::encode`)
+    pub fn code(&self) -> String {
+        share::encode(self.start, &self.moves)
+    }
+
+    fn pegs_left(&self) -> usize {
+        self.start.count_pegs() - self.moves.len()
+    }
+}
+
+/// all finished games, oldest first
+#[derive(Default, Resource)]
+pub struct Replays(pub Vec<Replay>);
+
+/// switches to watching a replay
+#[derive(Event)]
+pub struct WatchReplay(pub Replay);
+
+/// the replay being watched
+#[derive(Resource)]
+struct Watching {
+    replay: Replay,
+    /// time until the next move, while playing
+    autoplay: Option<Timer>,
+}
+
+/// the game in progress while watching a replay, continued afterwards
+#[derive(Resource)]
+pub struct StashedGame {
+    pub board: Board,
+    pub moves: Solution,
+    redo: Vec<Move>,
+    pub challenge: Option<Pos>,
+    time: Duration,
+}
+
+/// page of the browser, the newest replays come first
+#[derive(Resource)]
+struct ReplayPage(usize);
+
+const PAGE_SIZE: usize = 8;
+
+const AUTOPLAY_INTERVAL: f32 = 0.8;
+
+#[derive(Component)]
+struct ReplayBrowser;
+
+#[derive(Component)]
+struct ProgressText;
+
+#[derive(Component)]
+struct PlayLabel;
+
+#[derive(Component, Clone, Copy)]
+enum ReplayButton {
+    Watch(usize),
+    Newer,
+    Older,
+    MainMenu,
+    Back,
+    Play,
+    Next,
+    Close,
+}
+
+impl ReplayButton {
+    fn label(self) -> &'static str {
+        match self {
+            ReplayButton::Watch(_) => "watch",
+            ReplayButton::Newer => "newer",
+            ReplayButton::Older => "older",
+            ReplayButton::MainMenu => "main menu",
+            ReplayButton::Back => "back",
+            ReplayButton::Play => "play",
+            ReplayButton::Next => "next",
+            ReplayButton::Close => "close",
+        }
+    }
+}
+
+/// seconds since the unix epoch
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> Option<u64> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    Some(since_epoch.as_secs())
+}
+
+/// there is no clock in std on the web
+#[cfg(target_arch = "wasm32")]
+fn now() -> Option<u64> {
+    None
+}
+
+/// `yyyy-mm-dd` of a unix timestamp,
+/// see <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn date(secs: u64) -> String {
+    let z = secs / 86400 + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!("{year}-{month:02}-{day:02}")
+}
+
+fn record_replay(
+    mut replays: ResMut<Replays>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    let replay = Replay {
+        start: solution.start(board.0),
+        moves: solution.0.clone(),
+        finished: now(),
+    };
+    // the same game over again after an undo
+    if replays
+        .0
+        .last()
+        .is_some_and(|last| last.start == replay.start && last.moves == replay.moves)
+    {
+        return;
+    }
+    info!("recording replay: {}", replay.code());
+    replays.0.push(replay);
+}
+
+fn watch_replay(
+    watch: On<WatchReplay>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    redo: Single<&RedoMoves, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    game_time: Res<GameTime>,
+    stashed: Option<Res<StashedGame>>,
+) {
+    // the game in progress is continued after watching
+    if stashed.is_none() {
+        commands.insert_resource(StashedGame {
+            board: board.0,
+            moves: solution.0.clone(),
+            redo: redo.0.clone(),
+            challenge: challenge.0,
+            time: game_time.0,
+        });
+    }
+    let replay = watch.0.clone();
+    commands.trigger(NewGame(replay.start));
+    commands.insert_resource(Challenge::default());
+    commands.insert_resource(Watching {
+        replay,
+        autoplay: None,
+    });
+    next_state.set(AppState::Replay);
+}
+
+fn first_page(mut commands: Commands) {
+    commands.insert_resource(ReplayPage(0));
+}
+
+fn button(button: ReplayButton, label: String, font: TextFont) -> impl Bundle {
+    (
+        Button,
+        button,
+        Node {
+            padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+            border_radius: BorderRadius::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE),
+        children![(Text::new(label), font, TextColor(Color::BLACK))],
+    )
+}
+
+fn show_browser(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    browser: Query<Entity, With<ReplayBrowser>>,
+    replays: Res<Replays>,
+    page: Res<ReplayPage>,
+) {
+    for browser in browser {
+        commands.entity(browser).despawn();
+    }
+    let font = |font_size| text_font(&asset_server, font_size);
+    let newest_first = (0..replays.0.len()).rev();
+    let shown = newest_first.skip(page.0 * PAGE_SIZE).take(PAGE_SIZE);
+    let older = replays.0.len() > (page.0 + 1) * PAGE_SIZE;
+    commands
+        .spawn((
+            ReplayBrowser,
+            DespawnOnExit(AppState::Replays),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.),
+                        padding: UiRect::all(Val::Px(30.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("replays"), font(40.), TextColor::WHITE));
+                    if replays.0.is_empty() {
+                        let text = "finished games show up here";
+                        parent.spawn((Text::new(text), font(30.), TextColor::WHITE));
+                    }
+                    for i in shown {
+                        let replay = &replays.0[i];
+                        let finished = replay.finished.map_or("".into(), |secs| date(secs) + ", ");
+                        let label = format!(
+                            "{finished}{} moves, {} left",
+                            replay.moves.len(),
+                            replay.pegs_left()
+                        );
+                        parent.spawn(button(ReplayButton::Watch(i), label, font(25.)));
+                    }
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            let mut buttons = vec![];
+                            if page.0 > 0 {
+                                buttons.push(ReplayButton::Newer);
+                            }
+                            if older {
+                                buttons.push(ReplayButton::Older);
+                            }
+                            buttons.push(ReplayButton::MainMenu);
+                            for b in buttons {
+                                parent.spawn(button(b, b.label().into(), font(30.)));
+                            }
+                        });
+                });
+        });
+}
+
+fn stop_watching(
+    mut commands: Commands,
+    stashed: Option<Res<StashedGame>>,
+    main: Single<Entity, With<MainBoard>>,
+) {
+    commands.remove_resource::<Watching>();
+    let Some(stashed) = stashed else {
+        return;
+    };
+    // the skipped pegs are recreated like those of a saved game
+    commands.trigger(NewGame(stashed.board));
+    commands.insert_resource(RestoredMoves(stashed.moves.clone()));
+    commands
+        .entity(*main)
+        .insert(RedoMoves(stashed.redo.clone()));
+    commands.insert_resource(Challenge(stashed.challenge));
+    commands.insert_resource(GameTime(stashed.time));
+    commands.remove_resource::<StashedGame>();
+}
+
+/// a box at the bottom of the screen, out of the way of the board
+fn show_viewer(mut commands: Commands, asset_server: Res<AssetServer>, watching: Res<Watching>) {
+    let font = |font_size| text_font(&asset_server, font_size);
+    commands
+        .spawn((
+            DespawnOnExit(AppState::Replay),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::End,
+                padding: UiRect::bottom(Val::Px(20.)),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.),
+                        max_width: Val::Px(600.),
+                        padding: UiRect::all(Val::Px(20.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((ProgressText, Text::default(), font(30.), TextColor::WHITE));
+                    // to be copied by hand, there is no clipboard (see `share`)
+                    parent.spawn((
+                        Text::new(watching.replay.code()),
+                        font(15.),
+                        TextLayout::new_with_justify(Justify::Center),
+                        TextColor(Color::WHITE.with_alpha(0.6)),
+                    ));
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for b in [
+                                ReplayButton::Back,
+                                ReplayButton::Play,
+                                ReplayButton::Next,
+                                ReplayButton::Close,
+                            ] {
+                                let mut button =
+                                    parent.spawn(button(b, b.label().into(), font(30.)));
+                                if let ReplayButton::Play = b {
+                                    button.insert(PlayLabel);
+                                }
+                            }
+                        });
+                });
+        });
+}
+
+fn handle_replay_buttons(
+    buttons: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+    replays: Res<Replays>,
+    mut page: Option<ResMut<ReplayPage>>,
+    mut watching: Option<ResMut<Watching>>,
+    main: Single<Entity, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            ReplayButton::Watch(i) => commands.trigger(WatchReplay(replays.0[i].clone())),
+            ReplayButton::Newer => {
+                if let Some(page) = &mut page {
+                    page.0 = page.0.saturating_sub(1);
+                }
+            }
+            ReplayButton::Older => {
+                if let Some(page) = &mut page {
+                    page.0 += 1;
+                }
+            }
+            ReplayButton::MainMenu => next_state.set(AppState::Menu),
+            ReplayButton::Back => {
+                if let Some(watching) = &mut watching {
+                    watching.autoplay = None;
+                }
+                commands.trigger(UndoEvent);
+            }
+            ReplayButton::Play => {
+                if let Some(watching) = &mut watching {
+                    watching.autoplay = match watching.autoplay {
+                        Some(_) => None,
+                        None => Some(Timer::from_seconds(AUTOPLAY_INTERVAL, TimerMode::Repeating)),
+                    };
+                }
+            }
+            ReplayButton::Next => {
+                if let Some(watching) = &mut watching {
+                    watching.autoplay = None;
+                    next_move(&mut commands, *main, &watching.replay, &solution);
+                }
+            }
+            ReplayButton::Close => next_state.set(AppState::Replays),
+        }
+    }
+}
+
+/// plays the next move of `replay`, returns false at the end
+fn next_move(
+    commands: &mut Commands,
+    board: Entity,
+    replay: &Replay,
+    solution: &CurrentSolution,
+) -> bool {
+    let Some(&mov) = replay.moves.moves().get(solution.0.len()) else {
+        return false;
+    };
+    commands.trigger(RequestPegMove {
+        board,
+        src: mov.pos.into(),
+        dst: mov.target.into(),
+    });
+    true
+}
+
+fn autoplay(
+    mut commands: Commands,
+    watching: Option<ResMut<Watching>>,
+    main: Single<Entity, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let Some(mut watching) = watching else {
+        return;
+    };
+    let watching = &mut *watching;
+    let Some(timer) = &mut watching.autoplay else {
+        return;
+    };
+    if timer.tick(time.delta()).just_finished()
+        && !next_move(&mut commands, *main, &watching.replay, &solution)
+    {
+        watching.autoplay = None;
+    }
+    // keep updating while playing
+    request_redraw.write(RequestRedraw);
+}
+
+fn update_viewer(
+    watching: Option<Res<Watching>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    mut progress: Query<&mut Text, With<ProgressText>>,
+    play_label: Query<&Children, With<PlayLabel>>,
+    mut texts: Query<&mut Text, Without<ProgressText>>,
+) {
+    let Some(watching) = watching else {
+        return;
+    };
+    for mut text in &mut progress {
+        text.0 = format!(
+            "move {} of {}",
+            solution.0.len(),
+            watching.replay.moves.len()
+        );
+    }
+    let label = match watching.autoplay {
+        Some(_) => "pause",
+        None => ReplayButton::Play.label(),
+    };
+    for children in play_label {
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.0 = label.into();
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    ecs::{entity_disabling::Disabled, system::SystemParam},
+    prelude::*,
+    window::AppLifecycle,
+};
+use serde::{Deserialize, Serialize};
+use solitaire_solver::{Board, Pos, Solution};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard, MoveEvent, SolutionEvent,
+    board::{BoardPosition, Peg, peg},
+    challenge::Challenge,
+    game_state::AppState,
+    replay::{Replay, Replays, StashedGame},
+    storage,
+    total_progress::TotalProgress,
+};
+
+// Saves the game in progress, the total progress and the replays to the data directory
+// when the app exits or is suspended and restores them on the next launch.
+// Solving the board is saved right away, it should never get lost.
+//
+// The file is written in RON, boards in their compressed representation.
+// A save that can not be read is ignored and the game starts from scratch.
+
+const SAVE_FILE: &str = "save.ron";
+
+pub struct SaveGame;
+
+impl Plugin for SaveGame {
+    fn build(&self, app: &mut App) {
+        // the pegs are spawned from `CurrentBoard` at startup
+        app.add_systems(PreStartup, load_game);
+        app.add_systems(PostStartup, restore_moves);
+        // the game left for watching a replay
+        app.add_systems(
+            Update,
+            restore_moves.run_if(resource_exists::<RestoredMoves>),
+        );
+        app.init_resource::<SaveRequested>();
+        app.add_observer(request_save);
+        app.add_observer(flush_save);
+        app.add_systems(Last, save_game);
+    }
+}
+
+#[derive(Serialize)]
+struct SavedGame<'a> {
+    board: Board,
+    moves: &'a Solution,
+    /// the target hole of a challenge
+    challenge: Option<Pos>,
+    total_progress: &'a TotalProgress,
+    replays: &'a [Replay],
+}
+
+#[derive(Deserialize)]
+struct LoadedGame {
+    board: Board,
+    moves: Solution,
+    #[serde(default)]
+    challenge: Option<Pos>,
+    total_progress: TotalProgress,
+    #[serde(default)]
+    replays: Vec<Replay>,
+}
+
+#[derive(Default, Resource)]
+struct SaveRequested(bool);
+
+/// saves right away instead of at the end of the frame, e.g. before quitting
+#[derive(Default, Event)]
+pub struct FlushSave;
+
+/// everything written to the save file
+#[derive(SystemParam)]
+struct Saved<'w, 's> {
+    board: Single<'w, 's, &'static CurrentBoard, With<MainBoard>>,
+    solution: Single<'w, 's, &'static CurrentSolution, With<MainBoard>>,
+    challenge: Res<'w, Challenge>,
+    total_progress: Res<'w, TotalProgress>,
+    replays: Res<'w, Replays>,
+    app_state: Res<'w, State<AppState>>,
+    stashed: Option<Res<'w, StashedGame>>,
+}
+
+impl Saved<'_, '_> {
+    fn save(&self) {
+        let Some(path) = path() else {
+            return;
+        };
+        // the solutions playing by themselves began at the empty start
+        let empty = Solution::default();
+        // and a replay is watched instead of the game in progress
+        let (board, moves, challenge) = if *self.app_state.get() == AppState::Attract {
+            (Board::default(), &empty, None)
+        } else if let Some(stashed) = &self.stashed {
+            (stashed.board, &stashed.moves, stashed.challenge)
+        } else {
+            (self.board.0, &self.solution.0, self.challenge.0)
+        };
+        let game = SavedGame {
+            board,
+            moves,
+            challenge,
+            total_progress: &self.total_progress,
+            replays: &self.replays.0,
+        };
+        match try_save_game(&path, &game) {
+            Ok(()) => info!("saved game to {path:?}"),
+            Err(e) => warn!("could not save game to {path:?}: {e}"),
+        }
+    }
+}
+
+/// moves of the loaded game, which still need their pegs
+#[derive(Resource)]
+pub struct RestoredMoves(pub Solution);
+
+fn path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join(SAVE_FILE))
+}
+
+fn load_game(mut commands: Commands, mut board: Single<&mut CurrentBoard, With<MainBoard>>) {
+    let Some(path) = path() else {
+        return;
+    };
+    let Ok(ron) = fs::read_to_string(&path) else {
+        return;
+    };
+    let game = match ron::from_str::<LoadedGame>(&ron) {
+        Ok(game) if played(game.board, &game.moves) => game,
+        Ok(_) => {
+            warn!("ignoring save file {path:?}: moves dont match the board");
+            return;
+        }
+        Err(e) => {
+            warn!("ignoring save file {path:?}: {e}");
+            return;
+        }
+    };
+    info!(
+        "restored game with {} moves from {path:?}",
+        game.moves.len()
+    );
+    board.0 = game.board;
+    commands.insert_resource(Challenge(game.challenge));
+    commands.insert_resource(game.total_progress);
+    commands.insert_resource(Replays(game.replays));
+    commands.insert_resource(RestoredMoves(game.moves));
+}
+
+/// whether `board` can be reached by playing `moves`
+fn played(board: Board, moves: &Solution) -> bool {
+    let start = moves.moves().iter().rev().try_fold(board, |board, &mov| {
+        let undoable = [mov.pos, mov.skip, mov.target]
+            .into_iter()
+            .all(Board::inbounds)
+            && board.occupied(mov.target)
+            && !board.occupied(mov.skip)
+            && !board.occupied(mov.pos);
+        let prev = undoable.then(|| board.reverse_mov(mov))?;
+        (prev.check_move(mov.pos, mov.target) == Ok(mov)).then_some(prev)
+    });
+    start.is_some()
+}
+
+/// recreates the skipped pegs of the restored moves, so they can be undone
+fn restore_moves(
+    restored: Option<Res<RestoredMoves>>,
+    pegs: Query<(Entity, &BoardPosition, &ChildOf), With<Peg>>,
+    main: Single<(Entity, &mut CurrentSolution), With<MainBoard>>,
+    mut commands: Commands,
+) {
+    let Some(restored) = restored else {
+        return;
+    };
+    let (main, mut solution) = main.into_inner();
+    let mut pegs: HashMap<Pos, Entity> = pegs
+        .iter()
+        .filter(|(_, _, board)| board.parent() == main)
+        .map(|(e, p, _)| (p.into(), e))
+        .collect();
+    let mut events = Vec::with_capacity(restored.0.len());
+    for &mov in restored.0.moves().iter().rev() {
+        let moved = pegs.remove(&mov.target).expect("peg");
+        let skipped = commands
+            .spawn((peg(mov.skip), Disabled, ChildOf(main)))
+            .id();
+        pegs.insert(mov.pos, moved);
+        pegs.insert(mov.skip, skipped);
+        events.push(MoveEvent {
+            board: main,
+            mov,
+            moved,
+            skipped,
+        });
+    }
+    events.reverse();
+    *solution = CurrentSolution(restored.0.clone(), events);
+    commands.remove_resource::<RestoredMoves>();
+}
+
+fn request_save(_: On<SolutionEvent>, mut requested: ResMut<SaveRequested>) {
+    requested.0 = true;
+}
+
+fn flush_save(_: On<FlushSave>, mut requested: ResMut<SaveRequested>, saved: Saved) {
+    requested.0 = false;
+    saved.save();
+}
+
+fn save_game(
+    mut exit: MessageReader<AppExit>,
+    mut lifecycle: MessageReader<AppLifecycle>,
+    mut requested: ResMut<SaveRequested>,
+    saved: Saved,
+) {
+    // suspended apps may be killed without notice
+    let suspended = lifecycle
+        .read()
+        .any(|lifecycle| *lifecycle == AppLifecycle::WillSuspend);
+    let requested = std::mem::take(&mut requested.0);
+    if exit.read().count() == 0 && !suspended && !requested {
+        return;
+    }
+    saved.save();
+}
+
+fn try_save_game(path: &Path, game: &SavedGame) -> io::Result<()> {
+    let ron = ron::to_string(game).map_err(io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // dont replace the previous save with a truncated file
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, ron)?;
+    fs::rename(tmp, path)
+}
@@ -0,0 +1,237 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    camera::ClearColorConfig,
+    ecs::{entity_disabling::Disabled, query::Allow},
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    window::RequestRedraw,
+};
+
+use crate::{
+    BoardCamera,
+    board::{BOARD_POS, CircleComponent, HOLE_POS, PEG_POS, ThemeColor, flat},
+    game_state::AppState,
+    settings::Settings,
+};
+
+/// renders the board, holes and pegs as meshes seen by an orbiting camera.
+/// everything else (buttons, hints, texts) stays on the 2d camera above it.
+pub struct Scene3dPlugin;
+
+impl Plugin for Scene3dPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                switch_scene.run_if(resource_changed::<Settings>.or(state_changed::<AppState>)),
+                (add_meshes, update_materials, orbit).run_if(scene_shown),
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Update,
+            turn_slowly.run_if(scene_shown.and(in_state(AppState::Attract))),
+        );
+        app.add_systems(OnExit(AppState::Attract), face_player.run_if(scene_shown));
+    }
+}
+
+/// the camera, lights and ground, despawned when switching back
+#[derive(Component)]
+struct Scene3d;
+
+/// position of the camera around the center of the board
+#[derive(Component)]
+struct Orbit {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+impl Default for Orbit {
+    fn default() -> Self {
+        // from below the board, so its top stays at the top of the screen
+        Self {
+            yaw: -FRAC_PI_2,
+            pitch: 1.0,
+            distance: 12.,
+        }
+    }
+}
+
+impl Orbit {
+    fn transform(&self) -> Transform {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let direction = Vec3::new(cos_pitch * cos_yaw, cos_pitch * sin_yaw, sin_pitch);
+        Transform::from_translation(self.distance * direction).looking_at(Vec3::ZERO, Vec3::Z)
+    }
+}
+
+const BOARD_THICKNESS: f32 = 0.3;
+
+fn scene_shown(scene: Query<(), With<Scene3d>>) -> bool {
+    !scene.is_empty()
+}
+
+fn switch_scene(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    state: Res<State<AppState>>,
+    scene: Query<Entity, With<Scene3d>>,
+    circles: Query<Entity, (With<CircleComponent>, Allow<Disabled>)>,
+    camera_2d: Single<(Entity, &mut Camera), With<Camera2d>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let shown = !flat(settings, state);
+    if shown != scene.is_empty() {
+        return;
+    }
+    let (camera_2d, mut camera) = camera_2d.into_inner();
+    if !shown {
+        for entity in &scene {
+            commands.entity(entity).despawn();
+        }
+        for entity in &circles {
+            commands
+                .entity(entity)
+                .remove::<(Mesh3d, MeshMaterial3d<StandardMaterial>)>();
+        }
+        camera.order = 0;
+        camera.clear_color = ClearColorConfig::Default;
+        commands.entity(camera_2d).insert(BoardCamera);
+        return;
+    }
+    // the 2d camera draws on top of the scene
+    camera.order = 1;
+    camera.clear_color = ClearColorConfig::None;
+    commands.entity(camera_2d).remove::<BoardCamera>();
+    let orbit = Orbit::default();
+    commands.spawn((
+        Scene3d,
+        BoardCamera,
+        Camera3d::default(),
+        orbit.transform(),
+        orbit,
+        DistanceFog {
+            color: Color::srgb_u8(43, 44, 47),
+            falloff: FogFalloff::Linear {
+                start: 10.,
+                end: 50.,
+            },
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Scene3d,
+        PointLight {
+            intensity: 15_000_000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(-5.0, 9.0, 8.),
+    ));
+    // the table the board lies on
+    let ground_plane = Plane3d::new(Vec3::Z, Vec2::splat(12.));
+    commands.spawn((
+        Scene3d,
+        Mesh3d(meshes.add(ground_plane.mesh())),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.8, 0.8))),
+        Transform::from_xyz(0.0, 0.0, BOARD_POS - BOARD_THICKNESS),
+    ));
+}
+
+/// also to pegs spawned or enabled later on
+fn add_meshes(
+    mut commands: Commands,
+    circles: Query<(Entity, &CircleComponent, &ThemeColor), Without<Mesh3d>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, circle, theme_color) in circles {
+        let mesh = match theme_color {
+            // its top where the flat board is
+            ThemeColor::Board => Cylinder::new(circle.radius, BOARD_THICKNESS)
+                .mesh()
+                .build()
+                .rotated_by(Quat::from_rotation_x(FRAC_PI_2))
+                .translated_by(Vec3::Z * -BOARD_THICKNESS / 2.),
+            // marbles resting in their holes
+            ThemeColor::Peg(_) => Sphere::new(circle.radius)
+                .mesh()
+                .build()
+                .translated_by(Vec3::Z * (circle.radius + HOLE_POS - PEG_POS)),
+            ThemeColor::Hole(_) | ThemeColor::HoleCenter(_) => {
+                Circle::new(circle.radius).mesh().build()
+            }
+        };
+        let material = StandardMaterial {
+            base_color: circle.color,
+            perceptual_roughness: 0.3,
+            ..default()
+        };
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(material)),
+        ));
+    }
+}
+
+/// follows the colours of the theme
+fn update_materials(
+    circles: Query<(&CircleComponent, &MeshMaterial3d<StandardMaterial>), Changed<CircleComponent>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (circle, material) in circles {
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = circle.color;
+        }
+    }
+}
+
+/// dragging with the right mouse button turns the camera, scrolling zooms
+fn orbit(
+    buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    camera: Single<(&mut Orbit, &mut Transform)>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let (mut orbit, mut transform) = camera.into_inner();
+    let turn = if buttons.pressed(MouseButton::Right) {
+        motion.delta
+    } else {
+        Vec2::ZERO
+    };
+    if turn == Vec2::ZERO && scroll.delta.y == 0. {
+        return;
+    }
+    orbit.yaw -= 0.01 * turn.x;
+    orbit.pitch = (orbit.pitch + 0.01 * turn.y).clamp(0.2, 1.5);
+    orbit.distance = (orbit.distance - scroll.delta.y).clamp(6., 30.);
+    *transform = orbit.transform();
+    request_redraw.write(RequestRedraw);
+}
+
+/// radians per second while the solutions play by themselves
+const ATTRACT_TURN: f32 = 0.1;
+
+fn turn_slowly(
+    camera: Single<(&mut Orbit, &mut Transform)>,
+    time: Res<Time>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let (mut orbit, mut transform) = camera.into_inner();
+    orbit.yaw += ATTRACT_TURN * time.delta_secs();
+    *transform = orbit.transform();
+    request_redraw.write(RequestRedraw);
+}
+
+/// the top of the board up again, keeping the height and distance
+fn face_player(camera: Single<(&mut Orbit, &mut Transform)>) {
+    let (mut orbit, mut transform) = camera.into_inner();
+    orbit.yaw = Orbit::default().yaw;
+    *transform = orbit.transform();
+}
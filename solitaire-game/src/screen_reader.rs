@@ -0,0 +1,120 @@
+use accesskit::{Live, Role};
+use bevy::{a11y::AccessibilityNode, prelude::*};
+use solitaire_solver::{Board, Pos};
+
+use crate::{CurrentBoard, MainBoard, MoveEvent, challenge::Challenge, main_changed};
+
+// The board is drawn by hand, so screen readers only know about it through
+// the nodes spawned here: a grid with one cell per hole, saying whether there
+// is a peg in it, and a status announcing every move and how the game stands.
+//
+// Buttons of the ui are labelled by bevy from their text, the circle buttons
+// get their label where they are spawned (see `button`).
+
+pub struct ScreenReaderPlugin;
+
+impl Plugin for ScreenReaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_nodes);
+        app.add_systems(
+            Update,
+            (update_holes, update_status).run_if(main_changed::<CurrentBoard>),
+        );
+        app.add_observer(announce_move);
+    }
+}
+
+/// the hole at this position
+#[derive(Component)]
+struct HoleNode(Pos);
+
+/// read out whenever it changes, last for this board
+#[derive(Component)]
+struct StatusNode(Board);
+
+/// an accessible button, for the ones without ui text
+pub fn button(label: &'static str) -> AccessibilityNode {
+    let mut node = accesskit::Node::new(Role::Button);
+    node.set_label(label);
+    AccessibilityNode(node)
+}
+
+fn spawn_nodes(mut commands: Commands) {
+    let mut status = accesskit::Node::new(Role::Status);
+    status.set_live(Live::Polite);
+    commands.spawn((StatusNode(Board::empty()), AccessibilityNode(status)));
+
+    let mut grid = accesskit::Node::new(Role::Grid);
+    grid.set_label("board");
+    commands
+        .spawn(AccessibilityNode(grid))
+        .with_children(|grid| {
+            for pos in Board::empty().holes() {
+                let mut cell = accesskit::Node::new(Role::Cell);
+                cell.set_row_index(pos.row as usize);
+                cell.set_column_index(pos.col as usize);
+                grid.spawn((HoleNode(pos), AccessibilityNode(cell)));
+            }
+        });
+}
+
+fn update_holes(
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    mut holes: Query<(&HoleNode, &mut AccessibilityNode)>,
+) {
+    for (hole, mut node) in &mut holes {
+        let content = if board.0.occupied(hole.0) {
+            "peg"
+        } else {
+            "empty"
+        };
+        node.set_label(format!("{} {content}", hole.0.name()));
+    }
+}
+
+/// how the game stands, announced after the move
+fn game_status(board: Board, challenge: &Challenge) -> String {
+    if board == challenge.goal() {
+        "solved".into()
+    } else if board.get_legal_moves().is_empty() {
+        format!("no moves left, {} pegs remaining", board.count_pegs())
+    } else {
+        format!("{} pegs left", board.count_pegs())
+    }
+}
+
+/// also after undoing, a new game or an edit, without a move to announce
+fn update_status(
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    status: Single<(&mut StatusNode, &mut AccessibilityNode)>,
+) {
+    let (mut status, mut node) = status.into_inner();
+    // the move was announced already
+    if status.0 != board.0 {
+        status.0 = board.0;
+        node.set_label(game_status(board.0, &challenge));
+    }
+}
+
+fn announce_move(
+    move_event: On<MoveEvent>,
+    main: Single<(Entity, &CurrentBoard), With<MainBoard>>,
+    challenge: Res<Challenge>,
+    status: Single<(&mut StatusNode, &mut AccessibilityNode)>,
+) {
+    let (main, board) = *main;
+    if move_event.board != main {
+        return;
+    }
+    let (mut status, mut node) = status.into_inner();
+    let mov = move_event.mov;
+    status.0 = board.0;
+    node.set_label(format!(
+        "peg from {} jumped to {}, peg at {} removed, {}",
+        mov.pos.name(),
+        mov.target.name(),
+        mov.skip.name(),
+        game_status(board.0, &challenge)
+    ));
+}
@@ -0,0 +1,625 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{menu::text_font, storage, total_progress::TotalProgress};
+
+// The settings are written to the config directory in RON whenever they change
+// and loaded again at startup. Settings that can not be read are reset to their
+// defaults, missing ones (e.g. from an older version) take their default.
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        app.add_systems(PreStartup, load_settings);
+        app.add_observer(toggle_settings);
+        app.add_systems(Update, handle_setting_buttons);
+        app.add_systems(
+            Update,
+            highlight_selected.run_if(resource_changed::<Settings>),
+        );
+        app.add_systems(
+            Last,
+            save_settings.run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+        );
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hints: HintLevel,
+    /// pegs left on the board when practicing endgames
+    pub practice_pegs: usize,
+    /// time, moves and captures left above the board
+    pub hud: bool,
+    /// every legal move ranked, beside the board
+    pub assist: bool,
+    /// the moves made so far, left of the board
+    pub move_log: bool,
+    pub theme: BuiltinTheme,
+    /// colours of the pegs and hints
+    pub palette: Palette,
+    /// look of the pegs, unlocked by playing
+    pub peg_skin: PegSkin,
+    pub animation_speed: AnimationSpeed,
+    /// pegs snap into place instead of moving
+    pub reduced_motion: bool,
+    /// frames per second in the corner, not available on the web
+    pub fps_overlay: bool,
+    /// the board as a lit 3d scene, only with the `scene_3d` feature
+    pub scene_3d: bool,
+    /// wins are submitted to the online leaderboard, only with the
+    /// `leaderboard` feature
+    pub leaderboard: bool,
+    /// the tutorial is only shown by itself on the first launch
+    pub tutorial_seen: bool,
+    /// redraw only on input and animations, ignoring the mouse outside the window
+    pub low_power: bool,
+    /// no stats, hints or fps, calmer colours and no solver in the background
+    pub zen: bool,
+    /// labels for following written solutions
+    pub coordinates: Coordinates,
+    /// size of the menus, buttons and texts around the board, in percent
+    pub ui_scale: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            hints: HintLevel::default(),
+            practice_pegs: 10,
+            hud: true,
+            assist: false,
+            move_log: false,
+            theme: BuiltinTheme::default(),
+            palette: Palette::default(),
+            peg_skin: PegSkin::default(),
+            animation_speed: AnimationSpeed::default(),
+            reduced_motion: false,
+            fps_overlay: false,
+            scene_3d: false,
+            leaderboard: false,
+            tutorial_seen: false,
+            low_power: false,
+            zen: false,
+            coordinates: Coordinates::default(),
+            ui_scale: 100,
+        }
+    }
+}
+
+impl Settings {
+    /// `ui_scale` as a factor
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale as f32 / 100.
+    }
+}
+
+/// choices for `Settings::practice_pegs`
+const PRACTICE_PEGS: std::ops::RangeInclusive<usize> = 8..=15;
+
+/// choices for `Settings::ui_scale`
+const UI_SCALES: [u32; 6] = [75, 100, 125, 150, 175, 200];
+
+/// how much help the game gives
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HintLevel {
+    #[default]
+    Off,
+    /// legal moves coloured by whether they can still lead to a solution
+    Feasibility,
+    /// the best move only
+    BestMove,
+    /// legal moves coloured by the chance of winning
+    Heatmap,
+}
+
+impl HintLevel {
+    const ALL: [Self; 4] = [
+        HintLevel::Off,
+        HintLevel::Feasibility,
+        HintLevel::BestMove,
+        HintLevel::Heatmap,
+    ];
+
+    /// the level after this one, wrapping around
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&l| l == self).unwrap();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            HintLevel::Off => "off",
+            HintLevel::Feasibility => "feasible moves",
+            HintLevel::BestMove => "best move",
+            HintLevel::Heatmap => "win chances",
+        }
+    }
+}
+
+/// colours of the board, background and buttons (see `Theme`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuiltinTheme {
+    /// black or white, following the system
+    #[default]
+    System,
+    Black,
+    Slate,
+    Felt,
+    Wood,
+}
+
+impl BuiltinTheme {
+    const ALL: [Self; 5] = [
+        BuiltinTheme::System,
+        BuiltinTheme::Black,
+        BuiltinTheme::Slate,
+        BuiltinTheme::Felt,
+        BuiltinTheme::Wood,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            BuiltinTheme::System => "system",
+            BuiltinTheme::Black => "black",
+            BuiltinTheme::Slate => "slate",
+            BuiltinTheme::Felt => "felt",
+            BuiltinTheme::Wood => "wood",
+        }
+    }
+}
+
+/// colours of the pegs and hints (see `Theme`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Classic,
+    /// no red and green, for the most common colour vision deficiency
+    Deuteranopia,
+    /// no dark reds
+    Protanopia,
+    HighContrast,
+    Mono,
+}
+
+impl Palette {
+    const ALL: [Self; 5] = [
+        Palette::Classic,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::HighContrast,
+        Palette::Mono,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Palette::Classic => "classic",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::HighContrast => "high contrast",
+            Palette::Mono => "mono",
+        }
+    }
+}
+
+/// how pegs are drawn on top of their colour (see `skins`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PegSkin {
+    #[default]
+    Flat,
+    /// with a shine, unlocked by the first win
+    Glossy,
+    /// a symbol per colour, unlocked by a win without hints
+    Icons,
+    /// the hole every peg started in, unlocked by 10 unique solutions
+    Numbers,
+}
+
+impl PegSkin {
+    const ALL: [Self; 4] = [
+        PegSkin::Flat,
+        PegSkin::Glossy,
+        PegSkin::Icons,
+        PegSkin::Numbers,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PegSkin::Flat => "flat",
+            PegSkin::Glossy => "glossy",
+            PegSkin::Icons => "icons",
+            PegSkin::Numbers => "numbers",
+        }
+    }
+
+    pub fn unlocked(self, progress: &TotalProgress) -> bool {
+        match self {
+            PegSkin::Flat => true,
+            PegSkin::Glossy => progress.num_solutions > 0,
+            PegSkin::Icons => progress.fewest_hints == Some(0),
+            PegSkin::Numbers => progress.unique_solutions.len() >= 10,
+        }
+    }
+
+    /// shown next to the label while locked
+    fn requirement(self) -> &'static str {
+        match self {
+            PegSkin::Flat => "",
+            PegSkin::Glossy => "win once",
+            PegSkin::Icons => "win without hints",
+            PegSkin::Numbers => "find 10 solutions",
+        }
+    }
+}
+
+/// how fast pegs move (see `animation::Motion`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimationSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    const ALL: [Self; 3] = [
+        AnimationSpeed::Slow,
+        AnimationSpeed::Normal,
+        AnimationSpeed::Fast,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            AnimationSpeed::Slow => "slow",
+            AnimationSpeed::Normal => "normal",
+            AnimationSpeed::Fast => "fast",
+        }
+    }
+}
+
+/// labels of the columns and rows (see `Pos::name`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Coordinates {
+    #[default]
+    Off,
+    /// `a`-`g` above and `1`-`7` left of the board
+    Edges,
+    /// the name of every hole as well
+    Holes,
+}
+
+impl Coordinates {
+    const ALL: [Self; 3] = [Coordinates::Off, Coordinates::Edges, Coordinates::Holes];
+
+    fn label(self) -> &'static str {
+        match self {
+            Coordinates::Off => "off",
+            Coordinates::Edges => "edges",
+            Coordinates::Holes => "holes",
+        }
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ToggleSettings;
+
+#[derive(Component)]
+struct SettingsScreen;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SettingButton {
+    Hints(HintLevel),
+    PracticePegs(usize),
+    Hud(bool),
+    Assist(bool),
+    MoveLog(bool),
+    Theme(BuiltinTheme),
+    Palette(Palette),
+    PegSkin(PegSkin),
+    AnimationSpeed(AnimationSpeed),
+    ReducedMotion(bool),
+    FpsOverlay(bool),
+    Scene3d(bool),
+    Leaderboard(bool),
+    LowPower(bool),
+    Zen(bool),
+    Coordinates(Coordinates),
+    UiScale(u32),
+    Close,
+}
+
+impl SettingButton {
+    fn selected(self, settings: &Settings) -> bool {
+        match self {
+            SettingButton::Hints(level) => level == settings.hints,
+            SettingButton::PracticePegs(pegs) => pegs == settings.practice_pegs,
+            SettingButton::Hud(hud) => hud == settings.hud,
+            SettingButton::Assist(assist) => assist == settings.assist,
+            SettingButton::MoveLog(move_log) => move_log == settings.move_log,
+            SettingButton::Theme(theme) => theme == settings.theme,
+            SettingButton::Palette(palette) => palette == settings.palette,
+            SettingButton::PegSkin(skin) => skin == settings.peg_skin,
+            SettingButton::AnimationSpeed(speed) => speed == settings.animation_speed,
+            SettingButton::ReducedMotion(reduced) => reduced == settings.reduced_motion,
+            SettingButton::FpsOverlay(fps_overlay) => fps_overlay == settings.fps_overlay,
+            SettingButton::Scene3d(scene_3d) => scene_3d == settings.scene_3d,
+            SettingButton::Leaderboard(leaderboard) => leaderboard == settings.leaderboard,
+            SettingButton::LowPower(low_power) => low_power == settings.low_power,
+            SettingButton::Zen(zen) => zen == settings.zen,
+            SettingButton::Coordinates(coordinates) => coordinates == settings.coordinates,
+            SettingButton::UiScale(scale) => scale == settings.ui_scale,
+            SettingButton::Close => false,
+        }
+    }
+}
+
+fn toggle_settings(
+    _: On<ToggleSettings>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    screen: Query<Entity, With<SettingsScreen>>,
+    settings: Res<Settings>,
+    total_progress: Res<TotalProgress>,
+) {
+    if let Ok(screen) = screen.single() {
+        commands.entity(screen).despawn();
+        return;
+    }
+    let font = |font_size| text_font(&asset_server, font_size);
+    let button = |button: SettingButton, label: &str| {
+        let selected = button.selected(&settings);
+        (
+            Button,
+            button,
+            Node {
+                padding: UiRect::axes(Val::Px(15.), Val::Px(8.)),
+                border_radius: BorderRadius::all(Val::Px(10.)),
+                ..default()
+            },
+            BackgroundColor(background(selected)),
+            children![(Text::new(label), font(25.), TextColor(foreground(selected)))],
+        )
+    };
+    let on_off = |button: fn(bool) -> SettingButton| {
+        vec![(button(true), "on".into()), (button(false), "off".into())]
+    };
+    let mut sections: Vec<(&str, Vec<(SettingButton, String)>)> = vec![
+        (
+            "hints",
+            HintLevel::ALL
+                .map(|level| (SettingButton::Hints(level), level.label().into()))
+                .into(),
+        ),
+        (
+            "practice",
+            PRACTICE_PEGS
+                .map(|pegs| (SettingButton::PracticePegs(pegs), format!("{pegs} pegs")))
+                .collect(),
+        ),
+        ("hud", on_off(SettingButton::Hud)),
+        ("assist", on_off(SettingButton::Assist)),
+        ("move log", on_off(SettingButton::MoveLog)),
+        (
+            "theme",
+            BuiltinTheme::ALL
+                .map(|theme| (SettingButton::Theme(theme), theme.label().into()))
+                .into(),
+        ),
+        (
+            "colours",
+            Palette::ALL
+                .map(|palette| (SettingButton::Palette(palette), palette.label().into()))
+                .into(),
+        ),
+        (
+            "pegs",
+            PegSkin::ALL
+                .map(|skin| {
+                    let label = if skin.unlocked(&total_progress) {
+                        skin.label().into()
+                    } else {
+                        format!("{}: {}", skin.label(), skin.requirement())
+                    };
+                    (SettingButton::PegSkin(skin), label)
+                })
+                .into(),
+        ),
+        (
+            "animations",
+            AnimationSpeed::ALL
+                .map(|speed| (SettingButton::AnimationSpeed(speed), speed.label().into()))
+                .into(),
+        ),
+        ("reduced motion", on_off(SettingButton::ReducedMotion)),
+        ("low power", on_off(SettingButton::LowPower)),
+        ("zen", on_off(SettingButton::Zen)),
+        (
+            "coordinates",
+            Coordinates::ALL
+                .map(|c| (SettingButton::Coordinates(c), c.label().into()))
+                .into(),
+        ),
+        (
+            "ui scale",
+            UI_SCALES
+                .map(|scale| (SettingButton::UiScale(scale), format!("{scale}%")))
+                .into(),
+        ),
+    ];
+    #[cfg(not(target_arch = "wasm32"))]
+    sections.push(("fps", on_off(SettingButton::FpsOverlay)));
+    #[cfg(feature = "scene_3d")]
+    sections.push(("3d", on_off(SettingButton::Scene3d)));
+    #[cfg(feature = "leaderboard")]
+    sections.push(("leaderboard", on_off(SettingButton::Leaderboard)));
+    commands
+        .spawn((
+            SettingsScreen,
+            // above the menus
+            GlobalZIndex(1),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(15.),
+                        max_width: Val::Px(1000.),
+                        padding: UiRect::all(Val::Px(30.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    // one row per setting, to fit on a screen
+                    for (title, buttons) in sections {
+                        parent
+                            .spawn(Node {
+                                flex_wrap: FlexWrap::Wrap,
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(15.),
+                                row_gap: Val::Px(10.),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(title),
+                                    font(30.),
+                                    TextColor::WHITE,
+                                    Node {
+                                        width: Val::Px(150.),
+                                        ..default()
+                                    },
+                                ));
+                                for (b, label) in buttons {
+                                    parent.spawn(button(b, &label));
+                                }
+                            });
+                    }
+                    parent
+                        .spawn(Node {
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(button(SettingButton::Close, "close"));
+                        });
+                });
+        });
+}
+
+fn background(selected: bool) -> Color {
+    if selected { Color::WHITE } else { Color::BLACK }
+}
+
+fn foreground(selected: bool) -> Color {
+    background(!selected)
+}
+
+fn handle_setting_buttons(
+    buttons: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+    mut settings: ResMut<Settings>,
+    total_progress: Res<TotalProgress>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            SettingButton::Hints(level) => settings.hints = level,
+            SettingButton::PracticePegs(pegs) => settings.practice_pegs = pegs,
+            SettingButton::Hud(hud) => settings.hud = hud,
+            SettingButton::Assist(assist) => settings.assist = assist,
+            SettingButton::MoveLog(move_log) => settings.move_log = move_log,
+            SettingButton::Theme(theme) => settings.theme = theme,
+            SettingButton::Palette(palette) => settings.palette = palette,
+            SettingButton::PegSkin(skin) => {
+                if skin.unlocked(&total_progress) {
+                    settings.peg_skin = skin;
+                }
+            }
+            SettingButton::AnimationSpeed(speed) => settings.animation_speed = speed,
+            SettingButton::ReducedMotion(reduced) => settings.reduced_motion = reduced,
+            SettingButton::FpsOverlay(fps_overlay) => settings.fps_overlay = fps_overlay,
+            SettingButton::Scene3d(scene_3d) => settings.scene_3d = scene_3d,
+            SettingButton::Leaderboard(leaderboard) => settings.leaderboard = leaderboard,
+            SettingButton::LowPower(low_power) => settings.low_power = low_power,
+            SettingButton::Zen(zen) => settings.zen = zen,
+            SettingButton::Coordinates(coordinates) => settings.coordinates = coordinates,
+            SettingButton::UiScale(scale) => settings.ui_scale = scale,
+            SettingButton::Close => commands.trigger(ToggleSettings),
+        }
+    }
+}
+
+fn highlight_selected(
+    settings: Res<Settings>,
+    buttons: Query<(&SettingButton, &mut BackgroundColor, &Children)>,
+    mut text_colors: Query<&mut TextColor>,
+) {
+    for (button, mut background_color, children) in buttons {
+        let selected = button.selected(&settings);
+        background_color.0 = background(selected);
+        for child in children {
+            if let Ok(mut text_color) = text_colors.get_mut(*child) {
+                text_color.0 = foreground(selected);
+            }
+        }
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn load_settings(mut commands: Commands) {
+    let Some(path) = path() else {
+        return;
+    };
+    let Ok(ron) = fs::read_to_string(&path) else {
+        return;
+    };
+    match ron::from_str::<Settings>(&ron) {
+        Ok(settings) => {
+            info!("loaded settings from {path:?}");
+            commands.insert_resource(settings);
+        }
+        Err(e) => warn!("ignoring settings file {path:?}: {e}"),
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    let Some(path) = path() else {
+        return;
+    };
+    if let Err(e) = try_save_settings(&path, &settings) {
+        warn!("could not save settings to {path:?}: {e}");
+    }
+}
+
+fn try_save_settings(path: &Path, settings: &Settings) -> io::Result<()> {
+    let ron = ron::ser::to_string_pretty(settings, default()).map_err(io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, ron)
+}
@@ -0,0 +1,305 @@
+use bevy::{
+    input::{
+        ButtonState, InputSystems,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use solitaire_solver::{Board, Solution};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    menu::{self, text_font},
+    replay::{Replay, WatchReplay},
+};
+
+// A game is shared as a short code: the start in its compressed
+// representation (5 bytes) followed by one byte per move, in url safe base64.
+// On the web the code is put into the fragment of the url, so the link
+// itself can be shared, and a link with a code opens its replay.
+
+pub struct SharePlugin;
+
+impl Plugin for SharePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(share);
+        app.add_observer(import);
+        app.add_systems(
+            PreUpdate,
+            capture_keys
+                .after(InputSystems)
+                .run_if(resource_exists::<ImportInput>),
+        );
+        app.add_systems(
+            Update,
+            (
+                type_code.run_if(resource_exists::<ImportInput>),
+                handle_share_buttons,
+            )
+                .chain(),
+        );
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Update, import_from_url.run_if(run_once));
+    }
+}
+
+/// shows the code of the current game
+#[derive(Default, Event)]
+pub struct Share;
+
+/// asks for a code and replays it
+#[derive(Default, Event)]
+pub struct Import;
+
+/// the code typed so far, while importing
+#[derive(Resource)]
+struct ImportInput(String);
+
+#[derive(Component)]
+struct ShareDialog;
+
+#[derive(Component)]
+struct CodeText;
+
+#[derive(Component)]
+struct ErrorText;
+
+#[derive(Component, Clone, Copy)]
+enum ShareButton {
+    Load,
+    Close,
+}
+
+impl ShareButton {
+    fn label(self) -> &'static str {
+        match self {
+            ShareButton::Load => "load",
+            ShareButton::Close => "close",
+        }
+    }
+}
+
+const PLACEHOLDER: &str = "type a code";
+
+/// bytes of the start constellation
+const START_BYTES: usize = Board::SLOTS.div_ceil(8);
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// url safe base64 without padding
+fn to_base64(bytes: &[u8]) -> String {
+    let mut base64 = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            base64.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    base64
+}
+
+fn from_base64(base64: &str) -> Result<Vec<u8>, &'static str> {
+    let mut bytes = vec![];
+    let (mut n, mut bits) = (0u32, 0);
+    for c in base64.bytes() {
+        let Some(value) = ALPHABET.iter().position(|&a| a == c) else {
+            return Err("invalid character in code");
+        };
+        n = n << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((n >> bits) as u8);
+            n &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn encode(start: Board, moves: &Solution) -> String {
+    let start = start.to_compressed_repr().to_le_bytes();
+    let bytes = [&start[..START_BYTES], &moves.to_bytes()].concat();
+    to_base64(&bytes)
+}
+
+/// the game of a code, if all of its moves can be played
+pub fn decode(code: &str) -> Result<Replay, &'static str> {
+    let bytes = from_base64(code.trim())?;
+    if bytes.len() < START_BYTES {
+        return Err("code is too short");
+    }
+    let (start, moves) = bytes.split_at(START_BYTES);
+    let mut compressed = [0; 8];
+    compressed[..START_BYTES].copy_from_slice(start);
+    let start = Board::try_from_compressed_repr(u64::from_le_bytes(compressed))
+        .ok_or("invalid start position")?;
+    let moves = Solution::from_bytes(moves)?;
+    if moves.validate(start).is_err() {
+        return Err("the moves can not be played");
+    }
+    Ok(Replay {
+        start,
+        moves,
+        finished: None,
+    })
+}
+
+fn share(
+    _: On<Share>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    let code = encode(solution.start(board.0), &solution.0);
+    info!("share code: {code}");
+    #[cfg(target_arch = "wasm32")]
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_hash(&code);
+    }
+    show_code(&mut commands, &asset_server, "share", code, &[]);
+}
+
+fn import(_: On<Import>, mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ImportInput(String::new()));
+    show_code(
+        &mut commands,
+        &asset_server,
+        "import",
+        PLACEHOLDER.into(),
+        &[ShareButton::Load],
+    );
+}
+
+/// closed with `ShareButton::Close`
+fn show_code(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    title: &str,
+    code: String,
+    buttons: &[ShareButton],
+) {
+    let buttons: Vec<_> = buttons
+        .iter()
+        .chain([&ShareButton::Close])
+        .map(|&b| (b, b.label()))
+        .collect();
+    // the error goes below the code
+    let code = (
+        CodeText,
+        Text::new(code),
+        children![(
+            ErrorText,
+            TextSpan::default(),
+            text_font(asset_server, 25.),
+            TextColor(Color::srgb(1., 0.3, 0.3)),
+        )],
+    );
+    menu::spawn_dialog(commands, asset_server, ShareDialog, title, code, &buttons);
+}
+
+/// the keys typed into a code are no shortcuts, `q` would quit
+/// and `f` toggle fullscreen (`type_code` reads the keyboard messages)
+fn capture_keys(mut keys: ResMut<ButtonInput<KeyCode>>) {
+    keys.clear();
+}
+
+fn type_code(
+    mut keys: MessageReader<KeyboardInput>,
+    mut input: ResMut<ImportInput>,
+    mut code_text: Query<&mut Text, With<CodeText>>,
+    mut error_text: Query<&mut TextSpan, With<ErrorText>>,
+    dialogs: Query<Entity, With<ShareDialog>>,
+    mut commands: Commands,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Character(c) => input.0.push_str(c),
+            Key::Backspace => {
+                input.0.pop();
+            }
+            Key::Enter => match load(&mut commands, &input.0) {
+                Ok(()) => close(&mut commands, &dialogs),
+                Err(e) => error_text
+                    .iter_mut()
+                    .for_each(|mut text| text.0 = format!("\n{e}")),
+            },
+            Key::Escape => close(&mut commands, &dialogs),
+            _ => {}
+        }
+    }
+    if input.is_changed() {
+        let code = if input.0.is_empty() {
+            PLACEHOLDER
+        } else {
+            &input.0
+        };
+        for mut text in &mut code_text {
+            text.0 = code.into();
+        }
+    }
+}
+
+/// replays the game of `code`
+fn load(commands: &mut Commands, code: &str) -> Result<(), &'static str> {
+    let replay = decode(code)?;
+    commands.trigger(WatchReplay(replay));
+    Ok(())
+}
+
+fn close(commands: &mut Commands, dialogs: &Query<Entity, With<ShareDialog>>) {
+    commands.remove_resource::<ImportInput>();
+    for dialog in dialogs {
+        commands.entity(dialog).despawn();
+    }
+}
+
+fn handle_share_buttons(
+    buttons: Query<(&Interaction, &ShareButton), Changed<Interaction>>,
+    input: Option<Res<ImportInput>>,
+    mut error_text: Query<&mut TextSpan, With<ErrorText>>,
+    dialogs: Query<Entity, With<ShareDialog>>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            ShareButton::Load => {
+                let Some(input) = &input else {
+                    continue;
+                };
+                match load(&mut commands, &input.0) {
+                    Ok(()) => return close(&mut commands, &dialogs),
+                    Err(e) => error_text
+                        .iter_mut()
+                        .for_each(|mut text| text.0 = format!("\n{e}")),
+                }
+            }
+            ShareButton::Close => return close(&mut commands, &dialogs),
+        }
+    }
+}
+
+/// opens the replay of a link with a code
+#[cfg(target_arch = "wasm32")]
+fn import_from_url(mut commands: Commands) {
+    let Some(hash) = web_sys::window().and_then(|window| window.location().hash().ok()) else {
+        return;
+    };
+    let code = hash.trim_start_matches('#');
+    if code.is_empty() {
+        return;
+    }
+    match decode(code) {
+        Ok(replay) => commands.trigger(WatchReplay(replay)),
+        Err(e) => warn!("ignoring code {code:?} from the url: {e}"),
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::{
+    ecs::{entity::Entities, entity_disabling::Disabled},
+    prelude::*,
+};
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    board::{BoardPosition, CircleComponent, Peg, ThemeColor, flat},
+    game_state::AppState,
+    settings::{PegSkin, Settings},
+};
+
+// Skins only add to the flat circle of a peg: a shine is drawn over it, or a
+// label follows it around. Labels are entities of their own on the board of
+// their peg, children would stay visible when their peg is captured and disabled.
+
+pub struct SkinsPlugin;
+
+impl Plugin for SkinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, spawn_labels);
+        app.add_systems(
+            PostUpdate,
+            (
+                follow_pegs.before(TransformSystems::Propagate),
+                draw_shine
+                    .run_if(flat.and(glossy))
+                    .after(TransformSystems::Propagate),
+            ),
+        );
+    }
+}
+
+/// shows the skin of a peg
+#[derive(Component)]
+struct SkinLabel {
+    peg: Entity,
+    /// while the peg is at rest
+    scale: f32,
+}
+
+/// above the peg, also while it is dragged
+const LABEL_OFFSET: f32 = 0.01;
+
+/// a symbol for each of the peg colours
+const ICONS: [&str; 4] = ["\u{f005}", "\u{f004}", "\u{f186}", "\u{f185}"];
+
+fn glossy(settings: Res<Settings>) -> bool {
+    settings.peg_skin == PegSkin::Glossy
+}
+
+/// numbered 1 to 33 in reading order
+fn hole_number(pos: Pos) -> usize {
+    Board::empty()
+        .holes()
+        .position(|hole| hole == pos)
+        .unwrap_or(0)
+        + 1
+}
+
+/// labels for new pegs, or all of them when the skin changes
+fn spawn_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    mut skin: Local<Option<PegSkin>>,
+    pegs: Query<(Entity, Ref<Peg>, &BoardPosition, &ThemeColor, &ChildOf), Allow<Disabled>>,
+    labels: Query<Entity, With<SkinLabel>>,
+) {
+    let changed = *skin != Some(settings.peg_skin);
+    if changed {
+        *skin = Some(settings.peg_skin);
+        for label in &labels {
+            commands.entity(label).despawn();
+        }
+    }
+    for (peg, added, pos, color, board) in &pegs {
+        if !changed && !added.is_added() {
+            continue;
+        }
+        let (text, font, font_size, scale) = match (settings.peg_skin, color) {
+            (PegSkin::Icons, ThemeColor::Peg(t)) => (
+                ICONS[*t % ICONS.len()].to_string(),
+                "fonts/Font Awesome 7 Free-Solid-900.otf",
+                100.,
+                0.0025,
+            ),
+            (PegSkin::Numbers, _) => (
+                hole_number(Pos::from(pos)).to_string(),
+                "fonts/latinmodern-math.otf",
+                50.,
+                0.006,
+            ),
+            _ => continue,
+        };
+        commands.spawn((
+            SkinLabel { peg, scale },
+            Text2d::new(text),
+            TextFont {
+                font: asset_server.load(font),
+                font_size,
+                ..default()
+            },
+            TextColor(Color::BLACK.with_alpha(0.6)),
+            Transform::from_scale(Vec3::splat(scale)),
+            Visibility::Hidden,
+            ChildOf(board.parent()),
+        ));
+    }
+}
+
+/// hidden with captured pegs and in the 3d scene, gone with removed ones
+fn follow_pegs(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    state: Res<State<AppState>>,
+    entities: &Entities,
+    pegs: Query<&Transform, (With<Peg>, Without<SkinLabel>)>,
+    labels: Query<(Entity, &SkinLabel, &mut Transform, &mut Visibility)>,
+) {
+    let flat = flat(Res::clone(&settings), Res::clone(&state));
+    for (entity, label, mut transform, mut visibility) in labels {
+        let Ok(peg) = pegs.get(label.peg) else {
+            if entities.contains(label.peg) {
+                visibility.set_if_neq(Visibility::Hidden);
+            } else {
+                commands.entity(entity).despawn();
+            }
+            continue;
+        };
+        // pegs grow when lifted and shrink when captured
+        transform.translation = peg.translation + LABEL_OFFSET * Vec3::Z;
+        transform.scale = Vec3::splat(label.scale * peg.scale.x);
+        visibility.set_if_neq(if flat {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// a soft light from the top left
+fn draw_shine(
+    mut painter: ShapePainter,
+    pegs: Query<(&GlobalTransform, &CircleComponent), With<Peg>>,
+) {
+    for (transform, circle) in &pegs {
+        let transform = transform.compute_transform();
+        let radius = circle.radius * transform.scale.x;
+        let center = transform.translation + LABEL_OFFSET * Vec3::Z;
+        painter.transform = Transform::from_translation(center);
+        painter.set_color(Color::WHITE.with_alpha(0.15));
+        painter.circle(radius * 0.8);
+        painter.transform =
+            Transform::from_translation(center + Vec3::new(-0.35, 0.35, 0.) * radius);
+        painter.set_color(Color::WHITE.with_alpha(0.5));
+        painter.circle(radius * 0.25);
+    }
+}
@@ -1,5 +1,7 @@
 use futures_lite::future::{self, block_on};
-use solitaire_solver::{HashMap, HashSet, SolutionMultiset};
+use std::sync::{Arc, Mutex};
+
+use solitaire_solver::{HashMap, HashSet, Progress, SolutionMultiset};
 
 use bevy::{
     ecs::world::CommandQueue,
@@ -10,23 +12,51 @@ use bevy::{
 };
 use solitaire_solver::Board;
 
+use crate::{disk_cache, lifecycle::SolverPause, zen::zen};
+
+#[cfg(target_arch = "wasm32")]
+use crate::web_cache;
+
 pub struct Solver;
 
 impl Plugin for Solver {
     fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<FeasibleProgress>();
         app.add_systems(Startup, create_solution_dag);
+        // the calculations building on the feasible constellations,
+        // held back in zen mode until it is left
         app.add_systems(
             Update,
-            calculate_random_move_chances.run_if(resource_added::<FeasibleConstellations>),
+            calculate_random_move_chances.run_if(
+                resource_exists::<FeasibleConstellations>
+                    .and(not(zen))
+                    .and(run_once),
+            ),
         );
         #[cfg(not(target_arch = "wasm32"))]
         app.add_systems(
             Update,
-            calculate_unique_solutions.run_if(resource_added::<FeasibleConstellations>),
+            calculate_unique_solutions.run_if(
+                resource_exists::<FeasibleConstellations>
+                    .and(not(zen))
+                    .and(run_once),
+            ),
+        );
+        app.add_systems(
+            Update,
+            calculate_unique_paths.run_if(
+                resource_exists::<FeasibleConstellations>
+                    .and(not(zen))
+                    .and(run_once),
+            ),
         );
         app.add_systems(
             Update,
-            calculate_unique_paths.run_if(resource_added::<FeasibleConstellations>),
+            count_winnable_positions.run_if(
+                resource_exists::<FeasibleConstellations>
+                    .and(not(zen))
+                    .and(run_once),
+            ),
         );
         app.add_systems(Update, poll_task);
     }
@@ -35,6 +65,16 @@ impl Plugin for Solver {
 #[derive(Resource)]
 pub struct FeasibleConstellations(pub HashSet<Board>);
 
+/// the feasible constellations indexed by their number of pegs,
+/// inserted along with `FeasibleConstellations`
+#[derive(Resource)]
+pub struct FeasibleByPegs(pub Vec<Vec<Board>>);
+
+/// the last step of calculating the feasible constellations,
+/// `None` before the first one and while loading them from the cache
+#[derive(Resource, Clone, Default)]
+pub struct FeasibleProgress(pub Arc<Mutex<Option<Progress>>>);
+
 #[derive(Resource)]
 pub struct RandomMoveChances(pub HashMap<Board, f64>);
 
@@ -44,24 +84,57 @@ pub struct UniqueSolutions(pub Vec<SolutionMultiset>);
 #[derive(Resource)]
 pub struct UniquePaths(pub HashMap<Board, u64>);
 
+/// number of winnable positions by number of pegs,
+/// counting every symmetry of a feasible constellation
+#[derive(Resource)]
+pub struct WinnablePositions(pub [usize; Board::SLOTS]);
+
 #[derive(Component)]
 struct BackgroundTask {
     task: Task<CommandQueue>,
 }
 
-fn create_solution_dag(mut commands: Commands, wake: Res<EventLoopProxyWrapper>) {
+fn create_solution_dag(
+    mut commands: Commands,
+    wake: Res<EventLoopProxyWrapper>,
+    pause: Res<SolverPause>,
+    progress: Res<FeasibleProgress>,
+) {
     info!("calculating feasible constellations ...");
     let thread_pool = AsyncComputeTaskPool::get();
     let entity = commands.spawn_empty().id();
     let wake = wake.clone();
+    let pause = pause.clone();
+    let progress = progress.clone();
     let task = thread_pool.spawn(async move {
-        let feasible = solitaire_solver::calculate_feasible_set(None);
+        #[cfg(not(target_arch = "wasm32"))]
+        let cached = disk_cache::load_feasible();
+        #[cfg(target_arch = "wasm32")]
+        let cached = web_cache::fetch_feasible().await;
+        let feasible = cached.unwrap_or_else(|| {
+            let report = |step| {
+                *progress.0.lock().unwrap() = Some(step);
+                // shown by the loading indicator
+                wake.send_event(WakeUp).unwrap();
+                pause.wait();
+            };
+            // sorted, so the cache file does not depend on the number of threads
+            let feasible =
+                solitaire_solver::calculate_feasible_set_with_progress(None, true, report);
+            disk_cache::store_feasible(&feasible);
+            feasible
+        });
 
         let feasible_hashset = HashSet::from_iter(feasible.iter().copied());
+        let mut by_pegs = vec![vec![]; Board::SLOTS + 1];
+        for board in feasible {
+            by_pegs[board.count_pegs()].push(board);
+        }
         let mut command_queue = CommandQueue::default();
         command_queue.push(move |world: &mut World| {
             info!("feasible constellations calculated!");
             world.insert_resource(FeasibleConstellations(feasible_hashset));
+            world.insert_resource(FeasibleByPegs(by_pegs));
             world.entity_mut(entity).remove::<BackgroundTask>();
         });
         wake.send_event(WakeUp).unwrap();
@@ -74,15 +147,24 @@ fn calculate_random_move_chances(
     mut commands: Commands,
     feasible: Res<FeasibleConstellations>,
     wake: Res<EventLoopProxyWrapper>,
+    pause: Res<SolverPause>,
 ) {
     info!("calculating P(\"success by random moves\") ...");
     let thread_pool = AsyncComputeTaskPool::get();
     let entity = commands.spawn_empty().id();
     let feasible = feasible.0.clone();
     let wake = wake.clone();
+    let pause = pause.clone();
     let task = thread_pool.spawn(async move {
-        let feasible = feasible.iter().copied().collect();
-        let p_random_chance = solitaire_solver::calculate_p_random_chance_success(feasible);
+        let p_random_chance = disk_cache::load_random_move_chances().unwrap_or_else(|| {
+            let feasible = feasible.iter().copied().collect();
+            let p_random_chance =
+                solitaire_solver::calculate_p_random_chance_success_with_progress(feasible, |_| {
+                    pause.wait()
+                });
+            disk_cache::store_random_move_chances(&p_random_chance);
+            p_random_chance
+        });
 
         let mut command_queue = CommandQueue::default();
         command_queue.push(move |world: &mut World| {
@@ -146,6 +228,35 @@ fn calculate_unique_paths(
     commands.entity(entity).insert(BackgroundTask { task });
 }
 
+fn count_winnable_positions(
+    mut commands: Commands,
+    feasible: Res<FeasibleConstellations>,
+    wake: Res<EventLoopProxyWrapper>,
+) {
+    let thread_pool = AsyncComputeTaskPool::get();
+    let entity = commands.spawn_empty().id();
+    let feasible = feasible.0.clone();
+    let wake = wake.clone();
+    let task = thread_pool.spawn(async move {
+        let mut winnable = [0; Board::SLOTS];
+        for board in feasible {
+            let mut symmetries = board.symmetries();
+            symmetries.sort_unstable();
+            let distinct = 1 + symmetries.windows(2).filter(|w| w[0] != w[1]).count();
+            winnable[board.count_pegs()] += distinct;
+        }
+
+        let mut command_queue = CommandQueue::default();
+        command_queue.push(move |world: &mut World| {
+            world.insert_resource(WinnablePositions(winnable));
+            world.entity_mut(entity).remove::<BackgroundTask>();
+        });
+        wake.send_event(WakeUp).unwrap();
+        command_queue
+    });
+    commands.entity(entity).insert(BackgroundTask { task });
+}
+
 fn poll_task(
     mut commands: Commands,
     tasks: Query<(Entity, &mut BackgroundTask)>,
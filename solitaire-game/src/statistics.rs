@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    menu::text_font,
+    solver::{UniqueSolutions, WinnablePositions},
+    total_progress::{MOVE_LEVELS, TotalProgress},
+};
+
+#[cfg(feature = "leaderboard")]
+use crate::leaderboard::ToggleLeaderboard;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::progress_file::{ExportProgress, ImportProgress};
+
+/// a screen summarizing everything played so far
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(toggle_statistics);
+        app.add_systems(Update, handle_statistics_buttons);
+    }
+}
+
+#[derive(Default, Event)]
+pub struct ToggleStatistics;
+
+#[derive(Component)]
+struct StatisticsScreen;
+
+#[derive(Component, Clone, Copy)]
+enum StatisticsButton {
+    #[cfg(not(target_arch = "wasm32"))]
+    Export,
+    #[cfg(not(target_arch = "wasm32"))]
+    Import,
+    #[cfg(feature = "leaderboard")]
+    Leaderboard,
+    Close,
+}
+
+impl StatisticsButton {
+    const ALL: &[Self] = &[
+        #[cfg(not(target_arch = "wasm32"))]
+        StatisticsButton::Export,
+        #[cfg(not(target_arch = "wasm32"))]
+        StatisticsButton::Import,
+        #[cfg(feature = "leaderboard")]
+        StatisticsButton::Leaderboard,
+        StatisticsButton::Close,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            StatisticsButton::Export => "export",
+            #[cfg(not(target_arch = "wasm32"))]
+            StatisticsButton::Import => "import",
+            #[cfg(feature = "leaderboard")]
+            StatisticsButton::Leaderboard => "leaderboard",
+            StatisticsButton::Close => "close",
+        }
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// a bar filled by `fraction`
+fn progress_bar(fraction: f64, width: f32, height: f32) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            border_radius: BorderRadius::all(Val::Px(height / 2.)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE.with_alpha(0.2)),
+        children![(
+            Node {
+                width: Val::Percent(100. * fraction as f32),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0., 0.8, 0.)),
+        )],
+    )
+}
+
+fn toggle_statistics(
+    _: On<ToggleStatistics>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    screen: Query<Entity, With<StatisticsScreen>>,
+    total_progress: Res<TotalProgress>,
+    winnable: Option<Res<WinnablePositions>>,
+    unique_solutions: Option<Res<UniqueSolutions>>,
+) {
+    if let Ok(screen) = screen.single() {
+        commands.entity(screen).despawn();
+        return;
+    }
+    let font = |font_size| text_font(&asset_server, font_size);
+    let unique = match &unique_solutions {
+        Some(possible) => format!(
+            "{} of {}",
+            total_progress.unique_solutions.len(),
+            possible.0.len()
+        ),
+        None => total_progress.unique_solutions.len().to_string(),
+    };
+    let summary = format!(
+        "games played: {}\nwins: {}\nunique solutions: {unique}\nbest time: {}\nfewest hints: {}",
+        total_progress.games_played,
+        total_progress.num_solutions,
+        total_progress.best_time.map_or("-".into(), format_time),
+        total_progress
+            .fewest_hints
+            .map_or("-".into(), |hints| hints.to_string()),
+    );
+    let visited: usize = MOVE_LEVELS.map(|pegs| total_progress.visited(pegs)).sum();
+    let visited_fraction = |visited: usize, winnable: usize| visited as f64 / winnable as f64;
+    commands
+        .spawn((
+            StatisticsScreen,
+            // above the menus
+            GlobalZIndex(1),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(20.),
+                        max_width: Val::Px(800.),
+                        padding: UiRect::all(Val::Px(30.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("statistics"), font(40.), TextColor::WHITE));
+                    parent.spawn((Text::new(summary), font(30.), TextColor::WHITE));
+                    // the totals come with the feasible constellations
+                    if let Some(winnable) = &winnable {
+                        let total: usize = winnable.0[MOVE_LEVELS].iter().sum();
+                        let fraction = visited_fraction(visited, total);
+                        let text = format!(
+                            "you have personally visited {:.3}%\nof all winnable positions",
+                            100. * fraction
+                        );
+                        parent.spawn((
+                            Text::new(text),
+                            font(30.),
+                            TextLayout::new_with_justify(Justify::Center),
+                            TextColor::WHITE,
+                        ));
+                        parent.spawn(progress_bar(fraction, 500., 20.));
+                        parent.spawn((Text::new("by pegs left"), font(30.), TextColor::WHITE));
+                        parent
+                            .spawn(Node {
+                                flex_wrap: FlexWrap::Wrap,
+                                justify_content: JustifyContent::Center,
+                                column_gap: Val::Px(10.),
+                                row_gap: Val::Px(10.),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for pegs in MOVE_LEVELS.rev() {
+                                    let fraction = visited_fraction(
+                                        total_progress.visited(pegs),
+                                        winnable.0[pegs],
+                                    );
+                                    parent.spawn((
+                                        Node {
+                                            flex_direction: FlexDirection::Column,
+                                            align_items: AlignItems::Center,
+                                            row_gap: Val::Px(4.),
+                                            width: Val::Px(70.),
+                                            ..default()
+                                        },
+                                        children![
+                                            (
+                                                Text::new(format!(
+                                                    "{pegs}: {:.0}%",
+                                                    100. * fraction
+                                                )),
+                                                font(20.),
+                                                TextColor::WHITE,
+                                            ),
+                                            progress_bar(fraction, 60., 6.),
+                                        ],
+                                    ));
+                                }
+                            });
+                    } else {
+                        let text = format!("{visited} positions visited");
+                        parent.spawn((Text::new(text), font(30.), TextColor::WHITE));
+                    }
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for &b in StatisticsButton::ALL {
+                                parent.spawn((
+                                    Button,
+                                    b,
+                                    Node {
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        border_radius: BorderRadius::all(Val::Px(10.)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::WHITE),
+                                    children![(
+                                        Text::new(b.label()),
+                                        font(30.),
+                                        TextColor(Color::BLACK)
+                                    )],
+                                ));
+                            }
+                        });
+                });
+        });
+}
+
+fn handle_statistics_buttons(
+    buttons: Query<(&Interaction, &StatisticsButton), Changed<Interaction>>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            #[cfg(not(target_arch = "wasm32"))]
+            StatisticsButton::Export => commands.trigger(ExportProgress),
+            #[cfg(not(target_arch = "wasm32"))]
+            StatisticsButton::Import => {
+                commands.trigger(ImportProgress);
+                // shown again with the merged progress
+                commands.trigger(ToggleStatistics);
+                commands.trigger(ToggleStatistics);
+            }
+            #[cfg(feature = "leaderboard")]
+            StatisticsButton::Leaderboard => commands.trigger(ToggleLeaderboard),
+            StatisticsButton::Close => commands.trigger(ToggleStatistics),
+        }
+    }
+}
@@ -5,9 +5,17 @@ use bevy::{
 use num_format::{Locale, ToFormattedString};
 
 use crate::{
-    CurrentBoard, WorldSpaceViewPort,
-    solver::{FeasibleConstellations, RandomMoveChances, UniquePaths, UniqueSolutions},
-    total_progress::{PossibleUniqueSolutions, TotalProgress},
+    CurrentBoard, MainBoard,
+    hints::HintCount,
+    main_changed,
+    menu::text_font,
+    settings::Settings,
+    solver::{
+        FeasibleConstellations, RandomMoveChances, UniquePaths, UniqueSolutions, WinnablePositions,
+    },
+    total_progress::{MOVE_LEVELS, PossibleUniqueSolutions, TotalProgress},
+    ui_scale::UiScaled,
+    viewport::WorldSpaceViewPort,
 };
 
 #[derive(Default, Event)]
@@ -19,23 +27,27 @@ pub struct ToggleBookMarks;
 #[derive(Resource)]
 struct ShowStats;
 
+type StatsTexts<'w, 's> = Query<
+    'w,
+    's,
+    Entity,
+    (
+        Or<(
+            With<SolutionText>,
+            With<OverallSuccessRatioText>,
+            With<TotalProgressText>,
+            With<NextMoveChanceText>,
+            With<UniqueSolutionsText>,
+        )>,
+        Or<(With<Disabled>, Without<Disabled>)>,
+    ),
+>;
+
 fn toggle_stats(
     _: On<ToggleStats>,
     mut commands: Commands,
     show_stats: Option<Res<ShowStats>>,
-    stats: Query<
-        Entity,
-        (
-            Or<(
-                With<SolutionText>,
-                With<OverallSuccessRatioText>,
-                With<TotalProgressText>,
-                With<NextMoveChanceText>,
-                With<UniqueSolutionsText>,
-            )>,
-            Or<(With<Disabled>, Without<Disabled>)>,
-        ),
-    >,
+    stats: StatsTexts,
 ) {
     if show_stats.is_none() {
         info!("Hiding Stats");
@@ -61,6 +73,35 @@ fn toggle_stats(
     }
 }
 
+/// hidden in zen mode, afterwards shown again if they were before
+fn hide_in_zen(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut zen: Local<bool>,
+    show_stats: Option<Res<ShowStats>>,
+    stats: StatsTexts,
+) {
+    if *zen == settings.zen {
+        return;
+    }
+    *zen = settings.zen;
+    // hidden by the button
+    if show_stats.is_some() {
+        return;
+    }
+    for e in &stats {
+        let mut e = commands.entity(e);
+        if settings.zen {
+            e.insert(Disabled);
+        } else {
+            e.remove::<Disabled>();
+        }
+    }
+    if !settings.zen {
+        commands.trigger(UpdateStats);
+    }
+}
+
 pub struct StatsPlugin;
 
 impl Plugin for StatsPlugin {
@@ -74,16 +115,19 @@ impl Plugin for StatsPlugin {
                     .or(resource_added::<UniqueSolutions>)
                     .or(resource_changed::<PossibleUniqueSolutions>)
                     .or(resource_added::<UniquePaths>)
-                    .or(resource_changed::<CurrentBoard>),
+                    .or(resource_added::<WinnablePositions>)
+                    .or(main_changed::<CurrentBoard>),
             ),
         );
         app.add_observer(update_next_move_chance);
+        app.add_observer(update_hint_count);
         app.add_observer(update_overall_success);
         app.add_observer(update_total_progress);
         app.add_observer(update_solution_count);
         app.add_systems(Update, update_solution_text_pos);
         app.add_observer(update_unique_solutions);
         app.add_observer(toggle_stats);
+        app.add_systems(Update, hide_in_zen.run_if(resource_changed::<Settings>));
     }
 }
 
@@ -105,6 +149,10 @@ struct UniqueSolutionsText;
 #[derive(Component)]
 struct OverallSuccessRatioText;
 
+/// half the size of the board in world space
+const BOARD_EXTENT: f32 = 3.9;
+
+/// where a text goes relative to the board, depending on the orientation of the screen
 #[derive(Component)]
 enum TextPosition {
     TopLeft,
@@ -121,27 +169,14 @@ fn update_stats(mut commands: Commands) {
 }
 
 fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let latin_modern = asset_server.load("fonts/latinmodern-math.otf");
-    let large_font = TextFont {
-        font: latin_modern.clone(),
-        font_size: 100.0,
-        ..default()
-    };
-    let medium_font = TextFont {
-        font: latin_modern.clone(),
-        font_size: 80.0,
-        ..default()
-    };
-    let small_font = TextFont {
-        font: latin_modern.clone(),
-        font_size: 50.0,
-        ..default()
-    };
+    let large_font = text_font(&asset_server, 100.);
+    let medium_font = text_font(&asset_server, 80.);
+    let small_font = text_font(&asset_server, 50.);
     commands
         .spawn((
             TextPosition::TopLeft,
             Text2d::new("\u{1D4AB}(\u{1D437}) \u{2248} "),
-            Transform::from_scale(Vec3::new(0.005, 0.005, 0.005)),
+            UiScaled(0.005),
             medium_font.clone(),
             TextLayout::new_with_justify(Justify::Center),
             Anchor::CENTER,
@@ -156,7 +191,7 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         .spawn((
             TextPosition::TopRight,
             Text2d::new("remaining\nunique solutions\n"),
-            Transform::from_scale(Vec3::new(0.005, 0.005, 0.005)),
+            UiScaled(0.005),
             small_font.clone(),
             TextLayout::new_with_justify(Justify::Center),
             Anchor::CENTER,
@@ -170,7 +205,7 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         .spawn((
             TextPosition::BottomLeft,
             Text2d::new(""),
-            Transform::from_scale(Vec3::new(0.005, 0.005, 0.005)),
+            UiScaled(0.005),
             large_font.clone(),
             TextLayout::new_with_justify(Justify::Center),
             Anchor::CENTER,
@@ -180,12 +215,13 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         .with_child((
             TextSpan("moves lead to feasible\nconstellations".into()),
             small_font.clone(),
-        ));
+        ))
+        .with_child((TextSpan("".into()), small_font.clone()));
     commands
         .spawn((
             TextPosition::BottomRight,
-            Text2d::new("you have seen "),
-            Transform::from_scale(Vec3::new(0.004, 0.004, 0.004)),
+            Text2d::new("you have personally visited "),
+            UiScaled(0.004),
             small_font.clone(),
             TextLayout::new(Justify::Center, LineBreak::WordBoundary),
             TextBounds::from(Vec2::new(600.0, 300.0)),
@@ -194,7 +230,7 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         ))
         .with_child((TextSpan("?%".into()), large_font.clone()))
         .with_child((
-            TextSpan(" of feasible constellations".into()),
+            TextSpan(" of all winnable positions".into()),
             small_font.clone(),
         ))
         .with_child((TextSpan("".into()), small_font.clone()));
@@ -202,7 +238,7 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         .spawn((
             TextPosition::AboveOrLeft,
             Text2d::new("you have found "),
-            Transform::from_scale(Vec3::new(0.005, 0.005, 0.005)),
+            UiScaled(0.005),
             small_font.clone(),
             TextLayout::new(Justify::Center, LineBreak::WordBoundary),
             TextBounds::from(Vec2::new(600.0, 300.0)),
@@ -215,66 +251,44 @@ fn add_text(mut commands: Commands, asset_server: Res<AssetServer>) {
         .with_child((TextSpan(" of which are unique!".into()), small_font.clone()));
 }
 
+/// beside the board on landscape screens, in two columns left and right of it,
+/// and below it on portrait screens, in two rows
 fn update_solution_text_pos(
     ws_view_port: Res<WorldSpaceViewPort>,
-    camera: Single<&Camera>,
     text: Query<(&mut Transform, &TextPosition)>,
 ) {
-    let camera = *camera;
-    let board_tl = Vec2::new(-1.5, 1.5);
-    let board_tr = Vec2::new(1.5, 1.5);
-    let board_bl = Vec2::new(-1.5, -1.5);
-    let board_br = Vec2::new(1.5, -1.5);
-
-    let Some(view_port) = camera.logical_viewport_rect() else {
-        return;
-    };
-
+    let half_width = ws_view_port.width() / 2.0;
+    let half_height = ws_view_port.height() / 2.0;
+    let landscape = half_width > half_height;
+    let center = (ws_view_port.top_left + ws_view_port.bottom_right).xy() / 2.0;
+    let beside = (half_width + BOARD_EXTENT) / 2.0;
+    let below = half_height - BOARD_EXTENT;
     for (mut transform, pos) in text {
-        let (a, b) = match pos {
-            TextPosition::TopLeft => (ws_view_port.top_left, board_tl),
-            TextPosition::TopRight => (ws_view_port.top_right, board_tr),
-            TextPosition::BottomLeft => (ws_view_port.bottom_left, board_bl),
-            TextPosition::BottomRight => (ws_view_port.bottom_right, board_br),
-            TextPosition::AboveOrLeft => {
-                if view_port.width() > view_port.height() {
-                    // landscape
-                    (
-                        (ws_view_port.top_left + ws_view_port.bottom_left) / 2.0,
-                        Vec2::new(-3.5, 0.0),
-                    )
-                } else {
-                    // portrait
-                    (
-                        (ws_view_port.top_left + ws_view_port.top_right) / 2.0,
-                        Vec2::new(0.0, 3.5),
-                    )
-                }
-            }
-            TextPosition::BelowOrRight => {
-                if view_port.width() > view_port.height() {
-                    // landscape
-                    (
-                        (ws_view_port.top_right + ws_view_port.bottom_right) / 2.0,
-                        Vec2::new(-3.5, 0.0),
-                    )
+        let pos = match pos {
+            TextPosition::AboveOrLeft if landscape => Vec2::new(-beside, 0.0),
+            TextPosition::AboveOrLeft => Vec2::new(0.0, (half_height + 3.5) / 2.0),
+            TextPosition::BelowOrRight if landscape => Vec2::new(beside, 0.0),
+            TextPosition::BelowOrRight => Vec2::new(0.0, -(half_height + 3.5) / 2.0),
+            corner => {
+                let left = matches!(corner, TextPosition::TopLeft | TextPosition::BottomLeft);
+                let top = matches!(corner, TextPosition::TopLeft | TextPosition::TopRight);
+                let side = if left { -1.0 } else { 1.0 };
+                if landscape {
+                    Vec2::new(side * beside, if top { 1.5 } else { -1.5 })
                 } else {
-                    // portrait
-                    (
-                        (ws_view_port.bottom_left + ws_view_port.bottom_right) / 2.0,
-                        Vec2::new(0.0, -3.5),
-                    )
+                    let row = if top { 1.0 } else { 2.0 };
+                    Vec2::new(side * half_width / 2.0, -BOARD_EXTENT - below * row / 3.0)
                 }
             }
         };
-        transform.translation = Vec3::from(((a.xy() + b) / 2.0, 1.5));
+        transform.translation = Vec3::from((center + pos, 1.5));
     }
 }
 
 fn update_overall_success(
     _trigger: On<UpdateStats>,
     overall_success_text: Query<Entity, With<OverallSuccessRatioText>>,
-    board: Res<CurrentBoard>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
     p_success: Option<Res<RandomMoveChances>>,
     mut writer: TextUiWriter,
     mut request_redraw: MessageWriter<RequestRedraw>,
@@ -306,7 +320,7 @@ fn update_overall_success(
 fn update_next_move_chance(
     _: On<UpdateStats>,
     next_move_text: Query<Entity, With<NextMoveChanceText>>,
-    board: Res<CurrentBoard>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
     feasible: Option<Res<FeasibleConstellations>>,
     mut writer: TextUiWriter,
     mut request_redraw: MessageWriter<RequestRedraw>,
@@ -329,22 +343,36 @@ fn update_next_move_chance(
     request_redraw.write(RequestRedraw);
 }
 
+fn update_hint_count(
+    _: On<UpdateStats>,
+    next_move_text: Query<Entity, With<NextMoveChanceText>>,
+    hint_count: Res<HintCount>,
+    mut writer: TextUiWriter,
+) {
+    for text in next_move_text {
+        *writer.text(text, 3) = match hint_count.0 {
+            0 => "".to_string(),
+            1 => "\n(1 hint used)".to_string(),
+            n => format!("\n({n} hints used)"),
+        };
+    }
+}
+
 fn update_total_progress(
     _: On<UpdateStats>,
     total_progress: Res<TotalProgress>,
     total_progress_text: Query<Entity, With<TotalProgressText>>,
-    feasible_constellations: Option<Res<FeasibleConstellations>>,
+    winnable: Option<Res<WinnablePositions>>,
     mut writer: TextUiWriter,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
-    if let Some(feasible_constellations) = feasible_constellations {
-        let progressed = total_progress.explored_states.len();
-        let feasible = feasible_constellations.0.len();
-        let explored = progressed as f64 / feasible as f64;
-        let explored_perc = explored * 100.0;
+    if let Some(winnable) = winnable {
+        let visited: usize = MOVE_LEVELS.map(|pegs| total_progress.visited(pegs)).sum();
+        let winnable: usize = winnable.0[MOVE_LEVELS].iter().sum();
+        let visited_perc = visited as f64 / winnable as f64 * 100.0;
         for text in total_progress_text {
-            *writer.text(text, 1) = format!("{explored_perc:.3}%");
-            *writer.text(text, 3) = format!(" ({progressed}/{feasible})");
+            *writer.text(text, 1) = format!("{visited_perc:.3}%");
+            *writer.text(text, 3) = format!(" ({visited}/{winnable})");
         }
     }
     request_redraw.write(RequestRedraw);
@@ -371,7 +399,7 @@ fn update_unique_solutions(
     unique_solutions_text: Query<Entity, With<UniqueSolutionsText>>,
     unique_solutions: Res<PossibleUniqueSolutions>,
     unique_paths: Option<Res<UniquePaths>>,
-    current_board: Res<CurrentBoard>,
+    current_board: Single<&CurrentBoard, With<MainBoard>>,
     mut writer: TextUiWriter,
     mut request_redraw: MessageWriter<RequestRedraw>,
 ) {
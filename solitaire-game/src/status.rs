@@ -1,7 +1,18 @@
-use bevy::{prelude::*, sprite::Anchor};
-use bevy_vector_shapes::{prelude::ShapePainter, shapes::DiscPainter};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    sprite::Anchor,
+    window::{PrimaryWindow, RequestRedraw},
+};
+use bevy_vector_shapes::prelude::*;
 
-use crate::{CurrentSolution, viewport_to_world};
+use crate::{
+    CurrentSolution, MainBoard, RedoMoves,
+    buttons::{RedoEvent, UndoEvent},
+    game_state::in_game,
+    menu::text_font,
+    viewport_to_world,
+};
 
 pub struct StatusPlugin;
 
@@ -11,6 +22,43 @@ impl Plugin for StatusPlugin {
         app.add_systems(Update, update_text);
         app.add_systems(Update, update_text_pos);
         app.add_systems(Update, draw_solution);
+        app.add_systems(
+            Update,
+            (scrub.run_if(in_game), step_through_history).chain(),
+        );
+    }
+}
+
+/// the number of moves the history is being scrubbed to
+#[derive(Resource)]
+struct ScrubTarget(usize);
+
+/// present while the pointer is held down on the timeline
+#[derive(Resource)]
+struct Scrubbing;
+
+/// the mouse or the first touch
+#[derive(SystemParam)]
+struct Pointer<'w, 's> {
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    window: Single<'w, 's, &'static Window, With<PrimaryWindow>>,
+}
+
+impl Pointer<'_, '_> {
+    fn position(&self) -> Option<Vec2> {
+        match self.touches.first_pressed_position() {
+            Some(pos) => Some(pos),
+            None => self.window.cursor_position(),
+        }
+    }
+
+    fn just_pressed(&self) -> bool {
+        self.mouse.just_pressed(MouseButton::Left) || self.touches.any_just_pressed()
+    }
+
+    fn pressed(&self) -> bool {
+        self.mouse.pressed(MouseButton::Left) || self.touches.iter().next().is_some()
     }
 }
 
@@ -18,12 +66,7 @@ impl Plugin for StatusPlugin {
 struct MoveText(usize);
 
 fn init_text(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let latin_modern = asset_server.load("fonts/latinmodern-math.otf");
-    let small_font = TextFont {
-        font: latin_modern.clone(),
-        font_size: 50.0,
-        ..default()
-    };
+    let small_font = text_font(&asset_server, 50.);
     for i in 0..31 {
         commands.spawn((
             Text2d::new(""),
@@ -37,7 +80,10 @@ fn init_text(mut commands: Commands, asset_server: Res<AssetServer>) {
     }
 }
 
-fn update_text(moves: Query<(&mut Text2d, &MoveText)>, solution: Res<CurrentSolution>) {
+fn update_text(
+    moves: Query<(&mut Text2d, &MoveText)>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
     for (mut t, m) in moves {
         if m.0 < solution.0.len() {
             t.0 = format!("{}", solution.0[m.0]);
@@ -49,8 +95,8 @@ fn update_text(moves: Query<(&mut Text2d, &MoveText)>, solution: Res<CurrentSolu
 
 fn update_text_pos(
     moves: Query<(&mut Transform, &MoveText)>,
-    solution: Res<CurrentSolution>,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
 ) {
     let (cam, gt) = &*camera_query;
     if let Some(view_port) = cam.logical_viewport_rect() {
@@ -61,26 +107,103 @@ fn update_text_pos(
     }
 }
 
+/// played moves are filled, moves which can be redone grey
+/// and the current position is marked with a ring
 fn draw_solution(
-    solution: Res<CurrentSolution>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    redo: Single<&RedoMoves, With<MainBoard>>,
     mut painter: ShapePainter,
-    camera_query: Single<(&Camera, &GlobalTransform)>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
 ) {
     let (cam, gt) = &*camera_query;
     if let Some(view_port) = cam.logical_viewport_rect() {
-        for (i, _mov) in solution.0.clone().into_iter().enumerate() {
+        let played = solution.0.len();
+        for i in 0..solution.0.total() {
             let pos = pos(cam, gt, view_port, i, &*solution);
             painter.set_translation(pos);
             painter.set_color(Color::WHITE);
             painter.circle(0.07);
-            if i >= solution.0.len() {
-                painter.set_color(Color::BLACK);
+            if i >= played {
+                let redoable = i < played + redo.0.len();
+                painter.set_color(if redoable {
+                    Color::srgb(0.4, 0.4, 0.4)
+                } else {
+                    Color::BLACK
+                });
                 painter.circle(0.07 * 0.9);
             }
+            if i + 1 == played {
+                painter.hollow = true;
+                painter.thickness = 0.02;
+                painter.circle(0.12);
+                painter.hollow = false;
+            }
         }
     }
 }
 
+/// pressing or dragging on the timeline picks the number of moves to go back or forward to
+fn scrub(
+    mut commands: Commands,
+    pointer: Pointer,
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    scrubbing: Option<Res<Scrubbing>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    redo: Single<&RedoMoves, With<MainBoard>>,
+) {
+    if !pointer.pressed() {
+        commands.remove_resource::<Scrubbing>();
+        return;
+    }
+    let (cam, gt) = &*camera_query;
+    let (Some(view_port), Some(world_pos)) = (
+        cam.logical_viewport_rect(),
+        pointer
+            .position()
+            .and_then(|pos| viewport_to_world(pos, cam, gt)),
+    ) else {
+        return;
+    };
+    let first = pos(cam, gt, view_port, 0, &solution);
+    let second = pos(cam, gt, view_port, 1, &solution);
+    let spacing = second.x - first.x;
+    // dot `i` stands for the position after move `i`, left of the first one is the start
+    let dot = ((world_pos.x - first.x) / spacing).round() as isize;
+    let on_timeline =
+        (world_pos.y - first.y).abs() < 0.2 && (-1..solution.0.total() as isize).contains(&dot);
+    if pointer.just_pressed() && on_timeline {
+        commands.insert_resource(Scrubbing);
+    } else if scrubbing.is_none() {
+        return;
+    }
+    let reachable = solution.0.len() + redo.0.len();
+    let target = (dot + 1).clamp(0, reachable as isize) as usize;
+    commands.insert_resource(ScrubTarget(target));
+}
+
+/// one move per frame, so every move is animated
+fn step_through_history(
+    mut commands: Commands,
+    target: Option<Res<ScrubTarget>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    redo: Single<&RedoMoves, With<MainBoard>>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let Some(target) = target else {
+        return;
+    };
+    let played = solution.0.len();
+    if target.0 < played {
+        commands.trigger(UndoEvent);
+    } else if target.0 > played && !redo.0.is_empty() {
+        commands.trigger(RedoEvent);
+    } else {
+        commands.remove_resource::<ScrubTarget>();
+        return;
+    }
+    request_redraw.write(RequestRedraw);
+}
+
 fn pos(
     cam: &Camera,
     global_transform: &GlobalTransform,
@@ -96,5 +219,7 @@ fn pos(
         viewport_to_world(pos_vp.1, cam, global_transform).unwrap_or_default()
             + Vec3::new(-0.6, 0.6, 0.0),
     );
-    start.lerp(end, i as f32 / end_idx as f32)
+    // along the bottom of the single player board, also while the camera looks at a race
+    let center = global_transform.translation().with_z(0.);
+    start.lerp(end, i as f32 / end_idx as f32) - center
 }
@@ -0,0 +1,95 @@
+// platform specific locations of files written by the game
+
+use std::path::PathBuf;
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+fn project_dirs() -> Option<directories::ProjectDirs> {
+    directories::ProjectDirs::from("de", "feschber", "peg-solitaire")
+}
+
+/// directory for data that can be recomputed at any time
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn cache_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// directory for data that can be recomputed at any time
+#[cfg(target_os = "android")]
+pub fn cache_dir() -> Option<PathBuf> {
+    let app = bevy::android::ANDROID_APP.get()?;
+    app.internal_data_path().map(|p| p.join("cache"))
+}
+
+/// no filesystem on the web
+#[cfg(target_arch = "wasm32")]
+pub fn cache_dir() -> Option<PathBuf> {
+    None
+}
+
+/// directory for data that must not get lost, e.g. the game in progress
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn data_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// directory for data that must not get lost, e.g. the game in progress
+#[cfg(target_os = "android")]
+pub fn data_dir() -> Option<PathBuf> {
+    let app = bevy::android::ANDROID_APP.get()?;
+    app.internal_data_path()
+}
+
+/// no filesystem on the web
+#[cfg(target_arch = "wasm32")]
+pub fn data_dir() -> Option<PathBuf> {
+    None
+}
+
+/// directory for screenshots and animations of games
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn pictures_dir() -> Option<PathBuf> {
+    let user_dirs = directories::UserDirs::new()?;
+    let pictures = user_dirs.picture_dir()?;
+    Some(pictures.join("peg-solitaire"))
+}
+
+/// directory for screenshots and animations of games
+#[cfg(target_os = "android")]
+pub fn pictures_dir() -> Option<PathBuf> {
+    let app = bevy::android::ANDROID_APP.get()?;
+    app.internal_data_path().map(|p| p.join("pictures"))
+}
+
+/// directory for files the user takes to another device, e.g. the exported progress
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn documents_dir() -> Option<PathBuf> {
+    let user_dirs = directories::UserDirs::new()?;
+    let documents = user_dirs.document_dir()?;
+    Some(documents.join("peg-solitaire"))
+}
+
+/// directory for files the user takes to another device, e.g. the exported progress
+#[cfg(target_os = "android")]
+pub fn documents_dir() -> Option<PathBuf> {
+    let app = bevy::android::ANDROID_APP.get()?;
+    app.internal_data_path().map(|p| p.join("documents"))
+}
+
+/// directory for preferences of the user
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub fn config_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// directory for preferences of the user
+#[cfg(target_os = "android")]
+pub fn config_dir() -> Option<PathBuf> {
+    let app = bevy::android::ANDROID_APP.get()?;
+    app.internal_data_path().map(|p| p.join("config"))
+}
+
+/// no filesystem on the web
+#[cfg(target_arch = "wasm32")]
+pub fn config_dir() -> Option<PathBuf> {
+    None
+}
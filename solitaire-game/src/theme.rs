@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::settings::{BuiltinTheme, Palette, Settings};
+
+/// keeps `Theme` in sync with the theme and palette in the settings
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>();
+        app.add_systems(Update, update_theme.run_if(resource_changed::<Settings>));
+    }
+}
+
+/// colours of everything on the board
+#[derive(Resource, Clone)]
+pub struct Theme {
+    /// behind the board, black or white following the system if `None`
+    pub background: Option<Color>,
+    pub board: Color,
+    /// holes are darker versions of the peg colours
+    pub hole_luminance: f32,
+    /// the dot in the middle of a hole
+    pub hole_center_luminance: f32,
+    /// one for each type of peg (see `Board::type_masks`)
+    pub pegs: [Color; 4],
+    /// moves that can still lead to a solution
+    pub good: Color,
+    /// moves that can not
+    pub bad: Color,
+    /// the rim of a board that can not be won anymore
+    pub warning: Color,
+    pub button_foreground: Color,
+    pub button_background: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(BuiltinTheme::default(), Palette::default())
+    }
+}
+
+fn hex(hex: &str) -> Color {
+    Srgba::hex(hex).unwrap().into()
+}
+
+impl Theme {
+    pub fn new(theme: BuiltinTheme, palette: Palette) -> Self {
+        let black = Color::WHITE.with_luminance(0.02);
+        let (background, board, hole_luminance, button_foreground, button_background) = match theme
+        {
+            BuiltinTheme::System => (None, black, 0.05, Color::WHITE, Color::BLACK),
+            BuiltinTheme::Black => (Some(Color::BLACK), black, 0.05, Color::WHITE, Color::BLACK),
+            BuiltinTheme::Slate => (
+                Some(hex("#1d2021")),
+                hex("#32302f"),
+                0.06,
+                hex("#ebdbb2"),
+                hex("#3c3836"),
+            ),
+            BuiltinTheme::Felt => (
+                Some(hex("#0b2e1f")),
+                hex("#14452f"),
+                0.04,
+                Color::WHITE,
+                hex("#0b2e1f"),
+            ),
+            BuiltinTheme::Wood => (
+                Some(hex("#1e140d")),
+                hex("#5c3b22"),
+                0.03,
+                hex("#f2e0c4"),
+                hex("#3b2616"),
+            ),
+        };
+        let pegs = match palette {
+            Palette::Classic => ["#b7bb26", "#fabe2f", "#fb4934", "#8ec07c"],
+            // https://jfly.uni-koeln.de/color/
+            Palette::Deuteranopia => ["#009e73", "#e69f00", "#cc79a7", "#56b4e9"],
+            Palette::Protanopia => ["#56b4e9", "#f0e442", "#cc79a7", "#ffffff"],
+            Palette::HighContrast => ["#ffff00", "#00ffff", "#ff00ff", "#ffffff"],
+            Palette::Mono => ["#ebdbb2"; 4],
+        };
+        let (good, bad) = match palette {
+            Palette::Classic | Palette::HighContrast | Palette::Mono => {
+                (Color::srgb(0., 1., 0.), Color::srgb(1., 0., 0.))
+            }
+            Palette::Deuteranopia => (hex("#56b4e9"), hex("#d55e00")),
+            Palette::Protanopia => (hex("#56b4e9"), hex("#f0e442")),
+        };
+        let warning = match palette {
+            Palette::Deuteranopia | Palette::Protanopia => hex("#e69f00"),
+            _ => hex("#ffbf00"),
+        };
+        // black on white stands out on any background
+        let (button_foreground, button_background) = match palette {
+            Palette::HighContrast => (Color::BLACK, Color::WHITE),
+            _ => (button_foreground, button_background),
+        };
+        Self {
+            background,
+            board,
+            hole_luminance,
+            hole_center_luminance: 0.3,
+            pegs: pegs.map(hex),
+            good,
+            bad,
+            warning,
+            button_foreground,
+            button_background,
+        }
+    }
+
+    /// from `bad` to `good` with the chance of winning `t`,
+    /// along the hue like a traffic light
+    pub fn chance(&self, t: f32) -> Color {
+        Hsla::from(self.bad).mix(&self.good.into(), t).into()
+    }
+
+    /// muted pegs and hints, for zen mode
+    pub fn calm(mut self) -> Self {
+        let calm = |color: Color| {
+            let hsla = Hsla::from(color);
+            hsla.with_saturation(0.4 * hsla.saturation)
+                .with_lightness(0.85 * hsla.lightness)
+                .into()
+        };
+        self.pegs = self.pegs.map(calm);
+        self.good = calm(self.good);
+        self.bad = calm(self.bad);
+        self.warning = calm(self.warning);
+        self
+    }
+}
+
+fn update_theme(settings: Res<Settings>, mut theme: ResMut<Theme>) {
+    let new = Theme::new(settings.theme, settings.palette);
+    *theme = if settings.zen { new.calm() } else { new };
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::RangeInclusive, time::Duration};
 
 use bevy::{
     app::Plugin,
@@ -10,10 +10,17 @@ use bevy::{
     },
     prelude::*,
 };
+use serde::{Deserialize, Serialize};
 use solitaire_solver::{Board, HashMap, Solution};
 
 use crate::{
-    CurrentBoard, CurrentSolution, MoveEvent, SolutionEvent,
+    CurrentBoard, CurrentSolution, MainBoard, MoveEvent, SolutionEvent,
+    auto_solve::AutoSolve,
+    board::NewGame,
+    duel::Duel,
+    game_state::{AppState, GameTime},
+    hints::HintCount,
+    main_changed,
     solver::{FeasibleConstellations, UniqueSolutions},
     stats::UpdateStats,
 };
@@ -24,7 +31,7 @@ use crate::{
 
 pub struct TotalProgressPlugin;
 
-#[derive(Resource)]
+#[derive(Resource, Serialize, Deserialize)]
 pub struct TotalProgress {
     /// all states that have ever been seen (->amount)
     pub explored_states: HashMap<Board, usize>,
@@ -36,6 +43,58 @@ pub struct TotalProgress {
     pub unique_solutions: HashSet<Solution>,
     /// number of times the boared has been solved
     pub num_solutions: u64,
+    /// number of games started from the standard start
+    #[serde(default)]
+    pub games_played: u64,
+    /// fastest solution
+    #[serde(default)]
+    pub best_time: Option<Duration>,
+    /// fewest best move hints needed for a solution
+    #[serde(default)]
+    pub fewest_hints: Option<u32>,
+}
+
+/// numbers of pegs reached by moves from the start
+pub const MOVE_LEVELS: RangeInclusive<usize> = 1..=Board::SLOTS - 2;
+
+impl TotalProgress {
+    /// winnable positions that have been seen with `pegs` pegs
+    pub fn visited(&self, pegs: usize) -> usize {
+        self.explored_states_by_pegs[pegs - 1].len()
+    }
+
+    /// adds the progress made on another device,
+    /// merging the same progress twice changes nothing
+    pub fn merge(&mut self, other: TotalProgress) {
+        for (ours, theirs) in [
+            (&mut self.explored_states, other.explored_states),
+            (
+                &mut self.normalized_explored_states,
+                other.normalized_explored_states,
+            ),
+        ] {
+            for (board, count) in theirs {
+                let ours = ours.entry(board).or_default();
+                *ours = (*ours).max(count);
+            }
+        }
+        for (ours, theirs) in self
+            .explored_states_by_pegs
+            .iter_mut()
+            .zip(other.explored_states_by_pegs)
+        {
+            ours.extend(theirs);
+        }
+        self.unique_solutions.extend(other.unique_solutions);
+        self.num_solutions = self.num_solutions.max(other.num_solutions);
+        self.games_played = self.games_played.max(other.games_played);
+        self.best_time = self.best_time.into_iter().chain(other.best_time).min();
+        self.fewest_hints = self
+            .fewest_hints
+            .into_iter()
+            .chain(other.fewest_hints)
+            .min();
+    }
 }
 
 impl Default for TotalProgress {
@@ -46,6 +105,9 @@ impl Default for TotalProgress {
             explored_states_by_pegs: Default::default(),
             unique_solutions: Default::default(),
             num_solutions: Default::default(),
+            games_played: Default::default(),
+            best_time: Default::default(),
+            fewest_hints: Default::default(),
         }
     }
 }
@@ -54,22 +116,54 @@ impl Plugin for TotalProgressPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.init_resource::<PossibleUniqueSolutions>();
         app.init_resource::<TotalProgress>();
+        app.init_resource::<NewSolution>();
+        app.init_resource::<GameCounted>();
+        app.add_observer(reset_game_counted);
         app.add_observer(update_total_progress);
         app.add_observer(update_solutions);
         app.add_systems(
             Update,
-            update_unique_solutions.run_if(resource_changed::<CurrentSolution>),
+            update_unique_solutions.run_if(main_changed::<CurrentSolution>),
         );
     }
 }
 
+/// whether the game in progress has been counted in `games_played`,
+/// so undoing and redoing its first move does not count it again
+#[derive(Default, Resource)]
+struct GameCounted(bool);
+
+fn reset_game_counted(_: On<NewGame>, mut counted: ResMut<GameCounted>) {
+    counted.0 = false;
+}
+
 fn update_total_progress(
-    _: On<MoveEvent>,
+    move_event: On<MoveEvent>,
     mut total_progress: ResMut<TotalProgress>,
+    mut counted: ResMut<GameCounted>,
     feasible: Option<Res<FeasibleConstellations>>,
-    board: Res<CurrentBoard>,
+    main: Single<(Entity, &CurrentBoard), With<MainBoard>>,
+    app_state: Res<State<AppState>>,
+    duel: Option<Res<Duel>>,
 ) {
+    let (main, board) = *main;
+    if move_event.board != main {
+        return;
+    }
+    // watching a replay or solutions while idle, learning the rules or a duel is no progress
+    let watching = matches!(
+        app_state.get(),
+        AppState::Replay | AppState::Tutorial | AppState::Attract
+    );
+    if watching || duel.is_some() {
+        return;
+    }
     let board = board.0;
+    // the first move from the standard start
+    if board.reverse_mov(move_event.mov) == Board::default() && !counted.0 {
+        total_progress.games_played += 1;
+        counted.0 = true;
+    }
     if let Some(feasible) = feasible {
         if feasible.0.contains(&board.normalize()) {
             *total_progress
@@ -88,8 +182,12 @@ fn update_total_progress(
 #[derive(Default, Resource)]
 pub struct PossibleUniqueSolutions(pub Option<usize>);
 
+/// whether the last solution had never been found before
+#[derive(Default, Resource)]
+pub struct NewSolution(pub bool);
+
 fn update_unique_solutions(
-    current_solution: Res<CurrentSolution>,
+    current_solution: Single<&CurrentSolution, With<MainBoard>>,
     unique_solutions: Option<Res<UniqueSolutions>>,
     mut commands: Commands,
     mut possible_unique_solutions: ResMut<PossibleUniqueSolutions>,
@@ -118,9 +216,21 @@ fn update_unique_solutions(
 fn update_solutions(
     solution: On<SolutionEvent>,
     mut total_progress: ResMut<TotalProgress>,
+    mut new_solution: ResMut<NewSolution>,
+    game_time: Res<GameTime>,
+    hint_count: Res<HintCount>,
+    auto_solve: Option<Res<AutoSolve>>,
     mut commands: Commands,
 ) {
-    total_progress.unique_solutions.insert(solution.0.clone());
+    new_solution.0 = total_progress.unique_solutions.insert(solution.0.clone());
     total_progress.num_solutions += 1;
     commands.trigger(UpdateStats);
+    // the solver finishing the game sets no records
+    if auto_solve.is_some() {
+        return;
+    }
+    let best_time = total_progress.best_time.get_or_insert(game_time.0);
+    *best_time = (*best_time).min(game_time.0);
+    let fewest_hints = total_progress.fewest_hints.get_or_insert(hint_count.0);
+    *fewest_hints = (*fewest_hints).min(hint_count.0);
 }
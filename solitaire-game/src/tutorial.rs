@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::NewGame,
+    challenge::Challenge,
+    game_state::AppState,
+    menu::text_font,
+    settings::{HintLevel, Settings},
+    storage,
+    total_progress::TotalProgress,
+};
+
+/// teaches the rules on a board with three pegs, then the hints and stats
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, show_on_first_launch);
+        app.add_systems(OnEnter(AppState::Tutorial), start_tutorial);
+        app.add_systems(OnExit(AppState::Tutorial), end_tutorial);
+        app.add_systems(
+            Update,
+            (handle_tutorial_buttons, advance, update_tutorial)
+                .chain()
+                .run_if(in_state(AppState::Tutorial)),
+        );
+    }
+}
+
+/// the step of the tutorial and the hint level to restore afterwards
+#[derive(Resource)]
+struct Tutorial {
+    step: Step,
+    hints: HintLevel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Jump,
+    Finish,
+    Hints,
+    Stats,
+    Done,
+}
+
+impl Step {
+    fn text(self, board: Board) -> &'static str {
+        match self {
+            Step::Jump => {
+                "pegs move by jumping over a neighbouring peg into the empty hole behind it.\n\
+                 the peg jumped over is removed. drag a peg to jump!"
+            }
+            Step::Finish if board.get_legal_moves().is_empty() => {
+                "no moves left, but more than one peg.\nundo with u or the undo button and jump differently."
+            }
+            Step::Finish => "the game is won with a single peg left in the center. jump again!",
+            Step::Hints => {
+                "with hints on, every possible move is marked:\n\
+                 green ones can still lead to a solution, red ones can not."
+            }
+            Step::Stats => {
+                "the numbers around the board (shown with the chart button) are\n\
+                 the chance of winning with random moves, the moves leading to\n\
+                 feasible positions, the solutions left and how much you have explored."
+            }
+            Step::Done => "that's it, have fun!",
+        }
+    }
+
+    /// the buttons below the text
+    fn buttons(self) -> &'static [TutorialButton] {
+        match self {
+            Step::Jump | Step::Finish => &[TutorialButton::Skip],
+            Step::Hints | Step::Stats => &[TutorialButton::Next, TutorialButton::Skip],
+            Step::Done => &[TutorialButton::Play],
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum TutorialButton {
+    Next,
+    Skip,
+    Play,
+}
+
+impl TutorialButton {
+    fn label(self) -> &'static str {
+        match self {
+            TutorialButton::Next => "next",
+            TutorialButton::Skip => "skip",
+            TutorialButton::Play => "play",
+        }
+    }
+}
+
+#[derive(Component)]
+struct TutorialPanel;
+
+/// solved by jumping twice, into the center
+fn mini_board() -> Board {
+    [(5, 4), (4, 4), (3, 5)]
+        .into_iter()
+        .fold(Board::empty(), |board, (row, col)| {
+            board.set(Pos::new(row, col))
+        })
+}
+
+/// only if the settings remember that it was shown
+/// and there is no game to continue
+fn show_on_first_launch(
+    settings: Res<Settings>,
+    total_progress: Res<TotalProgress>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let first_launch =
+        !settings.tutorial_seen && total_progress.games_played == 0 && board.0 == Board::default();
+    if first_launch && storage::config_dir().is_some() {
+        next_state.set(AppState::Tutorial);
+    }
+}
+
+fn start_tutorial(mut commands: Commands, settings: Res<Settings>) {
+    commands.trigger(NewGame(mini_board()));
+    commands.insert_resource(Challenge::default());
+    commands.insert_resource(Tutorial {
+        step: Step::Jump,
+        hints: settings.hints,
+    });
+}
+
+/// skipping the tutorial counts as having seen it
+fn end_tutorial(mut commands: Commands, tutorial: Res<Tutorial>, mut settings: ResMut<Settings>) {
+    settings.hints = tutorial.hints;
+    settings.tutorial_seen = true;
+    commands.trigger(NewGame(Board::default()));
+    commands.remove_resource::<Tutorial>();
+}
+
+fn handle_tutorial_buttons(
+    buttons: Query<(&Interaction, &TutorialButton), Changed<Interaction>>,
+    mut tutorial: ResMut<Tutorial>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            TutorialButton::Next => {
+                tutorial.step = match tutorial.step {
+                    Step::Hints => Step::Stats,
+                    _ => Step::Done,
+                }
+            }
+            TutorialButton::Skip => next_state.set(AppState::Menu),
+            TutorialButton::Play => next_state.set(AppState::Playing),
+        }
+    }
+}
+
+/// the first steps are done by playing
+fn advance(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    mut settings: ResMut<Settings>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+) {
+    match tutorial.step {
+        Step::Jump if !solution.0.is_empty() => tutorial.step = Step::Finish,
+        Step::Finish if board.0.count_pegs() == 1 => {
+            tutorial.step = Step::Hints;
+            // the hints of the first jump
+            commands.trigger(NewGame(mini_board()));
+            if settings.hints == HintLevel::Off {
+                settings.hints = HintLevel::Feasibility;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// a box at the bottom of the screen, spawned again for every step
+fn update_tutorial(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    tutorial: Res<Tutorial>,
+    board: Single<Ref<CurrentBoard>, With<MainBoard>>,
+    panel: Query<Entity, With<TutorialPanel>>,
+) {
+    if !tutorial.is_changed() && !board.is_changed() {
+        return;
+    }
+    for panel in panel {
+        commands.entity(panel).despawn();
+    }
+    let font = |font_size| text_font(&asset_server, font_size);
+    let step = tutorial.step;
+    commands
+        .spawn((
+            TutorialPanel,
+            DespawnOnExit(AppState::Tutorial),
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::End,
+                padding: UiRect::bottom(Val::Px(20.)),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.),
+                        max_width: Val::Px(800.),
+                        padding: UiRect::all(Val::Px(20.)),
+                        border_radius: BorderRadius::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(step.text(board.0)),
+                        font(25.),
+                        TextLayout::new_with_justify(Justify::Center),
+                        TextColor::WHITE,
+                    ));
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for &b in step.buttons() {
+                                parent.spawn((
+                                    Button,
+                                    b,
+                                    Node {
+                                        padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                                        border_radius: BorderRadius::all(Val::Px(10.)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::WHITE),
+                                    children![(
+                                        Text::new(b.label()),
+                                        font(30.),
+                                        TextColor(Color::BLACK)
+                                    )],
+                                ));
+                            }
+                        });
+                });
+        });
+}
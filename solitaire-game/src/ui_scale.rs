@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+
+// The menus are bevy ui and follow `UiScale`. Texts beside the board are in
+// world space, which only grows with the window, so they are scaled by hand
+// (see `UiScaled`). The circle buttons scale themselves.
+
+pub struct UiScalePlugin;
+
+impl Plugin for UiScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            apply_ui_scale.run_if(resource_changed::<Settings>),
+        );
+        app.add_systems(Update, scale_world_texts);
+    }
+}
+
+/// a world space text of this scale at 100%
+#[derive(Component)]
+#[require(Transform)]
+pub struct UiScaled(pub f32);
+
+fn apply_ui_scale(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    if ui_scale.0 != settings.ui_scale() {
+        ui_scale.0 = settings.ui_scale();
+    }
+}
+
+fn scale_world_texts(settings: Res<Settings>, texts: Query<(Ref<UiScaled>, &mut Transform)>) {
+    for (scaled, mut transform) in texts {
+        if scaled.is_added() || settings.is_changed() {
+            transform.scale = Vec3::splat(scaled.0 * settings.ui_scale());
+        }
+    }
+}
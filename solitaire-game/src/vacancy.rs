@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use bevy_vector_shapes::prelude::*;
+use solitaire_solver::{Board, Pos};
+
+use crate::{
+    CurrentBoard, CurrentSolution, MainBoard,
+    board::{BoardPosition, NewGame, PEG_POS, PEG_RADIUS},
+    challenge::Challenge,
+    input::RequestPegMove,
+};
+
+/// before the first move, clicking a peg moves the initial vacancy there
+pub struct VacancyPlugin;
+
+impl Plugin for VacancyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(choose_vacancy);
+        app.add_systems(PostUpdate, draw_winnable_vacancies);
+    }
+}
+
+/// vacancies from which the board can be solved,
+/// the center and the middle of the arms' ends
+/// (`calculate_first_solution_from` for every hole)
+const WINNABLE_VACANCIES: [Pos; 5] = [
+    Pos::new(0, 3),
+    Pos::new(3, 0),
+    Pos::new(3, 3),
+    Pos::new(3, 6),
+    Pos::new(6, 3),
+];
+
+fn single_vacancy(pos: Pos) -> Board {
+    Board::full() ^ Board::empty().set(pos)
+}
+
+/// whether the game has not started yet and still has a single vacancy,
+/// challenges come with their own
+fn choosing(board: Board, solution: &CurrentSolution, challenge: &Challenge) -> bool {
+    solution.0.is_empty() && board.count_pegs() == Board::SLOTS - 1 && challenge.0.is_none()
+}
+
+fn choose_vacancy(
+    move_request: On<RequestPegMove>,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    challenge: Res<Challenge>,
+    mut commands: Commands,
+) {
+    // a peg dropped where it was picked up
+    let pos: Pos = move_request.src.into();
+    if move_request.src != move_request.dst || !choosing(board.0, &solution, &challenge) {
+        return;
+    }
+    if board.0.occupied(pos) {
+        info!("moving the vacancy to {pos}");
+        commands.trigger(NewGame(single_vacancy(pos)));
+    }
+}
+
+/// marks the pegs which can be removed for a winnable start
+fn draw_winnable_vacancies(
+    mut painter: ShapePainter,
+    board: Single<&CurrentBoard, With<MainBoard>>,
+    solution: Single<&CurrentSolution, With<MainBoard>>,
+    challenge: Res<Challenge>,
+) {
+    if !choosing(board.0, &solution, &challenge) {
+        return;
+    }
+    painter.set_color(Color::srgb(0., 0.8, 0.));
+    for pos in WINNABLE_VACANCIES {
+        if board.0.occupied(pos) {
+            let world_pos = BoardPosition::from(pos).to_world_space();
+            painter.set_translation((world_pos, PEG_POS + 0.01).into());
+            painter.circle(PEG_RADIUS * 0.3);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use crate::{settings::Settings, viewport_to_world};
+
+// The corners of the visible area in world space, to place things like the buttons
+// relative to the edges of the screen. They only change when the window is resized
+// or rotated, entities anchored to them are laid out again when they do. Their
+// offsets grow with the ui scale, like the buttons placed with them. The corners
+// are those around the single player board, also while the camera looks at a race.
+
+pub struct ViewPortPlugin;
+
+impl Plugin for ViewPortPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                calc_view_port,
+                relayout.run_if(resource_exists::<WorldSpaceViewPort>),
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Resource, PartialEq)]
+pub struct WorldSpaceViewPort {
+    pub top_left: Vec3,
+    pub bottom_left: Vec3,
+    pub top_right: Vec3,
+    pub bottom_right: Vec3,
+}
+
+impl WorldSpaceViewPort {
+    pub fn width(&self) -> f32 {
+        self.top_left.distance(self.top_right)
+    }
+
+    pub fn height(&self) -> f32 {
+        self.top_left.distance(self.bottom_left)
+    }
+
+    fn anchor(&self, anchor: ViewPortAnchor) -> Vec3 {
+        match anchor {
+            ViewPortAnchor::Top => (self.top_left + self.top_right) / 2.0,
+            ViewPortAnchor::TopLeft => self.top_left,
+            ViewPortAnchor::TopRight => self.top_right,
+            ViewPortAnchor::BottomLeft => self.bottom_left,
+            ViewPortAnchor::BottomRight => self.bottom_right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(unused)]
+pub enum ViewPortAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// translation relative to an edge of the screen
+#[derive(Component)]
+pub struct ViewPortRelativeTranslation(pub ViewPortAnchor, pub Vec3);
+
+/// below this width (in world units) whatever sits at the top center
+/// would run into the corners, so it moves down a row
+const NARROW: f32 = 8.0;
+
+fn calc_view_port(
+    mut commands: Commands,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    current: Option<ResMut<WorldSpaceViewPort>>,
+) {
+    let (camera, transform) = *camera;
+    let Some(view_port) = camera.logical_viewport_rect() else {
+        return;
+    };
+    let top_left = view_port.min;
+    let bottom_right = view_port.max;
+    let top_right = top_left + Vec2::new(view_port.width(), 0.);
+    let bottom_left = top_left + Vec2::new(0., view_port.height());
+    let Some(top_left) = viewport_to_world(top_left, camera, transform) else {
+        return;
+    };
+    let Some(top_right) = viewport_to_world(top_right, camera, transform) else {
+        return;
+    };
+    let Some(bottom_left) = viewport_to_world(bottom_left, camera, transform) else {
+        return;
+    };
+    let Some(bottom_right) = viewport_to_world(bottom_right, camera, transform) else {
+        return;
+    };
+    let center = transform.translation().with_z(0.);
+    let view_port = WorldSpaceViewPort {
+        top_left: top_left - center,
+        bottom_left: bottom_left - center,
+        top_right: top_right - center,
+        bottom_right: bottom_right - center,
+    };
+    // only a changed view port causes a relayout
+    match current {
+        Some(mut current) => {
+            current.set_if_neq(view_port);
+        }
+        None => commands.insert_resource(view_port),
+    }
+}
+
+fn relayout(
+    view_port: Res<WorldSpaceViewPort>,
+    settings: Res<Settings>,
+    anchored: Query<(Ref<ViewPortRelativeTranslation>, &mut Transform)>,
+) {
+    let scale = settings.ui_scale();
+    let narrow = view_port.width() < NARROW * scale;
+    for (relative, mut transform) in anchored {
+        if !view_port.is_changed() && !relative.is_changed() && !settings.is_changed() {
+            continue;
+        }
+        let ViewPortRelativeTranslation(anchor, mut offset) = *relative;
+        if narrow && anchor == ViewPortAnchor::Top {
+            offset.y -= 1.0;
+        }
+        // only in the plane, the depth orders the buttons
+        offset = (offset.xy() * scale).extend(offset.z);
+        transform.translation = view_port.anchor(anchor) + offset;
+    }
+}
@@ -0,0 +1,100 @@
+use std::io::{self, Write};
+
+use bevy::log::{info, warn};
+use brotli_decompressor::DecompressorWriter;
+use js_sys::{Reflect, Uint8Array};
+use solitaire_solver::Board;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{ReadableStreamDefaultReader, Response};
+
+// The web has no cache directory, so the feasible constellations are fetched
+// next to the page instead of being calculated on the main thread. The file is
+// written by `peg-solitaire export --encoding brotli` and decoded while it is
+// still being downloaded.
+
+const FEASIBLE_URL: &str = "feasible.bin.br";
+
+/// `None` if the file is missing or broken, the set is calculated then
+pub async fn fetch_feasible() -> Option<Vec<Board>> {
+    match try_fetch_feasible().await {
+        Ok(feasible) => {
+            info!(
+                "fetched {} constellations from {FEASIBLE_URL}",
+                feasible.len()
+            );
+            Some(feasible)
+        }
+        Err(e) => {
+            warn!("could not fetch {FEASIBLE_URL}: {e}");
+            None
+        }
+    }
+}
+
+async fn try_fetch_feasible() -> Result<Vec<Board>, String> {
+    let window = web_sys::window().ok_or("no window")?;
+    let response: Response = JsFuture::from(window.fetch_with_str(FEASIBLE_URL))
+        .await
+        .and_then(|response| response.dyn_into())
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(format!("status {}", response.status()));
+    }
+    let body = response.body().ok_or("empty response")?;
+    let reader: ReadableStreamDefaultReader = body.get_reader().dyn_into().map_err(js_error)?;
+    let mut decoder = DecompressorWriter::new(Boards::default(), 1 << 16);
+    loop {
+        let chunk = JsFuture::from(reader.read()).await.map_err(js_error)?;
+        let done = Reflect::get(&chunk, &"done".into()).map_err(js_error)?;
+        if done.as_bool().unwrap_or(true) {
+            break;
+        }
+        let value = Reflect::get(&chunk, &"value".into()).map_err(js_error)?;
+        let bytes = Uint8Array::new(&value).to_vec();
+        decoder.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+    decoder.close().map_err(|e| e.to_string())?;
+    let boards = decoder.into_inner().map_err(|_| "truncated")?;
+    boards.finish().map_err(|e| e.to_string())
+}
+
+fn js_error(e: JsValue) -> String {
+    e.as_string().unwrap_or_else(|| format!("{e:?}"))
+}
+
+/// compressed boards as u64 (le), parsed as they are decoded
+#[derive(Default)]
+struct Boards {
+    boards: Vec<Board>,
+    /// the start of a board split between two chunks
+    partial: Vec<u8>,
+}
+
+impl Boards {
+    fn finish(self) -> io::Result<Vec<Board>> {
+        if !self.partial.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated"));
+        }
+        Ok(self.boards)
+    }
+}
+
+impl Write for Boards {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.partial.extend_from_slice(buf);
+        let mut entries = self.partial.chunks_exact(8);
+        for entry in &mut entries {
+            let compressed = u64::from_le_bytes(entry.try_into().unwrap());
+            let board = Board::try_from_compressed_repr(compressed)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid entry"))?;
+            self.boards.push(board);
+        }
+        self.partial = entries.remainder().to_vec();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
+use std::time::Duration;
+
 use bevy::{
     log::{Level, LogPlugin},
     prelude::*,
     window::{WindowMode, WindowTheme, WindowThemeChanged},
-    winit::WinitSettings,
+    winit::{UpdateMode, WinitSettings},
 };
 
+use crate::{quit::RequestQuit, settings::Settings, theme::Theme};
+
 pub struct MainWindow;
 
 impl Plugin for MainWindow {
@@ -74,26 +78,59 @@ impl Plugin for MainWindow {
         app.add_systems(Update, handle_exit);
         app.add_systems(Update, fullscreen_toggle);
         app.add_systems(Update, update_window_theme);
+        app.add_systems(
+            Update,
+            update_update_mode.run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+/// how often the app wakes up by itself while nothing moves,
+/// in low power mode only for input to the window itself
+pub fn idle_settings(low_power: bool) -> WinitSettings {
+    if low_power {
+        WinitSettings {
+            focused_mode: UpdateMode::reactive_low_power(Duration::from_secs(60)),
+            unfocused_mode: UpdateMode::reactive_low_power(Duration::from_secs(60)),
+        }
+    } else {
+        WinitSettings::desktop_app()
     }
 }
 
+/// while focused, the mode is adjusted for the clock in `hud`
+fn update_update_mode(settings: Res<Settings>, mut winit_settings: ResMut<WinitSettings>) {
+    let unfocused_mode = idle_settings(settings.low_power).unfocused_mode;
+    if winit_settings.unfocused_mode != unfocused_mode {
+        winit_settings.unfocused_mode = unfocused_mode;
+    }
+}
+
+/// the background of the theme,
+/// black or white following the system otherwise
 fn update_window_theme(
+    theme: Res<Theme>,
     mut theme_changed: MessageReader<WindowThemeChanged>,
+    mut system_theme: Local<Option<WindowTheme>>,
     mut clear_color: ResMut<ClearColor>,
 ) {
     for message in theme_changed.read() {
         info!("Theme Changed!");
-        match message.theme {
-            WindowTheme::Light => *clear_color = ClearColor(Color::WHITE),
-            WindowTheme::Dark => *clear_color = ClearColor(Color::BLACK),
-        }
+        *system_theme = Some(message.theme);
+    }
+    let color = theme.background.unwrap_or(match *system_theme {
+        Some(WindowTheme::Light) => Color::WHITE,
+        _ => Color::BLACK,
+    });
+    if clear_color.0 != color {
+        clear_color.0 = color;
     }
 }
 
-fn handle_exit(input: Res<ButtonInput<KeyCode>>, mut exit: MessageWriter<AppExit>) {
+fn handle_exit(input: Res<ButtonInput<KeyCode>>, mut commands: Commands) {
     if input.just_pressed(KeyCode::KeyQ) || input.all_just_pressed([KeyCode::AltLeft, KeyCode::F4])
     {
-        exit.write(AppExit::Success);
+        commands.trigger(RequestQuit);
     }
 }
 
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use bevy::{
+    input::touch::Touch,
+    prelude::*,
+    window::{PrimaryWindow, RequestRedraw},
+};
+
+use crate::{
+    board::BOARD_RADIUS,
+    game_state::in_game,
+    lifecycle::{SolverPause, Suspended},
+    settings::Settings,
+    viewport_to_world,
+};
+
+// Zen mode leaves only the board and what is needed to play on it: the stats,
+// the buttons for hints and the fps overlay are hidden where they are drawn,
+// the theme is muted and the solver sleeps. Calculations still missing are
+// started once zen mode is left again (see `zen`).
+//
+// Besides the settings, it is toggled by holding still beside the board.
+
+pub struct ZenPlugin;
+
+impl Plugin for ZenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LongPress>();
+        app.add_systems(PreUpdate, pause_solver.run_if(resource_changed::<Settings>));
+        app.add_systems(
+            Update,
+            (start_long_press, finish_long_press)
+                .chain()
+                .run_if(in_game),
+        );
+    }
+}
+
+/// time to hold still until zen mode is toggled
+const LONG_PRESS: Duration = Duration::from_secs(1);
+
+/// pixels the pointer may move during a long press
+const SLOP: f32 = 20.;
+
+/// a press beside the board, which may become a long one
+struct Press {
+    /// `None` for the mouse
+    touch: Option<u64>,
+    start: Vec2,
+    /// in real time
+    since: Duration,
+}
+
+#[derive(Default, Resource)]
+struct LongPress(Option<Press>);
+
+/// run condition for systems that stay quiet in zen mode
+pub fn zen(settings: Res<Settings>) -> bool {
+    settings.zen
+}
+
+/// the app in the background pauses the solver as well
+fn pause_solver(
+    settings: Res<Settings>,
+    suspended: Option<Res<Suspended>>,
+    solver_pause: Res<SolverPause>,
+) {
+    solver_pause.set(settings.zen || suspended.is_some());
+}
+
+fn start_long_press(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    time: Res<Time<Real>>,
+    mut long_press: ResMut<LongPress>,
+) {
+    let pressed = if mouse.just_pressed(MouseButton::Left) {
+        window.cursor_position().map(|pos| (None, pos))
+    } else {
+        let touch = touches.iter_just_pressed().next();
+        touch.map(|touch| (Some(touch.id()), touch.position()))
+    };
+    let Some((touch, start)) = pressed else {
+        return;
+    };
+    // pegs are held on the board while dragging them
+    let (camera, camera_transform) = *camera;
+    let beside_board = viewport_to_world(start, camera, camera_transform)
+        .is_some_and(|pos| pos.xy().length() > BOARD_RADIUS);
+    long_press.0 = beside_board.then(|| Press {
+        touch,
+        start,
+        since: time.elapsed(),
+    });
+}
+
+fn finish_long_press(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    time: Res<Time<Real>>,
+    mut long_press: ResMut<LongPress>,
+    mut settings: ResMut<Settings>,
+    mut request_redraw: MessageWriter<RequestRedraw>,
+) {
+    let Some(press) = &long_press.0 else {
+        return;
+    };
+    let pos = match press.touch {
+        Some(id) => touches.get_pressed(id).map(Touch::position),
+        None if mouse.pressed(MouseButton::Left) => window.cursor_position(),
+        None => None,
+    };
+    if pos.is_none_or(|pos| pos.distance(press.start) > SLOP) {
+        long_press.0 = None;
+        return;
+    }
+    if time.elapsed() - press.since < LONG_PRESS {
+        // nothing else wakes the app while holding still
+        request_redraw.write(RequestRedraw);
+        return;
+    }
+    long_press.0 = None;
+    settings.zen = !settings.zen;
+    info!("zen mode {}", if settings.zen { "on" } else { "off" });
+}
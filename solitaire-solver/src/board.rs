@@ -1,10 +1,10 @@
 use std::{
-    fmt::{Display, Formatter, Write},
+    fmt::{Display, Formatter},
     hash::Hash,
     ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not, Shl, Shr},
 };
 
-use crate::{Dir, Move};
+use crate::{Dir, Move, MoveError, Pos, RenderStyle};
 #[cfg(not(target_arch = "wasm32"))]
 use voracious_radix_sort::peeka_sort;
 use voracious_radix_sort::{
@@ -195,41 +195,147 @@ impl Hash for Board {
 
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for y in 0..Board::SIZE {
-            for x in 0..Board::SIZE {
-                let occupied = self.occupied((y, x));
-                let inbounds = Self::inbounds((y, x));
-                let c = match (occupied, inbounds) {
-                    (_, false) => ' ',
-                    (true, _) => 'o',
-                    (false, _) => '.',
-                };
-                f.write_char(' ')?;
-                f.write_char(c)?;
-                f.write_char(' ')?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        self.write_rendered(RenderStyle::default(), f)
     }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        const { Self::full().unset((Board::SIZE / 2, Board::SIZE / 2)) }
+        const { Self::full().unset(Pos::new(Board::SIZE / 2, Board::SIZE / 2)) }
+    }
+}
+
+/// boards are stored in their compressed representation,
+/// which is the same on every platform
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_compressed_repr())
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let compressed = u64::deserialize(deserializer)?;
+        Self::try_from_compressed_repr(compressed)
+            .ok_or_else(|| serde::de::Error::custom("invalid board"))
+    }
+}
+
+#[cfg(feature = "serde")]
 #[test]
+fn test_serde() {
+    let board = Board::default();
+    let mov = board.get_legal_moves()[0];
+    let json = serde_json::to_string(&(board, mov)).unwrap();
+    assert_eq!(
+        json,
+        r#"[8589869055,{"pos":{"row":1,"col":3},"skip":{"row":2,"col":3},"target":{"row":3,"col":3}}]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<(Board, Move)>(&json).unwrap(),
+        (board, mov)
+    );
+    assert!(serde_json::from_str::<Board>(&(1u64 << 33).to_string()).is_err());
+}
+
+#[test]
+fn test_check_move() {
+    let board = Board::default();
+    let check = |src: (Idx, Idx), dst: (Idx, Idx)| {
+        board.check_move(Pos::new(src.0, src.1), Pos::new(dst.0, dst.1))
+    };
+    assert!(check((1, 3), (3, 3)).is_ok());
+    assert_eq!(check((1, 3), (2, 3)), Err(MoveError::NotAJump));
+    assert_eq!(check((3, 3), (1, 3)), Err(MoveError::NoPeg));
+    assert_eq!(check((2, 2), (2, 4)), Err(MoveError::TargetOccupied));
+    let board = board.mov(check((1, 3), (3, 3)).unwrap());
+    assert_eq!(
+        board.check_move(Pos::new(0, 3), Pos::new(2, 3)),
+        Err(MoveError::NothingToJump)
+    );
+    assert_eq!(
+        board.check_move(Pos::new(2, 2), Pos { row: 0, col: 1 }),
+        Err(MoveError::OutOfBounds)
+    );
+}
+
+#[cfg(test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 fn test_compression() {
-    let board = Board::default().set((3, 3));
-    let compressed = board.to_compressed_repr();
-    assert_eq!(compressed, 0x1_ffff_ffff);
-    println!("{:b}", compressed);
-    println!("{:b}", board.0);
-    let decompressed = Board::from_compressed_repr(compressed);
-    println!("{:b}", decompressed.0);
-    assert_eq!(decompressed, board);
+    // golden values, these must never change
+    let golden = [
+        (Board::empty(), 0),
+        (Board::full(), 0x1_ffff_ffff),
+        (Board::default(), 0x1_fffe_ffff),
+        (Board::solved(), 0x0_0001_0000),
+        (Board::empty().set(Pos::new(0, 2)), 0x0_0000_0001),
+        (Board::empty().set(Pos::new(0, 4)), 0x0_0000_0004),
+        (Board::empty().set(Pos::new(1, 2)), 0x0_0000_0008),
+        (Board::empty().set(Pos::new(2, 0)), 0x0_0000_0040),
+        (Board::empty().set(Pos::new(4, 6)), 0x0_0400_0000),
+        (Board::empty().set(Pos::new(6, 4)), 0x1_0000_0000),
+    ];
+    for (board, compressed) in golden {
+        assert_eq!(board.to_compressed_repr(), compressed, "{board}");
+        assert_eq!(Board::compress(board.0), compressed);
+        assert_eq!(Board::from_compressed_repr(compressed), board);
+    }
+    for (i, pos) in Board::full().pegs().enumerate() {
+        assert_eq!(Board::empty().set(pos).to_compressed_repr(), 1 << i);
+    }
+    assert_eq!(Board::try_from_compressed_repr(1 << 33), None);
+}
+
+#[cfg(test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn test_compression_round_trip() {
+    use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..100_000 {
+        let board = Board(rng.random::<u64>() & Board::full().0);
+        let compressed = board.to_compressed_repr();
+        assert_eq!(Board::compress(board.0), compressed);
+        assert_eq!(Board::try_from_compressed_repr(compressed), Some(board));
+        let compressed = rng.random_range(0..1 << Board::SLOTS);
+        assert_eq!(
+            Board::from_compressed_repr(compressed).to_compressed_repr(),
+            compressed
+        );
+    }
+}
+
+#[test]
+fn test_normalize() {
+    use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..100_000 {
+        let board = Board(rng.random::<u64>() & Board::full().0);
+        let t = board.transpose();
+        let expected = [
+            board,
+            board.reverse_cols(),
+            board.reverse_rows(),
+            board.rotate_180(),
+            t,
+            t.reverse_cols(),
+            t.reverse_rows(),
+            t.rotate_180(),
+        ];
+        let mut symmetries = board.symmetries();
+        symmetries.sort();
+        let mut expected_sorted = expected;
+        expected_sorted.sort();
+        assert_eq!(symmetries, expected_sorted);
+        for b in expected {
+            assert_eq!(b.normalize(), expected_sorted[0]);
+        }
+    }
 }
 
 type Lut = [[Board; 64]; 4];
@@ -250,21 +356,32 @@ impl Board {
         b
     }
 
-    #[cfg(target_arch = "x86_64")]
+    /// the 33 slots as bits in row major order, the top left slot
+    /// (row 0, column 2) being the least significant bit.
+    ///
+    /// this layout is used in files and caches and must be the same
+    /// on every platform (see `test_compression`).
+    ///
+    /// this is the radix key of the sort, so there is no room for a runtime
+    /// check of the cpu. pext is only used when bmi2 is enabled for the build
+    /// (e.g. `-C target-cpu=native`), it is microcoded and slower than the
+    /// shifts on amd cpus before zen 3.
+    #[inline]
     pub fn to_compressed_repr(&self) -> u64 {
-        const MASK: u64 = (0x7 << 2)
-            | (0x7 << 10)
-            | (0x7f << 16)
-            | (0x7f << 24)
-            | (0x7f << 32)
-            | (0x7 << 42)
-            | (0x7 << 50);
-        unsafe { core::arch::x86_64::_pext_u64(self.0, MASK) }
+        #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+        // SAFETY: bmi2 is enabled for the whole build
+        unsafe {
+            core::arch::x86_64::_pext_u64(self.0, Self::full().0)
+        }
+        #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+        {
+            Self::compress(self.0)
+        }
     }
 
-    #[cfg(not(target_arch = "x86_64"))]
-    pub fn to_compressed_repr(&self) -> u64 {
-        let board = self.0;
+    /// portable version of `_pext_u64(board, Board::full().0)`
+    #[cfg_attr(target_feature = "bmi2", allow(unused))]
+    const fn compress(board: u64) -> u64 {
         (board & (0x7 << 2)) >> 2
             | (board & (0x7 << 10)) >> (10 - 3)
             | (board & (0x7f << 16)) >> (16 - 6)
@@ -274,6 +391,7 @@ impl Board {
             | (board & (0x7 << 50)) >> (50 - (6 + 21 + 3))
     }
 
+    /// inverse of `to_compressed_repr`, bits above the 33 slots are ignored
     pub fn from_compressed_repr(compressed: u64) -> Self {
         let board = (compressed & 0x7) << 2
             | (compressed & (0x7 << 3)) << (8 + 2 - 3)
@@ -285,27 +403,91 @@ impl Board {
         Board(board)
     }
 
+    /// same as `from_compressed_repr` but rejects values
+    /// that do not fit into the 33 slots
+    pub fn try_from_compressed_repr(compressed: u64) -> Option<Self> {
+        (compressed >> Self::SLOTS == 0).then(|| Self::from_compressed_repr(compressed))
+    }
+
     pub fn inverse(&self) -> Board {
         !*self & Board::full()
     }
 
     pub fn normalize(self) -> Self {
-        let mut symmetries = self.symmetries().into_iter();
-        let mut min = symmetries.next().unwrap();
-        for b in symmetries {
-            if b < min {
-                min = b;
-            }
+        // the symmetries keeping rows as rows are cheap,
+        // only the transposed ones are not
+        let reverse_rows = self.reverse_rows();
+        let min = self
+            .min(self.reverse_cols())
+            .min(reverse_rows)
+            .min(reverse_rows.reverse_cols());
+
+        // boards compare by their last two rows first and for the
+        // transposed symmetries these come from the left or right arm,
+        // so a lookup often tells that none of them can be smaller
+        let (left, right) = (self.arm(0), self.arm(5));
+        let key = Self::TRANSPOSED_KEY_LUT[0][right]
+            .min(Self::TRANSPOSED_KEY_LUT[1][right])
+            .min(Self::TRANSPOSED_KEY_LUT[2][left])
+            .min(Self::TRANSPOSED_KEY_LUT[3][left]);
+        if key > (min.0 >> Self::KEY_SHIFT) as u16 {
+            return min;
         }
-        min
+
+        let transposed = self.transpose();
+        let rotate_90 = transposed.reverse_rows();
+        min.min(transposed)
+            .min(rotate_90)
+            .min(transposed.reverse_cols())
+            .min(rotate_90.reverse_cols())
     }
 
+    /// bits of the last two rows
+    const KEY_SHIFT: usize = 5 * Self::REPR as usize;
+
+    /// the six slots in columns `col` and `col + 1` of rows 2 to 4
+    #[inline]
+    const fn arm(&self, col: usize) -> usize {
+        let x = self.0;
+        let arm = ((x >> (16 + col)) & 0b11)
+            | ((x >> (22 + col)) & 0b1100)
+            | ((x >> (28 + col)) & 0b110000);
+        arm as usize
+    }
+
+    /// the board consisting of only the given arm (see `Board::arm`)
+    const fn from_arm(arm: usize, col: usize) -> Self {
+        let arm = arm as u64;
+        Self(
+            ((arm & 0b11) << (16 + col))
+                | ((arm & 0b1100) << (22 + col))
+                | ((arm & 0b110000) << (28 + col)),
+        )
+    }
+
+    /// last two rows of transposed, rotate_90, rotate_270 and anti_transpose
+    /// by the arm they are made of (right, right, left, left)
+    const TRANSPOSED_KEY_LUT: [[u16; 64]; 4] = {
+        let mut lut = [[0; 64]; 4];
+        let mut i = 0;
+        while i < 64 {
+            let right = Self::from_arm(i, 5).transpose();
+            let left = Self::from_arm(i, 0).transpose();
+            lut[0][i] = (right.0 >> Self::KEY_SHIFT) as u16;
+            lut[1][i] = (right.reverse_rows().0 >> Self::KEY_SHIFT) as u16;
+            lut[2][i] = (left.reverse_cols().0 >> Self::KEY_SHIFT) as u16;
+            lut[3][i] = (left.rotate_180().0 >> Self::KEY_SHIFT) as u16;
+            i += 1;
+        }
+        lut
+    };
+
     pub const fn empty() -> Self {
         Self(0)
     }
 
     pub const fn solved() -> Self {
-        Self::empty().set((3, 3))
+        Self::empty().set(Pos::new(3, 3))
     }
 
     pub const fn movable_positions(&self, dir: Dir) -> Self {
@@ -317,25 +499,25 @@ impl Board {
         //     o . .
         //     o . .
         const MOVABLE_EAST: Board = Board::empty()
-            .set((0, 2))
-            .set((1, 2))
-            .set((2, 0))
-            .set((2, 1))
-            .set((2, 2))
-            .set((2, 3))
-            .set((2, 4))
-            .set((3, 0))
-            .set((3, 1))
-            .set((3, 2))
-            .set((3, 3))
-            .set((3, 4))
-            .set((4, 0))
-            .set((4, 1))
-            .set((4, 2))
-            .set((4, 3))
-            .set((4, 4))
-            .set((5, 2))
-            .set((6, 2));
+            .set(Pos::new(0, 2))
+            .set(Pos::new(1, 2))
+            .set(Pos::new(2, 0))
+            .set(Pos::new(2, 1))
+            .set(Pos::new(2, 2))
+            .set(Pos::new(2, 3))
+            .set(Pos::new(2, 4))
+            .set(Pos::new(3, 0))
+            .set(Pos::new(3, 1))
+            .set(Pos::new(3, 2))
+            .set(Pos::new(3, 3))
+            .set(Pos::new(3, 4))
+            .set(Pos::new(4, 0))
+            .set(Pos::new(4, 1))
+            .set(Pos::new(4, 2))
+            .set(Pos::new(4, 3))
+            .set(Pos::new(4, 4))
+            .set(Pos::new(5, 2))
+            .set(Pos::new(6, 2));
         const MOVABLE_WEST: Board = MOVABLE_EAST.rotate_180();
         const MOVABLE_SOUTH: Board = MOVABLE_EAST.transpose();
         const MOVABLE_NORTH: Board = MOVABLE_WEST.transpose();
@@ -366,33 +548,6 @@ impl Board {
         *self == Self::solved()
     }
 
-    /// the game is not solvable, if none of the marked fields contain a ball:
-    ///
-    ///  ```
-    ///  //       .  .  .
-    ///  //       .  x  .
-    ///  // .  .  .  .  .  .  .
-    ///  // .  x  .  x  .  x  .
-    ///  // .  .  .  .  .  .  .
-    ///  //       .  x  .
-    ///  //       .  .  .
-    /// ```
-    pub(crate) fn is_solvable(&self) -> bool {
-        const POSITION_VEC: u64 = {
-            let mut vec = 0;
-            const POSITIONS: [(Idx, Idx); 5] = [(1, 3), (3, 1), (3, 3), (3, 5), (5, 3)];
-            let mut i = 0;
-            while i < POSITIONS.len() {
-                let (y, x) = POSITIONS[i];
-                let idx = y * Board::REPR + x;
-                vec |= 1 << idx;
-                i += 1;
-            }
-            vec
-        };
-        (self.0 & POSITION_VEC) != 0
-    }
-
     #[inline(always)]
     pub fn mov(&self, mov: Move) -> Board {
         debug_assert!(Self::inbounds(mov.pos));
@@ -496,9 +651,8 @@ impl Board {
     }
 
     #[inline(always)]
-    pub const fn occupied(&self, pos: (Idx, Idx)) -> bool {
-        let (y, x) = pos;
-        (self.0 & (1 << (y * Board::REPR + x))) != 0
+    pub const fn occupied(&self, pos: Pos) -> bool {
+        (self.0 & (1 << pos.index())) != 0
     }
 
     pub const fn occupied_idx(&self, idx: usize) -> bool {
@@ -506,30 +660,28 @@ impl Board {
     }
 
     #[inline(always)]
-    pub const fn set(self, pos: (Idx, Idx)) -> Self {
+    pub const fn set(self, pos: Pos) -> Self {
         debug_assert!(!self.occupied(pos));
-        let (y, x) = pos;
-        Self(self.0 | 1 << (y * Board::REPR + x))
+        Self(self.0 | 1 << pos.index())
     }
 
     #[inline(always)]
-    const fn unset(self, pos: (Idx, Idx)) -> Self {
+    const fn unset(self, pos: Pos) -> Self {
         debug_assert!(self.occupied(pos));
-        let (y, x) = pos;
-        Self(self.0 & !(1 << (y * Board::REPR + x)))
+        Self(self.0 & !(1 << pos.index()))
     }
 
     #[inline(always)]
-    pub const fn inbounds(pos: (Idx, Idx)) -> bool {
-        pos.0 >= 0
-            && pos.0 < Board::SIZE
-            && pos.1 >= 0
-            && pos.1 < Board::SIZE
-            && (Board::empty().set(pos).0 & Board::full().0) != 0
+    pub const fn inbounds(pos: Pos) -> bool {
+        pos.row >= 0
+            && pos.row < Board::SIZE
+            && pos.col >= 0
+            && pos.col < Board::SIZE
+            && Board::full().occupied(pos)
     }
 
     #[inline(always)]
-    pub fn get_legal_move(&self, pos: (Idx, Idx), dir: Dir) -> Option<Move> {
+    pub fn get_legal_move(&self, pos: Pos, dir: Dir) -> Option<Move> {
         debug_assert!(Self::inbounds(pos));
         let (skip, target) = dir.mov(pos);
         if Self::inbounds(target) && self.occupied(skip) && !self.occupied(target) {
@@ -540,7 +692,7 @@ impl Board {
     }
 
     #[inline(always)]
-    pub fn get_legal_inverse_move(&self, target: (Idx, Idx), dir: Dir) -> Option<Move> {
+    pub fn get_legal_inverse_move(&self, target: Pos, dir: Dir) -> Option<Move> {
         let (skip, pos) = dir.mov(target);
         if Self::inbounds(pos) && !self.occupied(skip) && !self.occupied(pos) {
             Some(Move { pos, skip, target })
@@ -549,13 +701,21 @@ impl Board {
         }
     }
 
+    /// positions of all pegs
+    pub fn pegs(self) -> impl Iterator<Item = Pos> {
+        self.into_iter().map(Pos::from_index)
+    }
+
+    /// positions of all empty holes
+    pub fn holes(self) -> impl Iterator<Item = Pos> {
+        (Board::full() & !self).pegs()
+    }
+
     pub fn get_legal_moves(self) -> Vec<Move> {
         let mut legal_moves = Vec::new();
-        for idx in self {
-            let y = idx as Idx / Board::REPR;
-            let x = idx as Idx % Board::REPR;
+        for pos in self.pegs() {
             for dir in Dir::enumerate() {
-                if let Some(mov) = self.get_legal_move((y, x), dir) {
+                if let Some(mov) = self.get_legal_move(pos, dir) {
                     legal_moves.push(mov);
                 }
             }
@@ -565,11 +725,9 @@ impl Board {
 
     pub fn get_legal_inverse_moves(self) -> Vec<Move> {
         let mut legal_moves = Vec::new();
-        for idx in self {
-            let y = idx as Idx / Board::REPR;
-            let x = idx as Idx % Board::REPR;
+        for pos in self.pegs() {
             for dir in Dir::enumerate() {
-                if let Some(mov) = self.get_legal_inverse_move((y, x), dir) {
+                if let Some(mov) = self.get_legal_inverse_move(pos, dir) {
                     legal_moves.push(mov);
                 }
             }
@@ -577,23 +735,35 @@ impl Board {
         legal_moves
     }
 
-    pub fn is_legal_move(&self, pos: (Idx, Idx), dst: (Idx, Idx)) -> Option<Move> {
-        let dist_y = (pos.0 - dst.0).abs();
-        let dist_x = (pos.1 - dst.1).abs();
-        if dist_y == 2 && dist_x == 0 || dist_x == 2 && dist_y == 0 {
-            let dir = match (pos, dst) {
-                (p, d) if d.0 < p.0 => Dir::North,
-                (p, d) if d.0 > p.0 => Dir::South,
-                (p, d) if d.1 < p.1 => Dir::West,
-                (p, d) if d.1 > p.1 => Dir::East,
-                _ => unreachable!(),
-            };
-            if !self.occupied(pos) {
-                return None;
-            }
-            self.get_legal_move(pos, dir)
+    pub fn is_legal_move(&self, pos: Pos, dst: Pos) -> Option<Move> {
+        self.check_move(pos, dst).ok()
+    }
+
+    /// the move from `pos` to `dst` or why it can not be played
+    pub fn check_move(&self, pos: Pos, dst: Pos) -> Result<Move, MoveError> {
+        if !Self::inbounds(pos) || !Self::inbounds(dst) {
+            return Err(MoveError::OutOfBounds);
+        }
+        let (dy, dx) = (dst.row - pos.row, dst.col - pos.col);
+        if !matches!((dy.abs(), dx.abs()), (2, 0) | (0, 2)) {
+            return Err(MoveError::NotAJump);
+        }
+        let skip = Pos {
+            row: pos.row + dy / 2,
+            col: pos.col + dx / 2,
+        };
+        if !self.occupied(pos) {
+            Err(MoveError::NoPeg)
+        } else if !self.occupied(skip) {
+            Err(MoveError::NothingToJump)
+        } else if self.occupied(dst) {
+            Err(MoveError::TargetOccupied)
         } else {
-            None
+            Ok(Move {
+                pos,
+                skip,
+                target: dst,
+            })
         }
     }
 
@@ -647,13 +817,15 @@ impl Board {
     }
 
     pub const fn symmetries(&self) -> [Self; 8] {
+        // most targets have no instruction to reverse bits, so the
+        // rotations by 180 degrees are derived from the mirrored boards
         let transposed = self.transpose();
         let reverse_cols = self.reverse_cols();
         let reverse_rows = self.reverse_rows();
-        let rotate_180 = self.rotate_180();
+        let rotate_180 = reverse_rows.reverse_cols();
         let rotate_90 = transposed.reverse_rows();
         let rotate_270 = transposed.reverse_cols();
-        let anti_transpose = transposed.rotate_180();
+        let anti_transpose = rotate_90.reverse_cols();
 
         [
             *self,
@@ -697,6 +869,37 @@ impl Board {
         }
     }
 
+    /// the position class of the constellation, which no move can change:
+    /// a move flips one hole on each diagonal modulo 3 (in both directions),
+    /// so the parities of the pegs on these diagonals change all at once.
+    ///
+    /// constellations of different classes can not be played into one another.
+    pub fn position_class(self) -> u8 {
+        let mut diag = [0u8; 3];
+        let mut anti_diag = [0u8; 3];
+        for pos in self.pegs() {
+            diag[(pos.row + pos.col) as usize % 3] ^= 1;
+            anti_diag[(pos.row - pos.col + Board::SIZE) as usize % 3] ^= 1;
+        }
+        (diag[0] ^ diag[1])
+            | (diag[1] ^ diag[2]) << 1
+            | (anti_diag[0] ^ anti_diag[1]) << 2
+            | (anti_diag[1] ^ anti_diag[2]) << 3
+    }
+
+    /// single line notation accepted by `Board::try_from`,
+    /// e.g. `ooo/ooo/ooooooo/ooo.ooo/ooooooo/ooo/ooo`
+    pub fn to_notation(&self) -> String {
+        let rows = (0..Board::SIZE).map(|y| {
+            (0..Board::SIZE)
+                .map(|x| Pos { row: y, col: x })
+                .filter(|&pos| Board::inbounds(pos))
+                .map(|pos| if self.occupied(pos) { 'o' } else { '.' })
+                .collect::<String>()
+        });
+        rows.collect::<Vec<_>>().join("/")
+    }
+
     ///
     /// ```rust
     /// use solitaire_solver::Board;
@@ -764,27 +967,30 @@ impl IntoIterator for Board {
 impl TryFrom<&'_ str> for Board {
     type Error = &'static str;
 
+    /// rows are separated by newlines or `/`, e.g.
+    /// `ooo/ooo/ooooooo/ooo.ooo/ooooooo/ooo/ooo`
     fn try_from(s: &'_ str) -> Result<Self, Self::Error> {
-        let lines = s.lines();
+        let lines = s.split(['\n', '/']);
         let mut board = Board::empty();
         for (y, l) in lines.enumerate() {
             let mut x = 0;
             for c in l.chars() {
                 match c {
-                    'o' => {
-                        {
-                            let x = match y {
-                                0 | 1 | 5 | 6 => x + 2,
-                                _ => x,
-                            };
-                            board = board.set((y as Idx, x as Idx));
+                    'o' | '.' => {
+                        let row = y as Idx;
+                        let pos = match y {
+                            0 | 1 | 5 | 6 => Pos { row, col: x + 2 },
+                            _ => Pos { row, col: x },
+                        };
+                        if y >= Board::SIZE as usize || !Board::inbounds(pos) {
+                            return Err("position out of bounds");
+                        }
+                        if c == 'o' {
+                            board = board.set(pos);
                         }
                         x += 1;
                     }
-                    '.' => {
-                        x += 1;
-                    }
-                    ' ' => {}
+                    ' ' | '\r' => {}
                     _ => return Err("invalid character"),
                 }
             }
@@ -793,6 +999,30 @@ impl TryFrom<&'_ str> for Board {
     }
 }
 
+#[test]
+fn test_position_class() {
+    let class = Board::solved().position_class();
+    assert_eq!(Board::default().position_class(), class);
+    for mov in Board::default().get_legal_moves() {
+        assert_eq!(Board::default().mov(mov).position_class(), class);
+    }
+    // rule of three: the only single vacancies that can end in the center
+    let vacancies = Board::empty()
+        .holes()
+        .filter(|&pos| (Board::full() ^ Board::empty().set(pos)).position_class() == class)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vacancies,
+        [
+            Pos::new(0, 3),
+            Pos::new(3, 0),
+            Pos::new(3, 3),
+            Pos::new(3, 6),
+            Pos::new(6, 3)
+        ]
+    );
+}
+
 #[test]
 fn test_parse() {
     let full = Board::try_from(
@@ -810,6 +1040,14 @@ fn test_parse() {
     assert_eq!(full, Board::full());
 }
 
+#[test]
+fn test_parse_single_line() {
+    let board = Board::try_from("ooo/ooo/ooooooo/ooo.ooo/ooooooo/ooo/ooo").unwrap();
+    assert_eq!(board, Board::default());
+    assert!(Board::try_from("ooo/ooo/ooooooo/ooo.ooo/ooooooo/ooo/ooo/o").is_err());
+    assert!(Board::try_from("oooo").is_err());
+}
+
 pub struct PegIter(Board); // or whatever your inner type is
 
 impl Iterator for PegIter {
@@ -0,0 +1,37 @@
+use crate::{Board, HashMap, Pos, calc_first::goal_class};
+
+/// counts all move sequences leading from `board` to a single peg at `target`
+pub fn count_solutions(board: Board, target: Pos) -> u128 {
+    fn count(
+        board: Board,
+        goal: Board,
+        class: Board,
+        symmetric: bool,
+        memo: &mut HashMap<Board, u128>,
+    ) -> u128 {
+        if board == goal {
+            return 1;
+        }
+        if board & class == Board::empty() {
+            return 0;
+        }
+        // with the goal in the center, all symmetries have the same count
+        let key = if symmetric { board.normalize() } else { board };
+        if let Some(&n) = memo.get(&key) {
+            return n;
+        }
+        let n = board
+            .get_legal_moves()
+            .into_iter()
+            .map(|m| count(board.mov(m), goal, class, symmetric, memo))
+            .sum();
+        memo.insert(key, n);
+        n
+    }
+    let goal = Board::empty().set(target);
+    let symmetric = goal == Board::solved();
+    let mut memo = HashMap::default();
+    let n = count(board, goal, goal_class(goal), symmetric, &mut memo);
+    log::info!("visited {} constellations", memo.len());
+    n
+}
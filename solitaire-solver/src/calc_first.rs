@@ -1,17 +1,58 @@
-use crate::{Board, Solution, hash::CustomHashSet as HashSet};
+use crate::{Board, Pos, Solution, hash::CustomHashSet as HashSet};
 
 pub fn calculate_first_solution() -> Solution {
+    let (solution, count) = solve_from(Board::default(), Board::solved());
+    log::info!("tried {count} constellations!");
+    solution.expect("the default board is solvable")
+}
+
+/// finds a solution leading from `board` to the solved constellation
+/// or `None`, if there is no such solution
+pub fn calculate_first_solution_from(board: Board) -> Option<Solution> {
+    solve_from(board, Board::solved()).0
+}
+
+/// finds a solution leading from `board` to a single peg at `target`
+/// or `None`, if there is no such solution
+pub fn calculate_first_solution_to(board: Board, target: Pos) -> Option<Solution> {
+    solve_from(board, Board::empty().set(target)).0
+}
+
+/// the parity class of `goal`: a peg can never leave its class,
+/// so at least one peg of the class has to be left on the board.
+///
+/// e.g. for the center, one of the marked fields has to contain a ball:
+///
+///  ```
+///  //       .  .  .
+///  //       .  x  .
+///  // .  .  .  .  .  .  .
+///  // .  x  .  x  .  x  .
+///  // .  .  .  .  .  .  .
+///  //       .  x  .
+///  //       .  .  .
+/// ```
+pub(crate) fn goal_class(goal: Board) -> Board {
+    Board::type_masks()
+        .into_iter()
+        .find(|&mask| mask & goal != Board::empty())
+        .unwrap_or(Board::empty())
+}
+
+fn solve_from(board: Board, goal: Board) -> (Option<Solution>, u64) {
     fn solve(
         board: Board,
+        goal: Board,
+        class: Board,
         solution: &mut Solution,
         visited: &mut HashSet<Board>,
         count: &mut u64,
     ) -> bool {
         *count += 1;
-        if board.is_solved() {
+        if board == goal {
             return true;
         }
-        if !board.is_solvable() {
+        if board & class == Board::empty() {
             return false;
         }
         if visited.contains(&board) {
@@ -27,7 +68,7 @@ pub fn calculate_first_solution() -> Solution {
         legal_moves.dedup();
         for (b, m) in legal_moves {
             solution.push(m);
-            if solve(b, solution, visited, count) {
+            if solve(b, goal, class, solution, visited, count) {
                 return true;
             }
             solution.pop();
@@ -35,10 +76,13 @@ pub fn calculate_first_solution() -> Solution {
         visited.insert(board);
         false
     }
+    if board.position_class() != goal.position_class() {
+        return (None, 0);
+    }
+    let class = goal_class(goal);
     let mut solution = Default::default();
     let mut visited = HashSet::default();
     let mut count = 0;
-    solve(Board::default(), &mut solution, &mut visited, &mut count);
-    println!("tried {count} constellations!");
-    solution
+    let solved = solve(board, goal, class, &mut solution, &mut visited, &mut count);
+    (solved.then_some(solution), count)
 }
@@ -1,5 +1,3 @@
-use crate::board::Idx;
-
 use super::{Board, Dir, hash::CustomHashSet as HashSet};
 
 pub fn calculate_all_solutions_naive() -> Vec<Board> {
@@ -21,14 +19,9 @@ pub fn calculate_all_solutions_naive() -> Vec<Board> {
         }
 
         let mut any_solution = false;
-        let mut copy = board.0;
-        while copy != 0 {
-            let idx = copy.trailing_zeros();
-            copy &= !(1 << idx);
-            let y = idx as Idx / Board::REPR;
-            let x = idx as Idx % Board::REPR;
+        for pos in board.pegs() {
             for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
-                if let Some(mov) = board.get_legal_move((y, x), dir) {
+                if let Some(mov) = board.get_legal_move(pos, dir) {
                     any_solution |=
                         solve_all(board.mov(mov).normalize(), already_checked, solvable);
                 }
@@ -46,7 +39,7 @@ pub fn calculate_all_solutions_naive() -> Vec<Board> {
     let total = already_checked.len();
     let solvable_count = solvable.len();
     assert_eq!(solvable_count, 1679072);
-    println!(
+    log::info!(
         "checked {total} constellations, {solvable_count} have a solution ({:.2}%)",
         (solvable_count as f64 / total as f64) * 100.
     );
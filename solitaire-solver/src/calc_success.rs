@@ -1,10 +1,20 @@
 use super::{
-    Board,
+    Board, Progress,
     hash::{CustomHashMap as HashMap, CustomHashSet as HashSet},
+    reachable::reachable_layers,
 };
 
 /// calculate the chances of winning the game by chosing possible moves at random
 pub fn calculate_p_random_chance_success(feasible: Vec<Board>) -> HashMap<Board, f64> {
+    calculate_p_random_chance_success_with_progress(feasible, |_| {})
+}
+
+/// same as `calculate_p_random_chance_success`,
+/// calling `progress` after every number of pegs
+pub fn calculate_p_random_chance_success_with_progress(
+    feasible: Vec<Board>,
+    mut progress: impl FnMut(Progress),
+) -> HashMap<Board, f64> {
     let feasible: HashSet<_> = feasible.into_iter().collect();
     let mut chances = HashMap::default();
     chances.insert(Board::solved(), 1.0);
@@ -35,6 +45,50 @@ pub fn calculate_p_random_chance_success(feasible: Vec<Board>) -> HashMap<Board,
 
             chances.insert(constellation, p_success);
         }
+        progress(Progress {
+            stage: "chances",
+            done: i - 1,
+            total: Board::SLOTS - 2,
+        });
+    }
+    chances
+}
+
+/// same as `calculate_p_random_chance_success` for the constellations
+/// reachable from `start` instead of the standard start.
+/// constellations without any chance of winning are left out.
+pub fn calculate_p_random_chance_success_from(start: Board) -> HashMap<Board, f64> {
+    let mut chances = HashMap::default();
+    // every move leads to the next layer, so the chances
+    // after a move are known when going backwards
+    for layer in reachable_layers(start).into_iter().rev() {
+        for constellation in layer {
+            if constellation == Board::solved() {
+                chances.insert(constellation, 1.0);
+                continue;
+            }
+            let legal_moves = constellation.get_legal_moves();
+            let p_success = legal_moves
+                .iter()
+                .filter_map(|&mov| chances.get(&constellation.mov(mov).normalize()))
+                .sum::<f64>()
+                / legal_moves.len() as f64;
+            if p_success > 0.0 {
+                chances.insert(constellation, p_success);
+            }
+        }
     }
     chances
 }
+
+#[test]
+fn test_chances_from() {
+    use crate::Pos;
+
+    // one of the two moves ends in the center
+    let start = Board::empty().set(Pos::new(3, 1)).set(Pos::new(3, 2));
+    let chances = calculate_p_random_chance_success_from(start);
+    assert_eq!(chances.get(&start.normalize()), Some(&0.5));
+    assert_eq!(chances.len(), 2);
+    assert!(calculate_p_random_chance_success_from(Board::empty().set(Pos::new(0, 2))).is_empty());
+}
@@ -0,0 +1,118 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::{Board, Dir, Idx, Pos};
+
+/// a point or a vector on the grid of the board for frontends.
+///
+/// `x` is the column (growing to the right) and `y` the row
+/// (growing downwards), so `Coord { x, y }` is `Pos { row: y, col: x }`.
+/// unlike a `Pos` it may be anywhere, e.g. under the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: Idx,
+    pub y: Idx,
+}
+
+const CENTER: Idx = Board::SIZE / 2;
+
+impl Coord {
+    pub const fn new(x: Idx, y: Idx) -> Self {
+        Self { x, y }
+    }
+
+    /// relative to the center of the board with `y` growing upwards
+    pub const fn centered(self) -> (Idx, Idx) {
+        (self.x - CENTER, CENTER - self.y)
+    }
+
+    /// inverse of `centered`, saturating far away from the board
+    pub const fn from_centered(x: Idx, y: Idx) -> Self {
+        Self {
+            x: x.saturating_add(CENTER),
+            y: CENTER.saturating_sub(y),
+        }
+    }
+
+    /// the direction of the longer axis of this vector,
+    /// `None` if both have the same length
+    pub fn dir(self) -> Option<Dir> {
+        match (self.x.abs(), self.y.abs()) {
+            (x, y) if x > y && self.x < 0 => Some(Dir::West),
+            (x, y) if x > y => Some(Dir::East),
+            (x, y) if y > x && self.y < 0 => Some(Dir::North),
+            (x, y) if y > x => Some(Dir::South),
+            _ => None,
+        }
+    }
+}
+
+impl From<Pos> for Coord {
+    fn from(pos: Pos) -> Self {
+        Self {
+            x: pos.col,
+            y: pos.row,
+        }
+    }
+}
+
+/// the position may be off the board (see `Board::check_move`)
+impl From<Coord> for Pos {
+    fn from(coord: Coord) -> Self {
+        Pos {
+            row: coord.y,
+            col: coord.x,
+        }
+    }
+}
+
+/// unit vector
+impl From<Dir> for Coord {
+    fn from(dir: Dir) -> Self {
+        Coord::from(Pos::default() + dir)
+    }
+}
+
+impl Add for Coord {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Coord {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Idx> for Coord {
+    type Output = Self;
+
+    fn mul(self, rhs: Idx) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[test]
+fn test_coords() {
+    let pos = Pos::new(2, 4);
+    assert_eq!(Coord::from(pos), Coord::new(4, 2));
+    assert_eq!(Pos::from(Coord::from(pos)), pos);
+    for dir in Dir::enumerate() {
+        assert_eq!(Pos::from(Coord::from(pos) + Coord::from(dir)), pos + dir);
+        assert_eq!((Coord::from(dir) * 2).dir(), Some(dir));
+    }
+    assert_eq!(Coord::new(1, -1).dir(), None);
+
+    let center = Coord::from(Pos::new(3, 3));
+    assert_eq!(center.centered(), (0, 0));
+    assert_eq!(Coord::from(Pos::new(0, 2)).centered(), (-1, 3));
+    assert_eq!(Coord::from_centered(-1, 3), Coord::new(2, 0));
+    assert_eq!(
+        Coord::from_centered(Idx::MAX, Idx::MIN),
+        Coord::new(Idx::MAX, Idx::MAX)
+    );
+}
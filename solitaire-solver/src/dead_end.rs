@@ -0,0 +1,23 @@
+use crate::Board;
+
+/// the minimum number of moves leading from `board` to a constellation
+/// without any legal moves left (other than the solved one)
+/// or `None`, if every sequence of moves ends in the solved constellation
+pub fn dead_end_distance(board: Board) -> Option<usize> {
+    let mut distance = 0;
+    let mut layer = vec![board.normalize()];
+    while !layer.is_empty() {
+        if layer
+            .iter()
+            .any(|b| !b.is_solved() && b.get_legal_moves().is_empty())
+        {
+            return Some(distance);
+        }
+        layer = Board::possible_moves(&layer);
+        Board::normalize_all(&mut layer);
+        layer.sort_unstable();
+        layer.dedup();
+        distance += 1;
+    }
+    None
+}
@@ -0,0 +1,57 @@
+use crate::{Board, Move, Pos};
+
+/// difference between two constellations (see `Board::diff`)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoardDiff {
+    /// pegs only present in the other constellation
+    pub added: Vec<Pos>,
+    /// pegs only present in this constellation
+    pub removed: Vec<Pos>,
+    /// the move leading to the other constellation, if there is one
+    pub mov: Option<Move>,
+}
+
+impl BoardDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Board {
+    /// what changes from `self` to `other`
+    pub fn diff(&self, other: &Board) -> BoardDiff {
+        let positions = |b: Board| b.pegs().collect();
+        let added = *other & !*self;
+        let removed = *self & !*other;
+        let mov = match (added.count_pegs(), removed.count_pegs()) {
+            (1, 2) => self
+                .get_legal_moves()
+                .into_iter()
+                .find(|&m| self.mov(m) == *other),
+            _ => None,
+        };
+        BoardDiff {
+            added: positions(added),
+            removed: positions(removed),
+            mov,
+        }
+    }
+}
+
+#[test]
+fn test_diff() {
+    let mov: Move = "13v".parse().unwrap();
+    let start = Board::default();
+    let next = start.mov(mov);
+
+    let diff = start.diff(&next);
+    assert_eq!(diff.added, [Pos::new(3, 3)]);
+    assert_eq!(diff.removed, [Pos::new(1, 3), Pos::new(2, 3)]);
+    assert_eq!(diff.mov, Some(mov));
+
+    let diff = next.diff(&start);
+    assert_eq!(diff.added.len(), 2);
+    assert_eq!(diff.mov, None);
+
+    assert!(start.diff(&start).is_empty());
+}
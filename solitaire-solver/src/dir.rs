@@ -1,8 +1,8 @@
 use std::fmt::{Display, Error, Formatter};
 
-use crate::board::Idx;
+use crate::Pos;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Dir {
     North,
     West,
@@ -21,16 +21,26 @@ impl Display for Dir {
     }
 }
 
-impl Dir {
-    pub(crate) fn mov(&self, pos: (Idx, Idx)) -> ((Idx, Idx), (Idx, Idx)) {
-        let (y, x) = pos;
-        match self {
-            Dir::North => ((y - 1, x), (y - 2, x)),
-            Dir::West => ((y, x - 1), (y, x - 2)),
-            Dir::East => ((y, x + 1), (y, x + 2)),
-            Dir::South => ((y + 1, x), (y + 2, x)),
+impl TryFrom<char> for Dir {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Dir::North),
+            '<' => Ok(Dir::West),
+            '>' => Ok(Dir::East),
+            'v' => Ok(Dir::South),
+            _ => Err("invalid direction"),
         }
     }
+}
+
+impl Dir {
+    /// the skipped and the target position of a move from `pos`
+    pub(crate) fn mov(&self, pos: Pos) -> (Pos, Pos) {
+        let skip = pos + *self;
+        (skip, skip + *self)
+    }
 
     pub(crate) fn enumerate() -> [Self; 4] {
         [Dir::North, Dir::West, Dir::East, Dir::South]
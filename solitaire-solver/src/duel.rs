@@ -0,0 +1,101 @@
+use crate::{Board, HashMap, Move};
+
+// In a duel two players take turns jumping on the same board and the one
+// who can not move anymore loses. Positions are searched a fixed number of
+// moves ahead, which solves them exactly close to the end of the game.
+
+/// the outcome of a duel for the player to move, ordered from worst to best
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DuelOutcome {
+    Loss,
+    /// not decided within the searched moves
+    Unknown,
+    Win,
+}
+
+impl DuelOutcome {
+    /// the outcome for the other player
+    fn flip(self) -> Self {
+        match self {
+            DuelOutcome::Loss => DuelOutcome::Win,
+            DuelOutcome::Unknown => DuelOutcome::Unknown,
+            DuelOutcome::Win => DuelOutcome::Loss,
+        }
+    }
+}
+
+/// the best move for the player to move, looking `depth` moves ahead,
+/// `None` if there is no move left and the duel is lost.
+/// undecided moves leaving the opponent the fewest moves are preferred.
+pub fn duel_move(board: Board, depth: usize) -> Option<(Move, DuelOutcome)> {
+    let mut memo = HashMap::default();
+    board
+        .get_legal_moves()
+        .into_iter()
+        .map(|mov| {
+            let next = board.mov(mov);
+            let outcome = negamax(next, depth.saturating_sub(1), &mut memo).flip();
+            let replies = next.get_legal_moves().len();
+            (mov, outcome, replies)
+        })
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)))
+        .map(|(mov, outcome, _)| (mov, outcome))
+}
+
+/// the outcome for the player to move at `board`, decided outcomes are
+/// remembered for every depth, undecided ones only for the depth searched
+fn negamax(
+    board: Board,
+    depth: usize,
+    memo: &mut HashMap<Board, (usize, DuelOutcome)>,
+) -> DuelOutcome {
+    let moves = board.get_legal_moves();
+    if moves.is_empty() {
+        return DuelOutcome::Loss;
+    }
+    if depth == 0 {
+        return DuelOutcome::Unknown;
+    }
+    let key = board.normalize();
+    if let Some(&(searched, outcome)) = memo.get(&key)
+        && (searched >= depth || outcome != DuelOutcome::Unknown)
+    {
+        return outcome;
+    }
+    let mut best = DuelOutcome::Loss;
+    for mov in moves {
+        best = best.max(negamax(board.mov(mov), depth - 1, memo).flip());
+        if best == DuelOutcome::Win {
+            break;
+        }
+    }
+    memo.insert(key, (depth, best));
+    best
+}
+
+#[test]
+fn test_duel_no_moves() {
+    assert_eq!(duel_move(Board::solved(), 4), None);
+}
+
+#[test]
+fn test_duel_win() {
+    use crate::Pos;
+    // jumping down leaves two pegs apart, jumping up lets the opponent
+    // make the last move
+    let board = Board::empty()
+        .set(Pos::new(3, 5))
+        .set(Pos::new(4, 4))
+        .set(Pos::new(5, 4));
+    let (mov, outcome) = duel_move(board, 4).expect("moves");
+    assert_eq!(outcome, DuelOutcome::Win);
+    assert!(board.mov(mov).get_legal_moves().is_empty());
+    assert_eq!(duel_move(board, 1).map(|(_, o)| o), Some(DuelOutcome::Win));
+}
+
+#[test]
+fn test_duel_start() {
+    // too far from the end to be decided
+    let (_, outcome) = duel_move(Board::default(), 3).expect("moves");
+    assert_eq!(outcome, DuelOutcome::Unknown);
+}
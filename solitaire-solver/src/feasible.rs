@@ -5,7 +5,7 @@ use std::{cmp::Ordering, num::NonZero};
 use log::info;
 
 use crate::{
-    Board,
+    Board, Progress,
     par::{self, ParDedup},
     sort::Sort,
     timer::Timer,
@@ -44,9 +44,97 @@ fn reverse_moves_par(states: &[Board], num_threads: usize) -> Vec<Board> {
 }
 
 pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
+    calculate_feasible_set_with_progress(threads, false, |_| {})
+}
+
+/// same as `calculate_feasible_set`, calling `progress` after every layer.
+///
+/// if `deterministic` is set, the constellations are sorted,
+/// otherwise they are in no particular order.
+pub fn calculate_feasible_set_with_progress(
+    threads: Option<NonZero<usize>>,
+    deterministic: bool,
+    progress: impl FnMut(Progress),
+) -> Vec<Board> {
+    let mut solvable = calculate_feasible_set_shard(threads, 0, 1, |own| own, progress);
+    assert_eq!(solvable.len(), 1679072);
+    if deterministic {
+        let threads = threads.unwrap_or(par::num_threads()).get();
+        solvable.fast_sort_unstable_mt(threads);
+    }
+    solvable
+}
+
+/// the feasible set, indexed by the number of pegs
+pub fn calculate_feasible_set_by_pegs(threads: Option<NonZero<usize>>) -> Vec<Vec<Board>> {
+    let levels = feasible_levels(threads, 0, 1, |own| own, |_| {});
+    assert_eq!(levels.iter().map(Vec::len).sum::<usize>(), 1679072);
+    levels
+}
+
+/// the shard out of `shards` a normalized constellation belongs to
+pub fn shard_of(board: Board, shards: usize) -> usize {
+    // fibonacci hashing, the low bits of a board are too regular
+    ((board.0.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32) % shards as u64) as usize
+}
+
+/// the part of the feasible set calculated by `shard` out of `shards`.
+///
+/// every level is split between the shards by `shard_of`, a shard only
+/// expands its own part. after every level, `exchange` is called with the
+/// new constellations by the shard they belong to. it has to hand them on
+/// to the other shards and return the constellations all shards
+/// (including this one) found for `shard`, in any order.
+///
+/// every constellation of the feasible set is part of exactly one shard.
+pub fn calculate_feasible_set_shard(
+    threads: Option<NonZero<usize>>,
+    shard: usize,
+    shards: usize,
+    exchange: impl FnMut(Vec<Vec<Board>>) -> Vec<Vec<Board>>,
+    progress: impl FnMut(Progress),
+) -> Vec<Board> {
+    feasible_levels(threads, shard, shards, exchange, progress)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// splits the sorted `constellations` between the shards and collects
+/// the ones belonging to `shard` from all of them
+fn distribute(
+    constellations: Vec<Board>,
+    shards: usize,
+    threads: usize,
+    exchange: &mut impl FnMut(Vec<Vec<Board>>) -> Vec<Vec<Board>>,
+) -> Vec<Board> {
+    if shards == 1 {
+        return constellations;
+    }
+    let mut buckets = vec![vec![]; shards];
+    for board in constellations {
+        buckets[shard_of(board, shards)].push(board);
+    }
+    // the same constellation may come from several shards
+    let mut constellations = exchange(buckets).concat();
+    constellations.fast_sort_unstable_mt(threads);
+    constellations.par_dedup(threads)
+}
+
+fn feasible_levels(
+    threads: Option<NonZero<usize>>,
+    shard: usize,
+    shards: usize,
+    mut exchange: impl FnMut(Vec<Vec<Board>>) -> Vec<Vec<Board>>,
+    mut progress: impl FnMut(Progress),
+) -> Vec<Vec<Board>> {
+    assert!(shard < shards, "invalid shard");
+    const BACKWARD: usize = (Board::SLOTS - 1) / 2 - 1;
+    const FORWARD: usize = (Board::SLOTS - 1) / 2;
     let mut timer = Timer::new();
     let threads = threads.unwrap_or(par::num_threads()).get();
-    let mut visited = vec![vec![], vec![Board::solved()]];
+    let solved = Some(Board::solved()).filter(|&b| shard_of(b, shards) == shard);
+    let mut visited = vec![vec![], solved.into_iter().collect()];
     let mut sort_time = Duration::ZERO;
 
     let mut total_constellations = 0;
@@ -71,6 +159,7 @@ pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
         timer.round("sort".into());
 
         let constellations = constellations.par_dedup(threads);
+        let constellations = distribute(constellations, shards, threads, &mut exchange);
         let deduped = constellations.len();
         visited.push(constellations);
 
@@ -88,6 +177,11 @@ pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
             timer.category("dedup".into()),
         );
         sort_time += timer.category("sort".into());
+        progress(Progress {
+            stage: "backward",
+            done: i,
+            total: BACKWARD,
+        });
     }
 
     timer.round("reverse step".into());
@@ -98,6 +192,7 @@ pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
         .collect();
     Board::normalize_all(&mut inverted);
     inverted.fast_sort_unstable_mt(threads);
+    let inverted = distribute(inverted, shards, threads, &mut exchange);
     visited.push(inverted);
 
     timer.round("inverse step".into());
@@ -115,6 +210,7 @@ pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
 
         constellations.fast_sort_unstable_mt(threads);
         let constellations = constellations.par_dedup(threads);
+        let constellations = distribute(constellations, shards, threads, &mut exchange);
         let deduped = constellations.len();
 
         timer.round("sort".into());
@@ -134,26 +230,36 @@ pub fn calculate_feasible_set(threads: Option<NonZero<usize>>) -> Vec<Board> {
             timer.category("intersect".into()),
         );
         sort_time += timer.category("sort".into());
+        progress(Progress {
+            stage: "forward",
+            done: (Board::SLOTS - 1) / 2 + 2 - remaining,
+            total: FORWARD,
+        });
     }
 
     timer.round("forward".into());
 
-    let solvable: Vec<Board> = visited
+    // the upper half are the inverses of the lower half,
+    // each of them belongs to the shard of its inverse
+    let mut levels = vec![vec![]; Board::SLOTS + 1];
+    for (pegs, level) in visited
         .into_iter()
+        .enumerate()
         .take((Board::SLOTS - 1) / 2 + 1)
-        .flat_map(|s| s.into_iter().flat_map(|b| [b, b.inverse().normalize()]))
-        .collect();
+    {
+        levels[Board::SLOTS - pegs] = level.iter().map(|b| b.inverse().normalize()).collect();
+        levels[pegs] = level;
+    }
 
     timer.round("collect".into());
 
-    assert_eq!(solvable.len(), 1679072);
     info!("analyzed {total_moves} moves and {total_constellations} different constellations");
     for (desc, dur) in timer.descriptions().zip(timer.durations()) {
         info!("{desc:>15}: {dur:>12?}");
     }
     info!("          total: {:>12?}", timer.total());
     info!("        sorting: {sort_time:?}");
-    solvable
+    levels
 }
 
 fn intersect_sorted_vecs<R>(a: &[R], b: &[R]) -> Vec<R>
@@ -176,3 +282,50 @@ where
     }
     res
 }
+
+#[test]
+#[ignore = "slow, run with --release"]
+fn test_shards() {
+    use std::sync::{Barrier, Mutex};
+
+    const SHARDS: usize = 3;
+    let threads = NonZero::new(1);
+    // mailbox[from][to]
+    let mailbox = Mutex::new(vec![vec![vec![]; SHARDS]; SHARDS]);
+    let barrier = Barrier::new(SHARDS);
+    let parts = std::thread::scope(|s| {
+        let shards: Vec<_> = (0..SHARDS)
+            .map(|shard| {
+                let (mailbox, barrier) = (&mailbox, &barrier);
+                let exchange = move |buckets| {
+                    mailbox.lock().unwrap()[shard] = buckets;
+                    barrier.wait();
+                    let parts = mailbox
+                        .lock()
+                        .unwrap()
+                        .iter_mut()
+                        .map(|to| std::mem::take(&mut to[shard]))
+                        .collect();
+                    barrier.wait();
+                    parts
+                };
+                s.spawn(move || {
+                    calculate_feasible_set_shard(threads, shard, SHARDS, exchange, |_| {})
+                })
+            })
+            .collect();
+        shards
+            .into_iter()
+            .map(|s| s.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    for (shard, part) in parts.iter().enumerate() {
+        assert!(!part.is_empty(), "shard {shard} is empty");
+    }
+    let mut feasible = calculate_feasible_set(None);
+    feasible.sort_unstable();
+    let mut union = parts.concat();
+    union.sort_unstable();
+    // disjoint and complete
+    assert_eq!(union, feasible);
+}
@@ -1,29 +1,63 @@
 mod board;
+mod calc_count;
 mod calc_first;
 mod calc_naive;
 mod calc_success;
+mod coords;
+mod dead_end;
+mod diff;
 mod dir;
 mod dominators;
+mod duel;
 mod feasible;
 mod hash;
 mod mov;
 mod normalize_dedup;
 mod pagoda;
 mod par;
+mod perft;
+mod pos;
+mod progress;
+mod puzzle;
+mod reachable;
+mod render;
+mod simulate;
 mod solution;
 mod sort;
 mod timer;
 mod unique_solutions;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 pub use board::{Board, Idx};
+pub use coords::Coord;
+pub use diff::BoardDiff;
 pub use dir::Dir;
 pub use hash::{CustomHashMap as HashMap, CustomHashSet as HashSet};
-pub use mov::Move;
-pub use solution::{Solution, SolutionMultiset};
+pub use mov::{Move, MoveError};
+pub use pos::Pos;
+pub use progress::Progress;
+pub use render::RenderStyle;
+pub use solution::{InvalidMove, Solution, SolutionMultiset};
 
-pub use calc_first::calculate_first_solution;
+pub use calc_count::count_solutions;
+pub use calc_first::{
+    calculate_first_solution, calculate_first_solution_from, calculate_first_solution_to,
+};
 pub use calc_naive::calculate_all_solutions_naive;
-pub use calc_success::calculate_p_random_chance_success;
-pub use feasible::calculate_feasible_set;
-pub use solution::print_solution;
-pub use unique_solutions::{all_unique_paths, all_unique_solutions};
+pub use calc_success::{
+    calculate_p_random_chance_success, calculate_p_random_chance_success_from,
+    calculate_p_random_chance_success_with_progress,
+};
+pub use dead_end::dead_end_distance;
+pub use duel::{DuelOutcome, duel_move};
+pub use feasible::{
+    calculate_feasible_set, calculate_feasible_set_by_pegs, calculate_feasible_set_shard,
+    calculate_feasible_set_with_progress, shard_of,
+};
+pub use perft::perft;
+pub use puzzle::{Difficulty, generate_puzzles};
+pub use reachable::reachable_states;
+pub use simulate::{Policy, Simulation, simulate};
+pub use solution::{print_solution, print_solution_from};
+pub use unique_solutions::{all_unique_paths, all_unique_solutions, all_unique_solutions_with_rng};
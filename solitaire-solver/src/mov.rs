@@ -1,30 +1,117 @@
 use board::Idx;
-use std::fmt::{Display, Error, Formatter};
+use std::{
+    fmt::{Display, Error, Formatter},
+    str::FromStr,
+};
 
-use crate::{Dir, board};
+use crate::{Board, Dir, Pos, board};
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
-    pub pos: (Idx, Idx),
-    pub skip: (Idx, Idx),
-    pub target: (Idx, Idx),
+    pub pos: Pos,
+    pub skip: Pos,
+    pub target: Pos,
 }
 
+/// why a move can not be played (see `Board::check_move`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    OutOfBounds,
+    /// source and target are not two holes apart in a row or column
+    NotAJump,
+    NoPeg,
+    NothingToJump,
+    TargetOccupied,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let reason = match self {
+            MoveError::OutOfBounds => "position out of bounds",
+            MoveError::NotAJump => "not a jump over a single hole",
+            MoveError::NoPeg => "no peg to move",
+            MoveError::NothingToJump => "no peg to jump over",
+            MoveError::TargetOccupied => "target is occupied",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for MoveError {}
+
 impl Move {
     fn dir(&self) -> Dir {
         match (self.pos, self.skip) {
-            (a, b) if a.0 < b.0 => Dir::South,
-            (a, b) if a.0 > b.0 => Dir::North,
-            (a, b) if a.1 < b.1 => Dir::East,
-            (a, b) if a.1 > b.1 => Dir::West,
+            (a, b) if a.row < b.row => Dir::South,
+            (a, b) if a.row > b.row => Dir::North,
+            (a, b) if a.col < b.col => Dir::East,
+            (a, b) if a.col > b.col => Dir::West,
             _ => unreachable!(),
         }
     }
+
+    /// the hole (`row * 7 + col`) in the upper six bits
+    /// and the direction in the lower two
+    pub fn to_byte(self) -> u8 {
+        let dir = Dir::enumerate().iter().position(|&d| d == self.dir());
+        ((self.pos.row * Board::SIZE + self.pos.col) as u8) << 2 | dir.unwrap() as u8
+    }
+
+    /// inverse of `to_byte`
+    pub fn from_byte(byte: u8) -> Result<Self, &'static str> {
+        let idx = (byte >> 2) as Idx;
+        let pos = Pos::try_new(idx / Board::SIZE, idx % Board::SIZE).ok_or("invalid position")?;
+        let (skip, target) = Dir::enumerate()[(byte & 0b11) as usize].mov(pos);
+        if !(Board::inbounds(skip) && Board::inbounds(target)) {
+            return Err("move leaves the board");
+        }
+        Ok(Move { pos, skip, target })
+    }
+
+    /// the holes it jumps from and to, e.g. `d2-d4` (see `Pos::name`)
+    pub fn name(self) -> String {
+        format!("{}-{}", self.pos.name(), self.target.name())
+    }
 }
 
 impl Display for Move {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "{}{}{}", self.pos.0, self.pos.1, self.dir())?;
+        write!(f, "{}{}", self.pos, self.dir())?;
         Ok(())
     }
 }
+
+impl FromStr for Move {
+    type Err = &'static str;
+
+    /// parses the format used by `Display`, e.g. `13v`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(y), Some(x), Some(dir), None) =
+            (chars.next(), chars.next(), chars.next(), chars.next())
+        else {
+            return Err("expected a move like `13v`");
+        };
+        let (Some(y), Some(x)) = (y.to_digit(10), x.to_digit(10)) else {
+            return Err("invalid position");
+        };
+        let pos = Pos {
+            row: y as Idx,
+            col: x as Idx,
+        };
+        let (skip, target) = Dir::try_from(dir)?.mov(pos);
+        if ![pos, skip, target].into_iter().all(Board::inbounds) {
+            return Err("move leaves the board");
+        }
+        Ok(Move { pos, skip, target })
+    }
+}
+
+#[test]
+fn test_move_name() {
+    let mov: Move = "13v".parse().unwrap();
+    assert_eq!(mov.name(), "d2-d4");
+    let mov: Move = "35<".parse().unwrap();
+    assert_eq!(mov.name(), "f4-d4");
+}
@@ -21,7 +21,7 @@ fn pagoda(board: Board) -> usize {
 fn prune_pagoda_inverse(constellations: &mut Vec<Board>) {
     let len = constellations.len();
     constellations.retain(|&b| pagoda(b.inverse()) >= pagoda(Board::solved()));
-    println!(
+    log::debug!(
         "pruned {} configurations ({}%)",
         len - constellations.len(),
         (len - constellations.len()) as f32 / len as f32
@@ -54,7 +54,7 @@ where
 /// slices `v` into multiple mutable slices according to `lens` lengths
 fn into_mut_slices<'a, T>(mut v: &'a mut [T], lens: &[usize]) -> Vec<&'a mut [T]> {
     let mut slices = vec![];
-    assert_eq!(v.len(), lens.iter().sum());
+    assert_eq!(v.len(), lens.iter().sum::<usize>());
     for len in lens {
         let (a, b) = v.split_at_mut(*len);
         slices.push(a);
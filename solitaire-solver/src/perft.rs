@@ -0,0 +1,46 @@
+use crate::{Board, Dir};
+
+/// number of move sequences of length `depth` starting at `board`
+pub fn perft(board: Board, depth: usize) -> u64 {
+    match depth {
+        0 => 1,
+        // bulk count the moves at the last level
+        1 => Dir::enumerate()
+            .into_iter()
+            .map(|dir| board.mov_pattern_mask(dir).count_pegs() as u64)
+            .sum(),
+        _ => Dir::enumerate()
+            .into_iter()
+            .flat_map(|dir| {
+                board
+                    .mov_pattern_mask(dir)
+                    .into_iter()
+                    .map(move |idx| board.toggle_mov_idx_unchecked(idx, dir))
+            })
+            .map(|b| perft(b, depth - 1))
+            .sum(),
+    }
+}
+
+#[test]
+fn test_perft() {
+    let board = Board::default();
+    assert_eq!(perft(board, 0), 1);
+    assert_eq!(perft(board, 1), 4);
+    assert_eq!(perft(board, 2), 12);
+    let legal_moves: u64 = board
+        .get_legal_moves()
+        .into_iter()
+        .map(|m| {
+            let b = board.mov(m);
+            b.get_legal_moves()
+                .into_iter()
+                .map(|m| b.mov(m).get_legal_moves().len() as u64)
+                .sum::<u64>()
+        })
+        .sum();
+    assert_eq!(perft(board, 3), legal_moves);
+    assert_eq!(perft(board, 4), 400);
+    assert_eq!(perft(board, 5), 2960);
+    assert_eq!(perft(board, 6), 24600);
+}
@@ -0,0 +1,106 @@
+use std::{
+    fmt::{Display, Error, Formatter},
+    ops::Add,
+};
+
+use crate::{Board, Dir, board::Idx};
+
+/// a hole on the board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pos {
+    pub row: Idx,
+    pub col: Idx,
+}
+
+impl Pos {
+    /// panics if the position is not on the board
+    pub const fn new(row: Idx, col: Idx) -> Self {
+        match Self::try_new(row, col) {
+            Some(pos) => pos,
+            None => panic!("position out of bounds"),
+        }
+    }
+
+    /// `None` if the position is not on the board
+    pub const fn try_new(row: Idx, col: Idx) -> Option<Self> {
+        let pos = Self { row, col };
+        if Board::inbounds(pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// position of the bit `idx` of a `Board`
+    pub const fn from_index(idx: usize) -> Self {
+        let pos = Self {
+            row: idx as Idx / Board::REPR,
+            col: idx as Idx % Board::REPR,
+        };
+        debug_assert!(Board::inbounds(pos));
+        pos
+    }
+
+    /// index of the bit of a `Board` representing this position
+    pub const fn index(self) -> usize {
+        (self.row * Board::REPR + self.col) as usize
+    }
+
+    /// `a`-`g` from left to right
+    pub const fn column_name(self) -> char {
+        (b'a' + self.col as u8) as char
+    }
+
+    /// `1`-`7` from top to bottom
+    pub const fn row_name(self) -> char {
+        (b'1' + self.row as u8) as char
+    }
+
+    /// column and row, e.g. `d4` for the center,
+    /// as labelled by `RenderStyle::coordinates`
+    pub fn name(self) -> String {
+        format!("{}{}", self.column_name(), self.row_name())
+    }
+}
+
+/// the neighbouring position in the given direction (which may be off the board)
+impl Add<Dir> for Pos {
+    type Output = Self;
+
+    fn add(self, dir: Dir) -> Self::Output {
+        let Pos { row, col } = self;
+        match dir {
+            Dir::North => Pos { row: row - 1, col },
+            Dir::West => Pos { row, col: col - 1 },
+            Dir::East => Pos { row, col: col + 1 },
+            Dir::South => Pos { row: row + 1, col },
+        }
+    }
+}
+
+impl Display for Pos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}{}", self.row, self.col)
+    }
+}
+
+#[test]
+fn test_pos() {
+    for idx in Board::full() {
+        assert_eq!(Pos::from_index(idx).index(), idx);
+    }
+    assert_eq!(Pos::try_new(0, 0), None);
+    assert_eq!(Pos::try_new(0, 2), Some(Pos { row: 0, col: 2 }));
+    assert_eq!(Pos::new(3, 3) + Dir::North, Pos::new(2, 3));
+    assert_eq!(Pos::new(3, 3) + Dir::West, Pos::new(3, 2));
+    assert_eq!(
+        Board::default().holes().collect::<Vec<_>>(),
+        [Pos::new(3, 3)]
+    );
+    assert_eq!(Board::default().pegs().count(), Board::SLOTS - 1);
+    assert_eq!(Board::empty().holes().count(), Board::SLOTS);
+    assert_eq!(Pos::new(3, 3).name(), "d4");
+    assert_eq!(Pos::new(0, 2).name(), "c1");
+    assert_eq!(Pos::new(4, 6).name(), "g5");
+}
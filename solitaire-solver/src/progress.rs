@@ -0,0 +1,10 @@
+/// progress of a long running calculation, reported after each step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// name of the current stage (e.g. "backward")
+    pub stage: &'static str,
+    /// number of finished steps in this stage
+    pub done: usize,
+    /// number of steps in this stage
+    pub total: usize,
+}
@@ -0,0 +1,85 @@
+use rand::{
+    Rng, RngExt,
+    seq::{IteratorRandom, SliceRandom},
+};
+
+use crate::{Board, HashMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// up to `count` distinct solvable constellations with `pegs` pegs,
+/// which can be reached from the starting constellation.
+///
+/// all of them are ranked by the chance of winning when chosing moves
+/// at random (`chances`, see `calculate_p_random_chance_success`):
+/// easy puzzles are taken from the best third, hard ones from the worst third.
+pub fn generate_puzzles(
+    pegs: usize,
+    difficulty: Difficulty,
+    count: usize,
+    chances: &HashMap<Board, f64>,
+    rng: &mut impl Rng,
+) -> Vec<Board> {
+    let mut pool = chances
+        .iter()
+        .filter(|(b, _)| b.count_pegs() == pegs)
+        .map(|(&b, &p)| (b, p))
+        .collect::<Vec<_>>();
+    pool.sort_by(|(a, p), (b, q)| q.total_cmp(p).then(a.cmp(b)));
+
+    let third = pool.len().div_ceil(3);
+    let (first, second) = (third, (2 * third).min(pool.len()));
+    let pool = match difficulty {
+        // too few constellations to tell them apart
+        _ if pool.len() < 3 => &mut pool[..],
+        Difficulty::Easy => &mut pool[..first],
+        Difficulty::Medium => &mut pool[first..second],
+        Difficulty::Hard => &mut pool[second..],
+    };
+    pool.shuffle(rng);
+    pool.iter()
+        .take(count)
+        // feasible constellations are normalized, pick a random orientation
+        .map(|(b, _)| b.symmetries()[rng.random_range(0..8)])
+        .collect()
+}
+
+impl Board {
+    /// a random constellation with `pegs` pegs out of the `feasible` ones
+    /// (see `calculate_feasible_set`) in a random orientation
+    /// or `None`, if there is none with that many pegs.
+    pub fn random_solvable<'a>(
+        pegs: usize,
+        feasible: impl IntoIterator<Item = &'a Board>,
+        rng: &mut impl Rng,
+    ) -> Option<Board> {
+        let board = feasible
+            .into_iter()
+            .filter(|b| b.count_pegs() == pegs)
+            .choose(rng)?;
+        Some(board.symmetries()[rng.random_range(0..8)])
+    }
+}
+
+#[test]
+fn test_random_solvable() {
+    use rand::{SeedableRng, rngs::StdRng};
+    let mut rng = StdRng::seed_from_u64(0);
+    let first_move = Board::default().get_legal_moves()[0];
+    let feasible = [
+        Board::solved(),
+        Board::default().mov(first_move).normalize(),
+    ];
+    assert_eq!(
+        Board::random_solvable(1, &feasible, &mut rng),
+        Some(Board::solved())
+    );
+    let board = Board::random_solvable(31, &feasible, &mut rng).unwrap();
+    assert_eq!(board.normalize(), feasible[1]);
+    assert_eq!(Board::random_solvable(2, &feasible, &mut rng), None);
+}
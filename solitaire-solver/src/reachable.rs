@@ -0,0 +1,24 @@
+use crate::{Board, HashSet, par, sort::Sort};
+
+/// all constellations (normalized) which can be reached from `start`,
+/// including `start` itself
+pub fn reachable_states(start: Board) -> HashSet<Board> {
+    reachable_layers(start).into_iter().flatten().collect()
+}
+
+/// the constellations (normalized) which can be reached from `start`,
+/// indexed by the number of moves it takes
+pub(crate) fn reachable_layers(start: Board) -> Vec<Vec<Board>> {
+    let threads = par::num_threads().get();
+    let mut layers = vec![];
+    let mut layer = vec![start.normalize()];
+    while !layer.is_empty() {
+        let mut next = Board::possible_moves(&layer);
+        Board::normalize_all(&mut next);
+        next.fast_sort_unstable_mt(threads);
+        next.dedup();
+        layers.push(layer);
+        layer = next;
+    }
+    layers
+}
@@ -0,0 +1,79 @@
+use std::fmt::{Result, Write};
+
+use crate::{Board, Move, Pos};
+
+/// how `Board::render` draws a constellation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStyle {
+    /// `●` and `○` instead of `o` and `.`
+    pub unicode: bool,
+    /// columns `a`-`g` above and rows `1`-`7` left of the board
+    pub coordinates: bool,
+    /// puts brackets around the holes of this move
+    pub highlight: Option<Move>,
+}
+
+impl Board {
+    pub fn render(&self, style: RenderStyle) -> String {
+        let mut out = String::new();
+        self.write_rendered(style, &mut out).unwrap();
+        out
+    }
+
+    pub(crate) fn write_rendered(&self, style: RenderStyle, w: &mut impl Write) -> Result {
+        if style.coordinates {
+            w.write_str("  ")?;
+            for col in 0..Board::SIZE {
+                write!(w, " {} ", (b'a' + col as u8) as char)?;
+            }
+            writeln!(w)?;
+        }
+        for row in 0..Board::SIZE {
+            if style.coordinates {
+                write!(w, "{} ", row + 1)?;
+            }
+            for col in 0..Board::SIZE {
+                let pos = Pos { row, col };
+                let c = match (Self::inbounds(pos), self.occupied(pos), style.unicode) {
+                    (false, _, _) => ' ',
+                    (true, true, false) => 'o',
+                    (true, false, false) => '.',
+                    (true, true, true) => '●',
+                    (true, false, true) => '○',
+                };
+                let highlighted = style
+                    .highlight
+                    .is_some_and(|m| [m.pos, m.skip, m.target].contains(&pos));
+                match highlighted {
+                    true => write!(w, "[{c}]")?,
+                    false => write!(w, " {c} ")?,
+                }
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_render() {
+    let mov = "13v".parse().unwrap();
+    let board = Board::default().mov(mov);
+    let style = RenderStyle {
+        unicode: true,
+        coordinates: true,
+        highlight: Some(mov),
+    };
+    let expected = [
+        "   a  b  c  d  e  f  g ",
+        "1        ●  ●  ●       ",
+        "2        ● [○] ●       ",
+        "3  ●  ●  ● [○] ●  ●  ● ",
+        "4  ●  ●  ● [●] ●  ●  ● ",
+        "5  ●  ●  ●  ●  ●  ●  ● ",
+        "6        ●  ●  ●       ",
+        "7        ●  ●  ●       ",
+    ];
+    assert_eq!(board.render(style).lines().collect::<Vec<_>>(), expected);
+    assert_eq!(board.render(RenderStyle::default()), board.to_string());
+}
@@ -0,0 +1,89 @@
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{Board, HashSet, Move};
+
+/// how moves are chosen during a playout
+#[derive(Clone, Copy, Debug)]
+pub enum Policy<'a> {
+    /// any legal move with equal chance
+    Random,
+    /// a move leaving the most legal moves behind
+    Greedy,
+    /// a move leading to a feasible constellation if there is one
+    Guided(&'a HashSet<Board>),
+}
+
+impl Policy<'_> {
+    fn choose(&self, board: Board, moves: &[Move], rng: &mut impl Rng) -> Move {
+        match self {
+            Policy::Random => *moves.choose(rng).expect("at least one move"),
+            Policy::Greedy => {
+                let mobility = |m: &Move| board.mov(*m).get_legal_moves().len();
+                let max = moves.iter().map(mobility).max().expect("at least one move");
+                let best = moves
+                    .iter()
+                    .copied()
+                    .filter(|m| mobility(m) == max)
+                    .collect::<Vec<_>>();
+                *best.choose(rng).unwrap()
+            }
+            Policy::Guided(feasible) => {
+                let good = moves
+                    .iter()
+                    .copied()
+                    .filter(|m| feasible.contains(&board.mov(*m).normalize()))
+                    .collect::<Vec<_>>();
+                *good.choose(rng).or_else(|| moves.choose(rng)).unwrap()
+            }
+        }
+    }
+}
+
+/// outcome of a number of playouts
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub games: u64,
+    pub wins: u64,
+    /// number of games by the number of pegs left at the end
+    pub remaining: Vec<u64>,
+}
+
+impl Simulation {
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.games as f64
+    }
+
+    /// wilson score interval of the win rate for the quantile `z`
+    /// (e.g. 1.96 for 95%)
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let n = self.games as f64;
+        let p = self.win_rate();
+        let z2 = z * z;
+        let center = (p + z2 / (2. * n)) / (1. + z2 / n);
+        let margin = z / (1. + z2 / n) * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+        ((center - margin).max(0.), (center + margin).min(1.))
+    }
+}
+
+/// plays `games` games from `start` until no moves are left
+pub fn simulate(start: Board, games: u64, policy: Policy, rng: &mut impl Rng) -> Simulation {
+    let mut simulation = Simulation {
+        games: 0,
+        wins: 0,
+        remaining: vec![0; Board::SLOTS + 1],
+    };
+    for _ in 0..games {
+        let mut board = start;
+        loop {
+            let moves = board.get_legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            board = board.mov(policy.choose(board, &moves, rng));
+        }
+        simulation.games += 1;
+        simulation.wins += board.is_solved() as u64;
+        simulation.remaining[board.count_pegs()] += 1;
+    }
+    simulation
+}
@@ -1,37 +1,126 @@
 use std::{
     collections::BTreeMap,
+    error::Error,
     fmt::{Display, Formatter, Result},
     ops::Index,
 };
 
-use crate::{Board, HashSet, Move};
+use smallvec::SmallVec;
 
-#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+use crate::{Board, HashSet, Move, MoveError, RenderStyle};
+
+/// a sequence of moves, stored inline for up to the
+/// length of a complete game on the english board
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Solution {
-    steps: [Move; 31],
-    count: usize,
+    steps: SmallVec<[Move; Board::SLOTS - 2]>,
 }
 
 impl Solution {
     pub fn push(&mut self, mov: Move) {
-        self.steps[self.count] = mov;
-        self.count += 1;
+        self.steps.push(mov);
     }
     pub fn pop(&mut self) -> Move {
-        self.count -= 1;
-        self.steps[self.count]
+        self.steps.pop().expect("no moves left")
     }
+    /// number of moves of a complete game on the english board
     pub fn total(&self) -> usize {
-        self.steps.len()
+        Board::SLOTS - 2
     }
     pub fn len(&self) -> usize {
-        self.count
+        self.steps.len()
+    }
+    pub fn moves(&self) -> &[Move] {
+        &self.steps
+    }
+
+    /// writes every move followed by the constellation after it
+    /// (with the holes of the move highlighted), starting with `start`
+    pub fn write_pretty(&self, start: Board, w: &mut impl std::fmt::Write) -> Result {
+        let mut board = start;
+        writeln!(w, "{board}")?;
+        for &mov in self.moves() {
+            board = board.mov(mov);
+            writeln!(w, "{mov}")?;
+            let style = RenderStyle {
+                highlight: Some(mov),
+                ..Default::default()
+            };
+            board.write_rendered(style, w)?;
+            writeln!(w)?;
+        }
+        Ok(())
     }
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// one byte per move (see `Move::to_byte`)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.moves().iter().map(|mov| mov.to_byte()).collect()
+    }
+
+    /// inverse of `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, &'static str> {
+        bytes.iter().map(|&byte| Move::from_byte(byte)).collect()
+    }
+
+    /// replays the moves starting at `start`, checking each of them for legality.
+    /// returns the constellation after the last move
+    pub fn validate(&self, start: Board) -> std::result::Result<Board, InvalidMove> {
+        let mut board = start;
+        for (index, &mov) in self.moves().iter().enumerate() {
+            board
+                .check_move(mov.pos, mov.target)
+                .and_then(|m| {
+                    if m.skip == mov.skip {
+                        Ok(m)
+                    } else {
+                        Err(MoveError::NotAJump)
+                    }
+                })
+                .map_err(|reason| InvalidMove { index, mov, reason })?;
+            board = board.mov(mov);
+        }
+        Ok(board)
+    }
+}
+
+impl From<&[Move]> for Solution {
+    fn from(moves: &[Move]) -> Self {
+        moves.iter().copied().collect()
+    }
+}
+
+impl FromIterator<Move> for Solution {
+    fn from_iter<T: IntoIterator<Item = Move>>(iter: T) -> Self {
+        Self {
+            steps: iter.into_iter().collect(),
+        }
+    }
 }
 
+/// the move at `index` of a solution can not be played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidMove {
+    pub index: usize,
+    pub mov: Move,
+    pub reason: MoveError,
+}
+
+impl Display for InvalidMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "move {} ({}): {}", self.index + 1, self.mov, self.reason)
+    }
+}
+
+impl Error for InvalidMove {}
+
 impl Index<usize> for Solution {
     type Output = Move;
 
@@ -42,8 +131,10 @@ impl Index<usize> for Solution {
 
 impl Display for Solution {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let steps = (0..self.steps.len())
-            .map(|i| format!("{}", self.steps[i]))
+        let steps = self
+            .moves()
+            .iter()
+            .map(|m| m.to_string())
             .collect::<Vec<_>>();
         write!(f, "{}", steps.join(" "))?;
         Ok(())
@@ -53,40 +144,21 @@ impl Display for Solution {
 impl IntoIterator for Solution {
     type Item = Move;
 
-    type IntoIter = SolutionIter;
+    type IntoIter = smallvec::IntoIter<[Move; Board::SLOTS - 2]>;
 
     fn into_iter(self) -> Self::IntoIter {
-        SolutionIter { sol: self, idx: 0 }
+        self.steps.into_iter()
     }
 }
 
-pub struct SolutionIter {
-    sol: Solution,
-    idx: usize,
-}
-
-impl Iterator for SolutionIter {
-    type Item = Move;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.idx < self.sol.steps.len() {
-            let res = self.sol.steps[self.idx];
-            self.idx += 1;
-            Some(res)
-        } else {
-            None
-        }
-    }
+pub fn print_solution(solution: Solution) {
+    print_solution_from(Board::default(), solution);
 }
 
-pub fn print_solution(solution: Solution) {
-    let mut board = Board::default();
-    println!("{board}");
-    for mov in solution {
-        board = board.mov(mov);
-        println!("{mov}");
-        println!("{board}");
-    }
+pub fn print_solution_from(board: Board, solution: Solution) {
+    let mut out = String::new();
+    solution.write_pretty(board, &mut out).unwrap();
+    print!("{out}");
 }
 
 /// A solution is a multiset of steps (step -> count)
@@ -124,7 +196,7 @@ fn from_unordered_moves(
 impl From<(SolutionMultiset, &HashSet<Board>)> for Solution {
     fn from(mf: (SolutionMultiset, &HashSet<Board>)) -> Self {
         let (mset, feasible) = mf;
-        log::info!("from::<SolutionMultiset>()");
+        log::debug!("from::<SolutionMultiset>()");
         let mut vec: Vec<_> = mset
             .into_iter()
             .flat_map(|(k, v)| std::iter::repeat(k).take(v))
@@ -153,3 +225,53 @@ impl From<Solution> for [Board; 32] {
         boards
     }
 }
+
+#[test]
+fn test_validate() {
+    let moves = ["13v", "21>", "02v", "04<"].map(|m| m.parse::<Move>().unwrap());
+    let solution = Solution::from(&moves[..]);
+    let board = solution.validate(Board::default()).unwrap();
+    assert_eq!(board.count_pegs(), 28);
+
+    let moves = ["13v", "21>", "13v"].map(|m| m.parse::<Move>().unwrap());
+    let solution = Solution::from(&moves[..]);
+    let err = solution.validate(Board::default()).unwrap_err();
+    assert_eq!(err.index, 2);
+    assert_eq!(err.reason, MoveError::NoPeg);
+}
+
+#[test]
+fn test_bytes() {
+    let solution = crate::calculate_first_solution_from(Board::default()).unwrap();
+    let bytes = solution.to_bytes();
+    assert_eq!(bytes.len(), solution.len());
+    assert_eq!(Solution::from_bytes(&bytes), Ok(solution));
+    // every move of the board survives the round trip
+    for pos in Board::full().pegs() {
+        for dir in crate::Dir::enumerate() {
+            let (skip, target) = dir.mov(pos);
+            if Board::inbounds(target) {
+                let mov = Move { pos, skip, target };
+                assert_eq!(Move::from_byte(mov.to_byte()), Ok(mov));
+            }
+        }
+    }
+    // (0, 0) is not a hole, (0, 2) can not jump up
+    assert!(Move::from_byte(0).is_err());
+    assert!(Move::from_byte(2 << 2).is_err());
+}
+
+#[test]
+fn test_eq() {
+    let moves = ["13v", "21>", "02v"].map(|m| m.parse::<Move>().unwrap());
+    let mut a = Solution::from(&moves[..]);
+    let b = Solution::from(&moves[..2]);
+    assert_ne!(a, b);
+    assert!(b < a);
+    a.pop();
+    assert_eq!(a, b);
+    assert_eq!(std::collections::HashSet::from([a, b]).len(), 1);
+    // longer than a game on the english board
+    let long = Solution::from_iter(std::iter::repeat_n(moves[0], 40));
+    assert_eq!(long.len(), 40);
+}
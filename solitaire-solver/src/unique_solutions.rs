@@ -1,6 +1,7 @@
 use crate::solution::SolutionMultiset;
 use crate::{Board, Move};
 use crate::{HashMap, HashSet, Solution};
+use rand::{Rng, RngExt};
 use std::array;
 use std::collections::BTreeMap;
 
@@ -15,6 +16,16 @@ use std::collections::BTreeMap;
 pub fn all_unique_solutions(
     start: Board,
     feasible: impl Iterator<Item = Board>,
+) -> std::collections::HashSet<SolutionMultiset> {
+    all_unique_solutions_with_rng(start, feasible, &mut rand::rng())
+}
+
+/// same as `all_unique_solutions`, drawing the hash keys of the multisets from `rng`
+/// (hash collisions can merge distinct multisets, a seeded `rng` makes them reproducible)
+pub fn all_unique_solutions_with_rng(
+    start: Board,
+    feasible: impl Iterator<Item = Board>,
+    rng: &mut impl Rng,
 ) -> std::collections::HashSet<SolutionMultiset> {
     log::info!("calculating unique solutions ....");
     let feasible: HashSet<Board> = feasible.collect();
@@ -29,8 +40,7 @@ pub fn all_unique_solutions(
 
     let mut visited: std::collections::HashSet<(Board, MultisetHash)> =
         std::collections::HashSet::new();
-    println!();
-    let mut zobrist = ZobristTable::default();
+    let mut zobrist = ZobristTable::new(rng);
     visited.insert((start, 0));
 
     while let Some((board, multiset, hash)) = stack.pop() {
@@ -74,12 +84,11 @@ fn canonicalize(
     feasible: HashSet<Board>,
 ) -> Vec<[Board; 32]> {
     for s in &unique_solutions {
-        for (s, c) in s {
-            for _ in 0..*c {
-                print!("{s} ");
-            }
-        }
-        println!();
+        let moves = s
+            .iter()
+            .flat_map(|(m, &c)| std::iter::repeat_n(m.to_string(), c))
+            .collect::<Vec<_>>();
+        log::debug!("{}", moves.join(" "));
     }
     // canonicalize => sort multiset,
     // then always take first possible move on initial board.
@@ -93,7 +102,7 @@ fn canonicalize(
         unique_solutions.len()
     );
     for s in &unique_solutions {
-        println!("{s}");
+        log::debug!("{s}");
     }
 
     let unique_solutions: std::collections::HashSet<[Board; 32]> = unique_solutions
@@ -112,12 +121,19 @@ fn canonicalize(
 
 /// Precomputed random values for each (Step, occurrence_index) pair.
 /// occurrence_index 0 means "going from 0 to 1 occurrences", etc.
-#[derive(Default)]
-struct ZobristTable {
+struct ZobristTable<'a, R> {
     table: std::collections::HashMap<(Move, usize), u64>,
+    rng: &'a mut R,
 }
 
-impl ZobristTable {
+impl<'a, R: Rng> ZobristTable<'a, R> {
+    fn new(rng: &'a mut R) -> Self {
+        Self {
+            table: Default::default(),
+            rng,
+        }
+    }
+
     fn delta(&mut self, step: &Move, new_count: usize) -> u64 {
         // XOR out the old count contribution, XOR in the new one
         let old = self.get(step, new_count - 1);
@@ -129,7 +145,7 @@ impl ZobristTable {
         *self
             .table
             .entry((step.clone(), count))
-            .or_insert_with(rand::random)
+            .or_insert_with(|| self.rng.random())
     }
 }
 
@@ -0,0 +1,178 @@
+// javascript bindings for embedding the solver without the game
+//
+// the feasible set can either be calculated in place or streamed in
+// from a precomputed file (compressed boards as u64 le), e.g.:
+//
+// ```js
+// const set = new FeasibleSet();
+// const reader = (await fetch("feasible.bin")).body.getReader();
+// for (let r = await reader.read(); !r.done; r = await reader.read()) {
+//     set.extend(r.value);
+// }
+// ```
+
+use std::num::NonZero;
+
+use wasm_bindgen::prelude::*;
+
+use crate::HashSet;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Board(crate::Board);
+
+#[wasm_bindgen]
+impl Board {
+    /// the starting constellation with only the center hole empty
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(crate::Board::default())
+    }
+
+    /// parses a board in `o` (peg) / `.` (hole) notation
+    pub fn parse(s: &str) -> Result<Board, JsError> {
+        crate::Board::try_from(s).map(Self).map_err(JsError::new)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn display(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[wasm_bindgen(js_name = countPegs)]
+    pub fn count_pegs(&self) -> usize {
+        self.0.count_pegs()
+    }
+
+    #[wasm_bindgen(js_name = isSolved)]
+    pub fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.0.get_legal_moves().into_iter().map(Move).collect()
+    }
+
+    /// applies a legal move, returning the resulting board
+    pub fn apply(&self, mov: &Move) -> Result<Board, JsError> {
+        self.0
+            .is_legal_move(mov.0.pos, mov.0.target)
+            .map(|m| Self(self.0.mov(m)))
+            .ok_or_else(|| JsError::new("illegal move"))
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Move(crate::Move);
+
+#[wasm_bindgen]
+impl Move {
+    #[wasm_bindgen(getter = fromY)]
+    pub fn src_y(&self) -> i8 {
+        self.0.pos.row
+    }
+
+    #[wasm_bindgen(getter = fromX)]
+    pub fn src_x(&self) -> i8 {
+        self.0.pos.col
+    }
+
+    #[wasm_bindgen(getter = toY)]
+    pub fn dst_y(&self) -> i8 {
+        self.0.target.row
+    }
+
+    #[wasm_bindgen(getter = toX)]
+    pub fn dst_x(&self) -> i8 {
+        self.0.target.col
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn display(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[wasm_bindgen]
+pub struct Solution(crate::Solution);
+
+#[wasm_bindgen]
+impl Solution {
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        self.0.clone().into_iter().map(Move).collect()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn display(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// first solution found from `board` or `undefined` if it is not solvable
+#[wasm_bindgen(js_name = solveFirstFrom)]
+pub fn solve_first_from(board: &Board) -> Option<Solution> {
+    crate::calculate_first_solution_from(board.0).map(Solution)
+}
+
+/// set of all normalized constellations that can still be solved
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct FeasibleSet {
+    boards: HashSet<crate::Board>,
+    /// trailing bytes of an incomplete entry from the last chunk
+    pending: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl FeasibleSet {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// calculates the set in place (takes a few seconds in the browser)
+    pub fn calculate() -> Self {
+        let boards = crate::calculate_feasible_set(NonZero::new(1));
+        Self {
+            boards: boards.into_iter().collect(),
+            pending: vec![],
+        }
+    }
+
+    /// adds a chunk of a precomputed set of compressed boards (u64 le).
+    /// chunks may be split at arbitrary offsets.
+    pub fn extend(&mut self, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+        let entries = self.pending.chunks_exact(8);
+        let rest = entries.remainder().len();
+        for e in entries {
+            let compressed = u64::from_le_bytes(e.try_into().unwrap());
+            self.boards
+                .insert(crate::Board::from_compressed_repr(compressed));
+        }
+        self.pending.drain(..self.pending.len() - rest);
+    }
+
+    /// whether `board` can still be solved
+    pub fn contains(&self, board: &Board) -> bool {
+        self.boards.contains(&board.0.normalize())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.boards.len()
+    }
+}
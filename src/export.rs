@@ -0,0 +1,90 @@
+// writing the feasible set to a file (see `export`)
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use solitaire_solver::Board;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Encoding {
+    /// compressed boards as sorted u64 (little endian)
+    #[default]
+    Raw,
+    /// `raw`, compressed with brotli
+    Brotli,
+    /// compressed boards as serialized `RoaringTreemap`
+    Roaring,
+    /// json array of compressed boards
+    Json,
+}
+
+/// writes `feasible` to `path`, returning the number of bytes written
+pub fn export(feasible: &[Board], path: &Path, encoding: Encoding) -> io::Result<u64> {
+    let mut compressed = feasible
+        .iter()
+        .map(Board::to_compressed_repr)
+        .collect::<Vec<_>>();
+    compressed.sort_unstable();
+
+    let mut w = BufWriter::new(File::create(path)?);
+    match encoding {
+        Encoding::Raw => write_raw(&mut w, &compressed)?,
+        Encoding::Brotli => {
+            let mut raw = vec![];
+            write_raw(&mut raw, &compressed)?;
+            // unlike `CompressorWriter`, this reports errors when finishing the stream
+            let params = brotli::enc::BrotliEncoderParams {
+                quality: 9,
+                lgwin: 22,
+                ..Default::default()
+            };
+            brotli::BrotliCompress(&mut raw.as_slice(), &mut w, &params)?;
+        }
+        Encoding::Roaring => {
+            let treemap =
+                roaring::RoaringTreemap::from_sorted_iter(compressed).expect("boards are sorted");
+            treemap.serialize_into(&mut w)?;
+        }
+        Encoding::Json => {
+            w.write_all(b"[")?;
+            for (i, c) in compressed.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                write!(w, "{c}")?;
+            }
+            w.write_all(b"]\n")?;
+        }
+    }
+    w.flush()?;
+    drop(w);
+    Ok(path.metadata()?.len())
+}
+
+fn write_raw(w: &mut impl Write, compressed: &[u64]) -> io::Result<()> {
+    for c in compressed {
+        w.write_all(&c.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// reads boards written with `Encoding::Raw`
+pub fn import_raw(path: &Path) -> io::Result<Vec<Board>> {
+    let mut bytes = vec![];
+    BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+    let entries = bytes.chunks_exact(8);
+    if !entries.remainder().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated"));
+    }
+    entries
+        .map(|entry| {
+            let compressed = u64::from_le_bytes(entry.try_into().unwrap());
+            Board::try_from_compressed_repr(compressed)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid entry"))
+        })
+        .collect()
+}
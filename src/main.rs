@@ -1,16 +1,42 @@
-use std::{collections::HashSet, num::NonZero};
+use std::{
+    collections::HashSet,
+    io::{self, Read},
+    num::NonZero,
+    path::{Path, PathBuf},
+};
 
-use clap::{Parser, Subcommand};
-use solitaire_solver::Board;
+use clap::{Parser, Subcommand, ValueEnum};
+use export::Encoding;
+use memory::Memory;
+use rand::{SeedableRng, rngs::StdRng};
+use report::{Format, Report, Value};
+use shard::Exchange;
+use solitaire_solver::{Board, Move, Pos, Solution};
+
+mod export;
+mod memory;
+mod progress;
+mod report;
+mod shard;
 
 #[derive(Parser)]
 struct Args {
     /// print the solution
     #[arg(short, long)]
     print: bool,
-    /// number of threads to use for all solutions
-    #[arg(short, long)]
+    /// number of threads of the solver
+    #[arg(short, long, global = true)]
     threads: Option<NonZero<usize>>,
+    /// output format
+    #[arg(short, long, global = true, value_enum, default_value_t)]
+    format: Format,
+    /// seed for reproducible runs of the randomized subcommands
+    /// (a random one is picked and reported otherwise)
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// only print results and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
     /// subcommands
     #[command(subcommand)]
     command: Option<Command>,
@@ -19,7 +45,19 @@ struct Args {
 #[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
 enum Command {
     /// calculate all solutions
-    CalculateAll,
+    CalculateAll {
+        /// only calculate part `i` of `n` (e.g. `2/4`) and write it to a file
+        /// (combine all parts with `merge`). every level is split between
+        /// the parts, which have to run at the same time
+        #[arg(long, value_parser = parse_shard)]
+        shard: Option<(usize, usize)>,
+        /// file to write the shard to (default: `shard-<i>-of-<n>.bin`)
+        #[arg(short, long, requires = "shard")]
+        output: Option<PathBuf>,
+        /// empty directory shared by all parts to exchange constellations
+        #[arg(long, requires = "shard", default_value = "shards")]
+        dir: PathBuf,
+    },
     /// calculate all solutions (naive recursively)
     CalculateAllNaive,
     /// calculate a single solution
@@ -32,71 +70,539 @@ enum Command {
     UniqueSolutions,
     /// calculate unique paths of solutions
     UniquePaths,
+    /// solve the given position
+    Solve {
+        /// rows of `o` (peg) and `.` (hole) separated by newlines or `/`
+        /// (`-` reads the position from stdin)
+        #[arg(value_parser = parse_board)]
+        position: Board,
+        /// count all solutions instead of printing one
+        #[arg(short, long)]
+        all: bool,
+        /// hole the last peg has to end up in (`y,x`)
+        #[arg(long, value_parser = parse_pos, default_value = "3,3")]
+        target: Pos,
+    },
+    /// replay a sequence of moves, checking each of them for legality
+    Verify {
+        /// position to start from (see `solve`)
+        #[arg(
+            short,
+            long,
+            value_parser = parse_board,
+            default_value = DEFAULT_POSITION
+        )]
+        start: Board,
+        /// moves in the format printed by `solve`, e.g. `13v 21>`
+        moves: Vec<Move>,
+    },
+    /// count all move sequences of the given length (move generator check)
+    Perft {
+        /// number of moves
+        depth: usize,
+        /// position to start from (see `solve`)
+        #[arg(
+            short,
+            long,
+            value_parser = parse_board,
+            default_value = DEFAULT_POSITION
+        )]
+        start: Board,
+    },
+    /// play games with a given strategy and print statistics about them
+    Simulate {
+        /// number of games
+        #[arg(short = 'n', long, default_value_t = 10000, value_parser = clap::value_parser!(u64).range(1..))]
+        games: u64,
+        /// how moves are chosen
+        #[arg(long, value_enum, default_value_t)]
+        policy: Policy,
+        /// position to start from (see `solve`)
+        #[arg(
+            short,
+            long,
+            value_parser = parse_board,
+            default_value = DEFAULT_POSITION
+        )]
+        start: Board,
+    },
+    /// generate solvable starting positions
+    Puzzle {
+        /// number of pegs
+        #[arg(short, long, default_value_t = 16, value_parser = clap::value_parser!(u8).range(1..=32))]
+        pegs: u8,
+        /// how hard the positions are to solve by chosing moves at random
+        #[arg(short, long, value_enum, default_value_t)]
+        difficulty: Difficulty,
+        /// number of positions
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+    },
+    /// write all feasible constellations to a file
+    Export {
+        /// file to write to
+        #[arg(short, long)]
+        output: PathBuf,
+        /// encoding of the file
+        #[arg(short, long, value_enum, default_value_t)]
+        encoding: Encoding,
+    },
+    /// combine the shards of `calculate-all --shard` into a single file
+    Merge {
+        /// shard files
+        #[arg(required = true)]
+        shards: Vec<PathBuf>,
+        /// file to write to
+        #[arg(short, long)]
+        output: PathBuf,
+        /// encoding of the file
+        #[arg(short, long, value_enum, default_value_t)]
+        encoding: Encoding,
+    },
+    /// print statistics and the best moves for the given position
+    Analyze {
+        /// rows of `o` (peg) and `.` (hole) separated by newlines or `/`
+        /// (`-` reads the position from stdin)
+        #[arg(value_parser = parse_board)]
+        position: Board,
+        /// also count the reachable constellations
+        /// (a full search, which takes a while from the start position)
+        #[arg(short, long)]
+        reachable: bool,
+    },
 }
 
-fn main() {
-    let args = Args::parse();
-    #[cfg(not(feature = "game"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum Policy {
+    /// any legal move with equal chance
+    #[default]
+    Random,
+    /// a move leaving the most legal moves behind
+    Greedy,
+    /// a move leading to a feasible constellation
+    Guided,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl From<Difficulty> for solitaire_solver::Difficulty {
+    fn from(d: Difficulty) -> Self {
+        match d {
+            Difficulty::Easy => Self::Easy,
+            Difficulty::Medium => Self::Medium,
+            Difficulty::Hard => Self::Hard,
+        }
+    }
+}
+
+const DEFAULT_POSITION: &str = "ooo/ooo/ooooooo/ooo.ooo/ooooooo/ooo/ooo";
+
+fn parse_board(s: &str) -> Result<Board, String> {
+    let mut input = s.to_string();
+    if s == "-" {
+        input.clear();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| e.to_string())?;
+    }
+    Board::try_from(input.as_str()).map_err(str::to_string)
+}
+
+fn parse_pos(s: &str) -> Result<Pos, String> {
+    let (y, x) = s.split_once(',').ok_or("expected `y,x`")?;
+    let y = y.trim().parse().map_err(|_| "invalid row")?;
+    let x = x.trim().parse().map_err(|_| "invalid column")?;
+    Pos::try_new(y, x).ok_or("position out of bounds".into())
+}
+
+fn parse_shard(s: &str) -> Result<(usize, usize), String> {
+    let (i, n) = s.split_once('/').ok_or("expected `i/n`")?;
+    let i: usize = i.trim().parse().map_err(|_| "invalid shard")?;
+    let n: usize = n.trim().parse().map_err(|_| "invalid number of shards")?;
+    if !(1..=n).contains(&i) {
+        return Err(format!("shard must be between 1 and {n}"));
+    }
+    Ok((i, n))
+}
+
+fn analyze(board: Board, reachable: bool, threads: Option<NonZero<usize>>) -> Report {
+    let feasible = solitaire_solver::calculate_feasible_set(threads);
+    // the feasible set only covers what can be reached from the standard start,
+    // other positions which can be won need a search of their own
+    let chances = if feasible.contains(&board.normalize())
+        || solitaire_solver::calculate_first_solution_from(board).is_none()
     {
-        use env_logger::Env;
+        solitaire_solver::calculate_p_random_chance_success(feasible)
+    } else {
+        solitaire_solver::calculate_p_random_chance_success_from(board)
+    };
+    let chance = |b: Board| chances.get(&b.normalize()).copied();
+
+    let mut moves = board
+        .get_legal_moves()
+        .into_iter()
+        .map(|m| (m, chance(board.mov(m))))
+        .collect::<Vec<_>>();
+    moves.sort_by(|(_, a), (_, b)| b.unwrap_or(0.).total_cmp(&a.unwrap_or(0.)));
+    let feasible_moves = moves.iter().filter(|(_, p)| p.is_some()).count();
 
-        let env = Env::default().filter_or("RUST_LOG", "info");
-        env_logger::init_from_env(env);
+    let mut report = Report::new()
+        .field("pegs", board.count_pegs())
+        .field("legal moves", moves.len())
+        .field("feasible moves", feasible_moves)
+        .field(
+            "random win chance",
+            Value::Chance(chance(board).unwrap_or(0.)),
+        )
+        .field(
+            "nearest dead end",
+            solitaire_solver::dead_end_distance(board),
+        );
+    if reachable {
+        report = report.field(
+            "reachable constellations",
+            solitaire_solver::reachable_states(board).len(),
+        );
     }
-    match args.command {
+    report.table(
+        "best moves",
+        &["move", "feasible", "random win chance"],
+        moves.into_iter().map(|(m, p)| {
+            vec![
+                m.to_string().into(),
+                p.is_some().into(),
+                Value::Chance(p.unwrap_or(0.)),
+            ]
+        }),
+    )
+}
+
+fn simulate(
+    start: Board,
+    games: u64,
+    policy: Policy,
+    seed: u64,
+    threads: Option<NonZero<usize>>,
+) -> Report {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let feasible = match policy {
+        Policy::Greedy => None,
+        _ => Some(solitaire_solver::calculate_feasible_set(threads)),
+    };
+    let (simulation, expected) = match (policy, feasible) {
+        (Policy::Random, Some(feasible)) => {
+            let simulation = solitaire_solver::simulate(
+                start,
+                games,
+                solitaire_solver::Policy::Random,
+                &mut rng,
+            );
+            let chances = solitaire_solver::calculate_p_random_chance_success(feasible);
+            let expected = chances.get(&start.normalize()).copied().unwrap_or(0.);
+            (simulation, Some(expected))
+        }
+        (Policy::Guided, Some(feasible)) => {
+            let feasible = feasible.into_iter().collect();
+            let policy = solitaire_solver::Policy::Guided(&feasible);
+            (
+                solitaire_solver::simulate(start, games, policy, &mut rng),
+                None,
+            )
+        }
+        _ => {
+            let policy = solitaire_solver::Policy::Greedy;
+            (
+                solitaire_solver::simulate(start, games, policy, &mut rng),
+                None,
+            )
+        }
+    };
+    // 95% confidence
+    let (low, high) = simulation.confidence_interval(1.96);
+    Report::new()
+        .field("seed", seed)
+        .field("games", simulation.games)
+        .field("wins", simulation.wins)
+        .field("win rate", simulation.win_rate())
+        .field("win rate lower bound", low)
+        .field("win rate upper bound", high)
+        .field("expected win rate", expected)
+        .table(
+            "remaining pegs",
+            &["pegs", "games", "share"],
+            (1..=start.count_pegs())
+                .filter(|&pegs| simulation.remaining[pegs] > 0)
+                .map(|pegs| {
+                    let n = simulation.remaining[pegs];
+                    vec![
+                        pegs.into(),
+                        n.into(),
+                        (n as f64 / simulation.games as f64).into(),
+                    ]
+                }),
+        )
+}
+
+/// exports `feasible`, exiting on errors
+fn write(feasible: &[Board], output: &Path, encoding: Encoding) -> u64 {
+    export::export(feasible, output, encoding).unwrap_or_else(|e| {
+        eprintln!("could not write {output:?}: {e}");
+        std::process::exit(1)
+    })
+}
+
+fn moves(solution: &Solution) -> Value {
+    Value::List(
+        solution
+            .clone()
+            .into_iter()
+            .map(|m| m.to_string().into())
+            .collect(),
+    )
+}
+
+fn main() {
+    let args = Args::parse();
+    // the game comes with its own logger
+    if args.command.is_some() {
+        progress::init_logger(args.quiet);
+    }
+    let format = args.format;
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut success = true;
+    let report = match args.command {
         Some(command) => match command {
-            Command::CalculateAll => {
-                let vec = solitaire_solver::calculate_feasible_set(args.threads);
-                println!("solutions: {}", vec.len());
+            Command::CalculateAll { shard, output, dir } => {
+                let mut bars = progress::bars(args.quiet);
+                let mut memory = Memory::new();
+                let progress = |progress| {
+                    memory.track(progress);
+                    bars(progress);
+                };
+                let report = match shard {
+                    None => {
+                        let vec = solitaire_solver::calculate_feasible_set_with_progress(
+                            args.threads,
+                            false,
+                            progress,
+                        );
+                        Report::new().field("solutions", vec.len())
+                    }
+                    Some((i, n)) => {
+                        let fail = |e: io::Error| -> ! {
+                            eprintln!("could not exchange constellations through {dir:?}: {e}");
+                            std::process::exit(1)
+                        };
+                        let mut exchange =
+                            Exchange::new(dir.clone(), i - 1).unwrap_or_else(|e| fail(e));
+                        let vec = solitaire_solver::calculate_feasible_set_shard(
+                            args.threads,
+                            i - 1,
+                            n,
+                            |buckets| exchange.exchange(buckets).unwrap_or_else(|e| fail(e)),
+                            progress,
+                        );
+                        let output =
+                            output.unwrap_or_else(|| format!("shard-{i}-of-{n}.bin").into());
+                        write(&vec, &output, Encoding::Raw);
+                        Report::new()
+                            .field("constellations", vec.len())
+                            .field("output", output.display().to_string())
+                    }
+                };
+                report.table("peak memory", &["phase", "MB"], memory.rows())
             }
             Command::CalculateAllNaive => {
-                solitaire_solver::calculate_all_solutions_naive();
+                let vec = solitaire_solver::calculate_all_solutions_naive();
+                Report::new().field("solutions", vec.len())
             }
             Command::CalculateRandomChanceSuccessRatio => {
-                let feasible = solitaire_solver::calculate_feasible_set(None);
+                let mut bars = progress::bars(args.quiet);
+                let feasible = solitaire_solver::calculate_feasible_set_with_progress(
+                    args.threads,
+                    false,
+                    &mut bars,
+                );
                 let start = std::time::Instant::now();
                 let feasible = feasible.into_iter().collect();
                 let success_probabilities =
-                    solitaire_solver::calculate_p_random_chance_success(feasible);
+                    solitaire_solver::calculate_p_random_chance_success_with_progress(
+                        feasible, bars,
+                    );
                 let p = *success_probabilities.get(&Board::default()).unwrap();
-                let percentage = p * 100.;
 
-                println!("took {:?}", start.elapsed());
-                println!("success probability when chosing moves at random: {percentage}%");
+                Report::new()
+                    .field("took", format!("{:?}", start.elapsed()))
+                    .field("success probability", p)
             }
             Command::CalculateSingle => {
                 let solution = solitaire_solver::calculate_first_solution();
-                if args.print {
-                    solitaire_solver::print_solution(solution);
+                if args.print && format == Format::Text {
+                    solitaire_solver::print_solution(solution.clone());
                 }
+                Report::new().field("solution", moves(&solution))
             }
             Command::CompareSolutions => {
-                let solutions: HashSet<Board> = solitaire_solver::calculate_feasible_set(None)
-                    .into_iter()
-                    .collect();
+                let solutions: HashSet<Board> =
+                    solitaire_solver::calculate_feasible_set(args.threads)
+                        .into_iter()
+                        .collect();
                 let solutions_naive: HashSet<Board> =
                     solitaire_solver::calculate_all_solutions_naive()
                         .into_iter()
                         .collect();
-                assert_eq!(solutions, solutions_naive)
+                assert_eq!(solutions, solutions_naive);
+                Report::new().field("solutions", solutions.len())
             }
             Command::UniqueSolutions => {
-                let feasible = solitaire_solver::calculate_feasible_set(None);
+                let feasible = solitaire_solver::calculate_feasible_set(args.threads);
                 log::info!("feasible: {}", feasible.len());
-                let solutions =
-                    solitaire_solver::all_unique_solutions(Board::default(), feasible.into_iter());
-                log::info!("unique solutions: {}", solutions.len());
+                let solutions = solitaire_solver::all_unique_solutions_with_rng(
+                    Board::default(),
+                    feasible.into_iter(),
+                    &mut StdRng::seed_from_u64(seed),
+                );
+                Report::new()
+                    .field("seed", seed)
+                    .field("unique solutions", solutions.len())
             }
             Command::UniquePaths => {
-                let feasible = solitaire_solver::calculate_feasible_set(None);
+                let feasible = solitaire_solver::calculate_feasible_set(args.threads);
                 log::info!("feasible: {}", feasible.len());
                 let paths = solitaire_solver::all_unique_paths(feasible);
-                log::info!("unique paths: {}", paths.get(&Board::default()).unwrap());
+                Report::new().field("unique paths", *paths.get(&Board::default()).unwrap())
             }
+            Command::Solve {
+                position,
+                all,
+                target,
+            } => {
+                if all {
+                    let count = solitaire_solver::count_solutions(position, target);
+                    Report::new().field("solutions", count)
+                } else {
+                    let solution = solitaire_solver::calculate_first_solution_to(position, target);
+                    if let Some(solution) = &solution
+                        && args.print
+                        && format == Format::Text
+                    {
+                        solitaire_solver::print_solution_from(position, solution.clone());
+                    }
+                    success = solution.is_some();
+                    Report::new()
+                        .field("solvable", success)
+                        .field("solution", solution.as_ref().map(moves))
+                }
+            }
+            Command::Verify { start, moves } => {
+                let solution = Solution::from(moves.as_slice());
+                match solution.validate(start) {
+                    Ok(board) => Report::new()
+                        .field("valid", true)
+                        .field("pegs", board.count_pegs())
+                        .field("solved", board.is_solved()),
+                    Err(e) => {
+                        success = false;
+                        Report::new()
+                            .field("valid", false)
+                            .field("failing move", e.index + 1)
+                            .field("move", e.mov.to_string())
+                            .field("reason", e.reason.to_string())
+                    }
+                }
+            }
+            Command::Perft { depth, start } => {
+                let t = std::time::Instant::now();
+                let nodes = solitaire_solver::perft(start, depth);
+                let took = t.elapsed();
+                Report::new()
+                    .field("depth", depth)
+                    .field("nodes", nodes)
+                    .field("took", format!("{took:?}"))
+                    .field(
+                        "nodes per second",
+                        (nodes as f64 / took.as_secs_f64()) as u64,
+                    )
+            }
+            Command::Simulate {
+                games,
+                policy,
+                start,
+            } => simulate(start, games, policy, seed, args.threads),
+            Command::Puzzle {
+                pegs,
+                difficulty,
+                count,
+            } => {
+                let feasible = solitaire_solver::calculate_feasible_set(args.threads);
+                let chances = solitaire_solver::calculate_p_random_chance_success(feasible);
+                let puzzles = solitaire_solver::generate_puzzles(
+                    pegs as usize,
+                    difficulty.into(),
+                    count,
+                    &chances,
+                    &mut StdRng::seed_from_u64(seed),
+                );
+                if args.print && format == Format::Text {
+                    for puzzle in &puzzles {
+                        println!("{puzzle}");
+                    }
+                }
+                Report::new().field("seed", seed).table(
+                    "puzzles",
+                    &["position", "random win chance"],
+                    puzzles.iter().map(|p| {
+                        let chance = chances.get(&p.normalize()).copied().unwrap_or(0.);
+                        vec![p.to_notation().into(), Value::Chance(chance)]
+                    }),
+                )
+            }
+            Command::Export { output, encoding } => {
+                let feasible = solitaire_solver::calculate_feasible_set(args.threads);
+                let bytes = write(&feasible, &output, encoding);
+                Report::new()
+                    .field("constellations", feasible.len())
+                    .field("bytes", bytes)
+                    .field("output", output.display().to_string())
+            }
+            Command::Merge {
+                shards,
+                output,
+                encoding,
+            } => {
+                let mut feasible = vec![];
+                for shard in &shards {
+                    feasible.extend(export::import_raw(shard).unwrap_or_else(|e| {
+                        eprintln!("could not read {shard:?}: {e}");
+                        std::process::exit(1)
+                    }));
+                }
+                feasible.sort_unstable();
+                feasible.dedup();
+                let bytes = write(&feasible, &output, encoding);
+                Report::new()
+                    .field("shards", shards.len())
+                    .field("constellations", feasible.len())
+                    .field("bytes", bytes)
+                    .field("output", output.display().to_string())
+            }
+            Command::Analyze {
+                position,
+                reachable,
+            } => analyze(position, reachable, args.threads),
         },
         None => {
             #[cfg(feature = "game")]
-            peg_solitaire::run();
+            {
+                peg_solitaire::run();
+                return;
+            }
 
             #[cfg(not(feature = "game"))]
             {
@@ -104,5 +610,9 @@ fn main() {
                 std::process::exit(1)
             }
         }
+    };
+    report.print(format);
+    if !success {
+        std::process::exit(1)
     }
 }
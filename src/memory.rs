@@ -0,0 +1,56 @@
+// peak memory usage of the solver phases
+//
+// a memory limit needs a solver that can spill layers to disk, until then
+// the peak usage is only reported
+
+use std::fs;
+
+use solitaire_solver::Progress;
+
+use crate::report::Value;
+
+/// peak resident set size of the process in bytes (linux only)
+fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+/// starts measuring the peak resident set size from the current one
+fn reset_peak_rss() {
+    // best effort, the peak just keeps growing if this fails
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// records the peak memory usage of every stage reported by the solver
+pub struct Memory {
+    phases: Vec<(&'static str, u64)>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        if peak_rss().is_none() {
+            log::warn!("peak memory can only be measured on linux");
+        }
+        reset_peak_rss();
+        Self { phases: vec![] }
+    }
+
+    pub fn track(&mut self, progress: Progress) {
+        let Some(peak) = peak_rss() else {
+            return;
+        };
+        if progress.done == progress.total {
+            self.phases.push((progress.stage, peak));
+            reset_peak_rss();
+        }
+    }
+
+    /// rows of phase and peak memory in MB
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Value>> {
+        self.phases
+            .iter()
+            .map(|&(stage, peak)| vec![stage.into(), (peak / 1024 / 1024).into()])
+    }
+}
@@ -0,0 +1,66 @@
+// progress bars for the long running subcommands (see `--quiet`)
+
+use std::{
+    io::{self, Write},
+    sync::LazyLock,
+};
+
+use env_logger::{Env, Target};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use log::LevelFilter;
+use solitaire_solver::Progress;
+
+static BARS: LazyLock<MultiProgress> = LazyLock::new(MultiProgress::new);
+
+/// logs to stderr without tearing the progress bars
+struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        BARS.suspend(|| io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// logs at `RUST_LOG` (default "info"), only errors when `quiet`
+pub fn init_logger(quiet: bool) {
+    let mut builder = if quiet {
+        let mut builder = env_logger::Builder::new();
+        builder.filter_level(LevelFilter::Error);
+        builder
+    } else {
+        env_logger::Builder::from_env(Env::default().filter_or("RUST_LOG", "info"))
+    };
+    builder.target(Target::Pipe(Box::new(LogWriter))).init();
+}
+
+/// a progress bar for every stage reported by the solver (none when `quiet`)
+pub fn bars(quiet: bool) -> impl FnMut(Progress) {
+    let mut current: Option<(&'static str, ProgressBar)> = None;
+    move |progress| {
+        if quiet {
+            return;
+        }
+        let bar = match &current {
+            Some((stage, bar)) if *stage == progress.stage => bar,
+            _ => {
+                let style = ProgressStyle::with_template(
+                    "{prefix:>9} [{bar:40}] {pos:>2}/{len:2} {elapsed_precise}",
+                )
+                .unwrap()
+                .progress_chars("=> ");
+                let bar = BARS.add(ProgressBar::new(progress.total as u64));
+                bar.set_style(style);
+                bar.set_prefix(progress.stage);
+                &current.insert((progress.stage, bar)).1
+            }
+        };
+        bar.set_position(progress.done as u64);
+        if progress.done == progress.total {
+            bar.finish();
+        }
+    }
+}
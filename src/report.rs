@@ -0,0 +1,233 @@
+// structured output of the subcommands (see `--format`)
+
+use std::{fmt::Write, io};
+
+use clap::ValueEnum;
+use serde::{Serialize, Serializer, ser::SerializeMap};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// human readable
+    #[default]
+    Text,
+    /// a single json object
+    Json,
+    /// a header and a row per table entry (if any), the other values as leading columns
+    Csv,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    /// printed as "none" / `null`
+    None,
+    Bool(bool),
+    Int(u128),
+    Float(f64),
+    /// a probability, printed as `1/n` in text form
+    Chance(f64),
+    Str(String),
+    List(Vec<Value>),
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Self::Int(n as u128)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Self::Int(n as u128)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        Self::Int(n)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        Self::Float(f)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            Some(v) => v.into(),
+            None => Self::None,
+        }
+    }
+}
+
+/// output of a subcommand: named values and an optional table
+#[derive(Default, Serialize)]
+pub struct Report {
+    #[serde(flatten, serialize_with = "serialize_fields")]
+    fields: Vec<(&'static str, Value)>,
+    #[serde(flatten, serialize_with = "serialize_table")]
+    table: Option<Table>,
+}
+
+struct Table {
+    name: &'static str,
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.fields.push((name, value.into()));
+        self
+    }
+
+    pub fn table(
+        mut self,
+        name: &'static str,
+        columns: &[&'static str],
+        rows: impl IntoIterator<Item = Vec<Value>>,
+    ) -> Self {
+        self.table = Some(Table {
+            name,
+            columns: columns.to_vec(),
+            rows: rows.into_iter().collect(),
+        });
+        self
+    }
+
+    pub fn print(&self, format: Format) {
+        match format {
+            Format::Text => print!("{}", self.text()),
+            Format::Json => println!("{}", serde_json::to_string(self).unwrap()),
+            Format::Csv => self.csv(io::stdout()).unwrap(),
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.fields {
+            writeln!(out, "{name}: {}", value.text()).unwrap();
+        }
+        if let Some(table) = &self.table {
+            writeln!(out, "{}:", table.name).unwrap();
+            for row in &table.rows {
+                let row = row.iter().map(Value::text).collect::<Vec<_>>();
+                writeln!(out, "  {}", row.join("  ")).unwrap();
+            }
+        }
+        out
+    }
+
+    fn csv(&self, w: impl io::Write) -> csv::Result<()> {
+        // the fields are repeated as leading columns of every row of the table
+        let (mut columns, fields): (Vec<_>, Vec<_>) = self.fields.iter().cloned().unzip();
+        let rows: Vec<Vec<Value>> = match &self.table {
+            Some(table) if !table.rows.is_empty() => {
+                columns.extend(&table.columns);
+                table
+                    .rows
+                    .iter()
+                    .map(|row| fields.iter().chain(row).cloned().collect())
+                    .collect()
+            }
+            Some(table) => {
+                columns.extend(&table.columns);
+                let empty = table.columns.iter().map(|_| Value::Str(String::new()));
+                vec![fields.into_iter().chain(empty).collect()]
+            }
+            None => vec![fields],
+        };
+        let mut w = csv::Writer::from_writer(w);
+        w.write_record(&columns)?;
+        for row in rows {
+            w.write_record(row.iter().map(Value::csv))?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// "feasible moves" => "feasible_moves"
+fn key(name: &str) -> String {
+    name.replace(' ', "_")
+}
+
+fn serialize_fields<S: Serializer>(
+    fields: &[(&'static str, Value)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(fields.len()))?;
+    for (name, value) in fields {
+        map.serialize_entry(&key(name), value)?;
+    }
+    map.end()
+}
+
+/// the rows as objects of their columns
+fn serialize_table<S: Serializer>(table: &Option<Table>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    if let Some(table) = table {
+        let rows = table.rows.iter().map(|row| Row(&table.columns, row));
+        map.serialize_entry(&key(table.name), &rows.collect::<Vec<_>>())?;
+    }
+    map.end()
+}
+
+struct Row<'a>(&'a [&'static str], &'a [Value]);
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (column, value) in self.0.iter().zip(self.1) {
+            map.serialize_entry(&key(column), value)?;
+        }
+        map.end()
+    }
+}
+
+impl Value {
+    fn text(&self) -> String {
+        match self {
+            Value::Chance(p) if *p > 0. => format!("1/{:.0}", 1. / p),
+            Value::Chance(_) => "0".into(),
+            Value::List(l) => l.iter().map(Value::text).collect::<Vec<_>>().join(" "),
+            v => v.csv(),
+        }
+    }
+
+    fn csv(&self) -> String {
+        match self {
+            Value::None => "none".into(),
+            Value::Bool(b) => b.to_string(),
+            Value::Int(n) => n.to_string(),
+            Value::Float(f) | Value::Chance(f) => f.to_string(),
+            Value::Str(s) => s.clone(),
+            Value::List(l) => l.iter().map(Value::csv).collect::<Vec<_>>().join(" "),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// exchanging constellations between the shards of `calculate-all --shard`
+//
+// after every level, each shard writes the constellations belonging to
+// another shard to `<dir>/<round>-<from>-<to>.bin` and waits for the files
+// of all other shards addressed to it. a file only shows up under its name
+// once it is complete, so the shards can run on different machines sharing
+// the directory.
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+use solitaire_solver::Board;
+
+use crate::export::{self, Encoding};
+
+/// how often to look for the files of the other shards
+const POLL: Duration = Duration::from_millis(50);
+
+pub struct Exchange {
+    dir: PathBuf,
+    shard: usize,
+    round: usize,
+}
+
+impl Exchange {
+    pub fn new(dir: PathBuf, shard: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            shard,
+            round: 0,
+        })
+    }
+
+    fn path(&self, from: usize, to: usize) -> PathBuf {
+        self.dir.join(format!("{}-{from}-{to}.bin", self.round))
+    }
+
+    /// hands `buckets[i]` to shard `i` and returns
+    /// what every shard found for this one
+    pub fn exchange(&mut self, buckets: Vec<Vec<Board>>) -> io::Result<Vec<Vec<Board>>> {
+        let shards = buckets.len();
+        let mut parts = vec![];
+        for (to, bucket) in buckets.into_iter().enumerate() {
+            if to == self.shard {
+                parts.push(bucket);
+                continue;
+            }
+            let path = self.path(self.shard, to);
+            let tmp = path.with_extension("tmp");
+            export::export(&bucket, &tmp, Encoding::Raw)?;
+            fs::rename(&tmp, &path)?;
+        }
+        for from in (0..shards).filter(|&from| from != self.shard) {
+            let path = self.path(from, self.shard);
+            let start = Instant::now();
+            let mut waiting = false;
+            while !path.exists() {
+                if !waiting && start.elapsed() > Duration::from_secs(10) {
+                    log::info!("waiting for shard {}", from + 1);
+                    waiting = true;
+                }
+                thread::sleep(POLL);
+            }
+            parts.push(export::import_raw(&path)?);
+            // every file is read by one shard only
+            fs::remove_file(&path)?;
+        }
+        self.round += 1;
+        Ok(parts)
+    }
+}